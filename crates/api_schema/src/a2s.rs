@@ -4,6 +4,20 @@ use serde::{Deserialize, Serialize};
 pub enum PlayerOrInfo {
     Players(Vec<Player>),
     Info(Box<Info>),
+    State(A2sState),
+}
+
+/// Whether the server answers A2S queries, info and players are only known while it is `Reachable`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum A2sState {
+    /// The server isn't running, so it isn't queried.
+    #[default]
+    Stopped,
+    /// The server is running but hasn't answered yet, it takes a while to load.
+    Waiting,
+    Reachable,
+    /// The server stopped answering or never did.
+    Unreachable,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -79,7 +93,7 @@ impl std::fmt::Display for ServerType {
     }
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[repr(u8)]
 pub enum ServerOS {
     Linux = b'l',
@@ -181,3 +195,50 @@ pub struct TheShipPlayer {
 
     pub money: u32,
 }
+
+/// What Arma reports through A2S_RULES, decoded from its binary format.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct ArmaRules {
+    /// Version of the binary format, 3 for current servers.
+    pub protocol: u8,
+    pub difficulty: ArmaDifficulty,
+    pub crosshair: bool,
+    /// The official DLC the server has loaded.
+    pub dlcs: Vec<ArmaDlc>,
+    /// Mods and creator DLC in the order they were loaded.
+    pub mods: Vec<ArmaMod>,
+    /// The server loaded more mods than fit into the response.
+    pub mods_overflow: bool,
+    /// Names of the keys the server accepts.
+    pub signatures: Vec<String>,
+    pub signatures_overflow: bool,
+    /// Taken from the keywords of A2S_INFO, `None` if the server didn't report it.
+    pub battleye: Option<bool>,
+    /// Taken from the keywords of A2S_INFO, the platform clients have to be on to join.
+    pub platform: Option<ServerOS>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct ArmaDifficulty {
+    /// Recruit, Regular, Veteran or Custom.
+    pub level: String,
+    /// Novice, Normal, Expert or Custom.
+    pub ai_level: String,
+    pub advanced_flight_model: bool,
+    pub third_person: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ArmaDlc {
+    pub name: String,
+    pub hash: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ArmaMod {
+    pub name: String,
+    /// The workshop id of a mod or the app id of a creator DLC, 0 for mods not from the workshop.
+    pub steam_id: u64,
+    pub hash: u32,
+    pub dlc: bool,
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::response::{AnnouncementSchedule, BanKind, MessageKind, RotationMission, WhitelistKind};
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RegisterUserSchema {
     pub name: String,
@@ -45,6 +47,7 @@ pub struct UpdatePresetItemSchema {
     pub enabled: Option<bool>,
     pub position: Option<i64>,
     pub server_mod: Option<bool>,
+    pub optional: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -85,3 +88,164 @@ pub struct DeletePresetSchema {
 pub struct UpdateConfigSchema {
     pub config: String,
 }
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateInstanceSchema {
+    pub name: String,
+    pub port: i64,
+    pub preset_id: Option<i64>,
+    pub profile_name: Option<String>,
+    pub parameters: Vec<String>,
+    pub headless_clients: i64,
+    pub rcon_port: Option<i64>,
+    pub rcon_ip: Option<String>,
+    pub max_ping: Option<i64>,
+    pub query_port: Option<i64>,
+    pub query_ip: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateInstanceSchema {
+    pub id: i64,
+    pub name: String,
+    pub port: i64,
+    pub preset_id: Option<i64>,
+    pub profile_name: String,
+    pub parameters: Vec<String>,
+    pub headless_clients: i64,
+    pub rcon_port: Option<i64>,
+    pub rcon_ip: Option<String>,
+    pub max_ping: Option<i64>,
+    pub query_port: Option<i64>,
+    pub query_ip: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteInstanceSchema {
+    pub id: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateMissionRotationSchema {
+    pub missions: Vec<RotationMission>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RenameMissionSchema {
+    /// The new file name without `.pbo`, still `<mission>.<world>`.
+    pub name: String,
+}
+
+/// Players are addressed by slot, the GUID makes sure it is still the same player in that slot.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct KickPlayerSchema {
+    pub slot: i64,
+    pub guid: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BanPlayerSchema {
+    pub slot: i64,
+    pub guid: String,
+    /// 0 bans permanently.
+    pub minutes: i64,
+    pub reason: String,
+}
+
+/// Without a slot the message goes to everyone.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SendMessageSchema {
+    pub slot: Option<i64>,
+    pub guid: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LockServerSchema {
+    pub locked: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateBanSchema {
+    pub kind: BanKind,
+    pub value: String,
+    /// 0 bans permanently.
+    pub minutes: i64,
+    pub reason: String,
+}
+
+/// The files bans are kept in, the engine one only holds Steam UIDs.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub enum BanListFormat {
+    Engine,
+    BattlEye,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ImportBansSchema {
+    pub format: BanListFormat,
+    pub content: String,
+}
+
+/// Messages come newest first, `before` is the id of the oldest message of the previous page.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct MessageQuery {
+    pub search: Option<String>,
+    pub kind: Option<MessageKind>,
+    pub before: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Used to create and to update an announcement.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AnnouncementSchema {
+    pub message: String,
+    pub schedule: AnnouncementSchedule,
+    pub enabled: bool,
+}
+
+/// Replaces the times of day the instance restarts at.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateRestartScheduleSchema {
+    /// Minutes after midnight UTC.
+    pub minutes: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateWhitelistEntrySchema {
+    pub kind: WhitelistKind,
+    pub value: String,
+    pub name: String,
+}
+
+/// A CSV with a Steam UID or GUID and optionally a name on each line, in either order.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ImportWhitelistSchema {
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateWhitelistSettingsSchema {
+    pub enabled: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PresetWhitelistSchema {
+    pub enabled: bool,
+}
+
+/// Statistics cover the last `days` days, 30 if not set.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct StatsQuery {
+    pub days: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Newest first, `player` is a GUID or, for players seen without RCon, a name.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SessionQuery {
+    pub player: Option<String>,
+    pub limit: Option<i64>,
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -48,7 +50,56 @@ pub enum State {
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Status {
     pub steamcmd: State,
-    pub arma: State,
+    /// The server state per instance id, instances that were never started are missing.
+    pub instances: HashMap<i64, State>,
+    /// The headless client states per instance id, in the order they were started.
+    pub headless_clients: HashMap<i64, Vec<State>>,
+}
+
+impl Status {
+    pub fn arma(&self, instance: i64) -> State {
+        self.instances.get(&instance).cloned().unwrap_or_default()
+    }
+
+    /// The state of the n-th headless client, starting at 1 like their names.
+    pub fn headless_client(&self, instance: i64, index: usize) -> State {
+        self.headless_clients
+            .get(&instance)
+            .and_then(|states| states.get(index.checked_sub(1)?))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Instance {
+    pub id: i64,
+    pub name: String,
+    pub port: i64,
+    /// The preset to launch with, `None` follows the selected preset.
+    pub preset_id: Option<i64>,
+    /// Relative to the config directory.
+    pub config_file: String,
+    /// Relative to the config directory.
+    pub profile_file: String,
+    pub profile_name: String,
+    /// Launch options, the defaults are used when empty.
+    pub parameters: Vec<String>,
+    /// Started after and stopped before the server.
+    pub headless_clients: i64,
+    /// BattlEye RCon listens on the game port when not set.
+    pub rcon_port: Option<i64>,
+    /// BattlEye RCon listens on every interface when not set.
+    pub rcon_ip: Option<String>,
+    /// Players with a higher ping are kicked by BattlEye.
+    pub max_ping: Option<i64>,
+    /// The Steam query port A2S is polled on, the game port + 1 when not set.
+    pub query_port: Option<i64>,
+    /// The address A2S is polled on, the first network interface when not set.
+    pub query_ip: Option<String>,
+    /// Generated by the manager and written to the BattlEye config, it never leaves the server.
+    #[serde(skip)]
+    pub rcon_password: String,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
@@ -66,6 +117,8 @@ pub struct Preset {
     pub id: i64,
     pub name: String,
     pub selected: bool,
+    /// Enforces the whitelist on every instance running with this preset.
+    pub whitelist: bool,
     pub items: Vec<PresetItem>,
     pub dlcs: Vec<DlcItem>,
 }
@@ -80,10 +133,129 @@ pub struct PresetItem {
     pub enabled: bool,
     pub blacklisted: bool,
     pub server_mod: bool,
+    /// Clients may load it, the server only installs its keys.
+    pub optional: bool,
     #[cfg_attr(feature = "ssr", sqlx(skip))]
     pub exists: bool,
 }
 
+/// A load order for the enabled mods of a preset that respects the `requiredAddons` of their `CfgPatches`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct LoadOrderReport {
+    /// The enabled mods in the proposed order.
+    pub order: Vec<LoadOrderItem>,
+    /// Whether the proposed order differs from the current one.
+    pub changed: bool,
+    /// Mods that depend on each other in a circle, their order can't be fixed automatically.
+    pub cycles: Vec<String>,
+    pub missing: Vec<MissingDependency>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LoadOrderItem {
+    /// The id of the preset item.
+    pub id: i64,
+    pub published_file_id: i64,
+    pub name: String,
+    pub position: i64,
+    pub previous_position: i64,
+}
+
+/// An addon a mod requires that neither the game nor another enabled mod provides.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MissingDependency {
+    pub published_file_id: i64,
+    pub name: String,
+    pub addon: String,
+}
+
+/// Everything that would get players kicked when the server verifies signatures.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct KeyReport {
+    /// Only mods with at least one problem are listed.
+    pub mods: Vec<ModKeyCheck>,
+    pub duplicates: Vec<DuplicateKey>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ModKeyCheck {
+    pub published_file_id: i64,
+    pub name: String,
+    /// The `.bikey` files in the `keys` folder of the mod, empty if it brings none.
+    pub keys: Vec<String>,
+    /// PBOs without a `.bisign` next to them.
+    pub unsigned: Vec<String>,
+    pub unknown_signatures: Vec<UnknownSignature>,
+}
+
+/// A PBO signed with a key that neither the preset nor the server provides.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct UnknownSignature {
+    pub pbo: String,
+    pub authority: String,
+}
+
+/// A key file name used by more than one mod, only the first one gets installed.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DuplicateKey {
+    pub key: String,
+    pub mods: Vec<String>,
+    /// Whether every copy of the key has the same content.
+    pub identical: bool,
+}
+
+/// Addons that more than one enabled mod of a preset brings, only one of them ends up loaded.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ConflictReport {
+    pub conflicts: Vec<AddonConflict>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AddonConflict {
+    pub kind: ConflictKind,
+    /// The PBO file name, `CfgPatches` class or prefix the mods share.
+    pub name: String,
+    pub mods: Vec<ConflictingMod>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConflictKind {
+    Pbo,
+    CfgPatches,
+    Prefix,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ConflictingMod {
+    pub published_file_id: i64,
+    pub name: String,
+}
+
+/// Where a key in the `keys` folder of the server came from.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum KeyOrigin {
+    /// The key of the base game.
+    Game,
+    Mod,
+    Dlc,
+    /// Uploaded through the manager, kept until deleted.
+    Manual,
+    /// Put into the folder by hand, the manager never touches it.
+    Unmanaged,
+}
+
+/// A key in the `keys` folder of the server.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ServerKey {
+    pub name: String,
+    pub authority: Option<String>,
+    pub origin: KeyOrigin,
+    /// The mod the key was copied from.
+    pub published_file_id: Option<i64>,
+    /// The DLC the key was copied from.
+    pub dlc: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum PresetUpdate {
     Created(Preset),
@@ -98,7 +270,68 @@ pub enum PresetUpdate {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MissionResponse {
-    pub missions: Vec<String>,
+    pub missions: Vec<Mission>,
+}
+
+/// A mission in `mpmissions` with the metadata read from its PBO.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Mission {
+    pub id: i64,
+    /// The file name without `.pbo`, this is what the rotation refers to.
+    pub name: String,
+    pub world: String,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub min_players: Option<i64>,
+    pub max_players: Option<i64>,
+    pub game_type: Option<String>,
+    pub addons: Vec<String>,
+    /// The size of the PBO in bytes.
+    pub size: i64,
+    /// Goes up every time the mission is replaced, previous versions are archived.
+    pub version: i64,
+}
+
+/// Which missions can be played with a preset, based on the `CfgPatches` its mods provide.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct MissionAddonReport {
+    pub missions: Vec<MissionAddonCheck>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MissionAddonCheck {
+    pub mission: String,
+    pub playable: bool,
+    pub missing: Vec<MissingAddon>,
+}
+
+/// An addon a mission needs that nothing in the preset provides.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MissingAddon {
+    pub addon: String,
+    /// Downloaded workshop items that provide the addon, empty if none do.
+    pub providers: Vec<AddonProvider>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AddonProvider {
+    pub published_file_id: i64,
+    pub name: String,
+}
+
+/// A mission in the rotation, written to `class Missions` of the config in this order.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RotationMission {
+    /// The mission file name in `mpmissions`, without `.pbo`.
+    pub template: String,
+    pub difficulty: String,
+    pub params: Vec<MissionParam>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MissionParam {
+    pub name: String,
+    pub value: i64,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -111,3 +344,228 @@ pub struct DlcItem {
     pub enabled: bool,
     pub position: i64,
 }
+
+/// The mods the running server reported compared with its preset.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LoadedPresetReport {
+    pub preset_id: i64,
+    pub preset_name: String,
+    /// Mods and DLC of the preset the server didn't load.
+    pub missing: Vec<String>,
+    /// Mods the server loaded that aren't in the preset.
+    pub unexpected: Vec<String>,
+    /// The server loaded more mods than it could report, mods may be missing only from the report.
+    pub incomplete: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LaunchPreview {
+    pub arguments: Vec<String>,
+    pub config: Vec<String>,
+    pub keys: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PreflightCheck {
+    pub name: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PreflightReport {
+    pub checks: Vec<PreflightCheck>,
+}
+
+impl PreflightReport {
+    pub fn push(&mut self, name: impl Into<String>, severity: Severity, message: impl Into<String>) {
+        self.checks.push(PreflightCheck {
+            name: name.into(),
+            severity,
+            message: message.into(),
+        });
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &PreflightCheck> {
+        self.checks.iter().filter(|check| check.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &PreflightCheck> {
+        self.checks.iter().filter(|check| check.severity == Severity::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+}
+
+/// A player as RCon sees them, unlike A2S this includes the slot, address and GUID.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RconPlayer {
+    pub slot: i64,
+    pub ip: String,
+    pub port: u16,
+    pub ping: i64,
+    /// The BattlEye GUID, empty until BattlEye computed it.
+    pub guid: String,
+    pub verified: bool,
+    pub name: String,
+    pub lobby: bool,
+}
+
+/// An action taken on a server through the manager, like kicking a player.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub instance_id: i64,
+    pub user: String,
+    pub action: String,
+    pub player: Option<String>,
+    pub guid: Option<String>,
+    pub details: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub enum BanKind {
+    /// Steam UID, banned by the engine through `ban.txt`.
+    Uid,
+    /// BattlEye GUID, banned through BattlEye's `bans.txt`.
+    Guid,
+    /// IPv4 address, banned through BattlEye's `bans.txt`.
+    Ip,
+}
+
+/// A ban that applies to every instance.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Ban {
+    pub id: i64,
+    pub kind: BanKind,
+    pub value: String,
+    /// Unix timestamp, `None` for permanent bans.
+    pub expires_at: Option<i64>,
+    pub reason: String,
+    pub author: String,
+    pub created_at: i64,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub enum MessageKind {
+    Chat,
+    Connect,
+    Disconnect,
+    Kick,
+    /// Kicks by the BattlEye filters.
+    Filter,
+    Admin,
+    Other,
+}
+
+/// A message the server pushed over RCon, like chat or a player connecting.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ServerMessage {
+    pub id: i64,
+    pub instance_id: i64,
+    pub kind: MessageKind,
+    pub channel: Option<String>,
+    pub slot: Option<i64>,
+    pub player: Option<String>,
+    pub guid: Option<String>,
+    pub message: String,
+    pub created_at: i64,
+}
+
+/// When an announcement goes out, never while the server is stopped.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub enum AnnouncementSchedule {
+    /// Every that many minutes, counted from when the server started.
+    Interval { minutes: i64 },
+    /// Once at the unix timestamp, skipped if the server isn't running then.
+    At { timestamp: i64 },
+    /// That many minutes before each scheduled restart of the instance.
+    BeforeRestart { minutes: i64 },
+}
+
+/// A message sent to everyone on the server over RCon.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Announcement {
+    pub id: i64,
+    pub instance_id: i64,
+    pub message: String,
+    pub schedule: AnnouncementSchedule,
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub enum WhitelistKind {
+    /// Steam UID, enforced through the BattlEye GUID it maps to.
+    Uid,
+    /// BattlEye GUID.
+    Guid,
+}
+
+/// A player that may join the instances that enforce the whitelist.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct WhitelistEntry {
+    pub id: i64,
+    pub kind: WhitelistKind,
+    pub value: String,
+    /// A note to tell the entries apart, like the player name.
+    pub name: String,
+    pub author: String,
+    pub created_at: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct WhitelistSettings {
+    pub enabled: bool,
+    /// The kick message for players that aren't on the whitelist.
+    pub message: String,
+    /// Whether the preset the instance runs with enforces the whitelist, which it then does regardless.
+    pub preset_enabled: bool,
+}
+
+/// A player on one mission of an instance, times are unix timestamps.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PlayerSession {
+    pub id: i64,
+    pub instance_id: i64,
+    pub name: String,
+    /// Only known while RCon is reachable.
+    pub guid: Option<String>,
+    pub mission: Option<String>,
+    pub joined_at: i64,
+    /// When the player was last seen, for a player that is still on the server this keeps moving.
+    pub left_at: i64,
+    /// Seconds.
+    pub duration: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DailyPlayerStats {
+    /// `YYYY-MM-DD` in the time zone of the server.
+    pub day: String,
+    pub unique_players: i64,
+    /// The most players on the server at the same time.
+    pub peak_players: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PlayerPlaytime {
+    /// The name the player was last seen with.
+    pub name: String,
+    pub guid: Option<String>,
+    /// Seconds.
+    pub playtime: i64,
+    pub sessions: i64,
+    pub last_seen: i64,
+}
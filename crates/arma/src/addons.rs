@@ -0,0 +1,213 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use pbo::PboReader;
+
+use crate::{config, ARMA_CLIENT_APP_ID};
+
+/// A class in `CfgPatches`, the name is what missions and other addons refer to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CfgPatch {
+    pub name: String,
+    /// The addons that have to be loaded before this one.
+    pub required_addons: Vec<String>,
+}
+
+/// A PBO in the `addons` folder of a mod and the prefix its files are mounted under.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PboInfo {
+    pub file_name: String,
+    pub prefix: Option<String>,
+}
+
+/// The `addons` folder of a mod or DLC, mods don't agree on the casing.
+pub fn get_addons_path(folder: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(folder)
+        .ok()?
+        .flatten()
+        .find(|entry| entry.file_name().to_string_lossy().eq_ignore_ascii_case("addons"))
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+}
+
+pub(crate) fn get_pbos(folder: &Path) -> Vec<PathBuf> {
+    let Some(addons_path) = get_addons_path(folder) else {
+        return vec![];
+    };
+
+    let Ok(entries) = std::fs::read_dir(addons_path) else {
+        return vec![];
+    };
+
+    let mut pbos = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("pbo"))
+        })
+        .collect::<Vec<_>>();
+
+    pbos.sort();
+    pbos
+}
+
+/// Changes whenever a PBO in the `addons` folder is added, removed or updated.
+pub fn addons_fingerprint(folder: &Path) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    for pbo in get_pbos(folder) {
+        pbo.hash(&mut hasher);
+
+        if let Ok(metadata) = std::fs::metadata(&pbo) {
+            metadata.len().hash(&mut hasher);
+
+            metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .hash(&mut hasher);
+        }
+    }
+
+    hasher.finish()
+}
+
+/// The `CfgPatches` classes of every PBO in the `addons` folder of a mod or DLC,
+/// these are the names missions list in `addons[]`.
+pub fn read_cfg_patches(folder: &Path) -> Vec<CfgPatch> {
+    let mut patches = Vec::new();
+
+    for pbo in get_pbos(folder) {
+        match read_pbo_cfg_patches(&pbo) {
+            Ok(found) => patches.extend(found),
+            Err(e) => tracing::warn!("Failed to read CfgPatches of {}: {}", pbo.display(), e),
+        }
+    }
+
+    patches.sort_by_key(|patch| patch.name.to_lowercase());
+    patches.dedup_by(|a, b| a.name.eq_ignore_ascii_case(&b.name));
+    patches
+}
+
+/// Reads every config in the PBO, a single PBO can hold several addons in subfolders.
+fn read_pbo_cfg_patches(path: &Path) -> Result<Vec<CfgPatch>, Box<dyn std::error::Error>> {
+    let mut reader = PboReader::open(path)?;
+
+    let configs = reader
+        .entries()
+        .iter()
+        .filter(|entry| {
+            let name = entry.name.rsplit('\\').next().unwrap_or_default();
+            name.eq_ignore_ascii_case("config.bin") || name.eq_ignore_ascii_case("config.cpp")
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut patches = Vec::new();
+
+    for entry in configs {
+        let data = reader.read_entry(&entry)?;
+
+        // text configs can rely on macros we can't resolve, a config.bin next to it usually exists anyway
+        let Ok(config) = config::parse_bytes(&data) else {
+            continue;
+        };
+
+        if let Some(cfg_patches) = config.class("CfgPatches") {
+            patches.extend(cfg_patches.classes().map(|(name, class)| {
+                CfgPatch {
+                    name: name.to_string(),
+                    required_addons: class
+                        .get("requiredAddons")
+                        .and_then(|required| required.as_array())
+                        .unwrap_or_default()
+                        .iter()
+                        .filter_map(|addon| addon.as_str())
+                        .map(|addon| addon.to_string())
+                        .collect(),
+                }
+            }));
+        }
+    }
+
+    Ok(patches)
+}
+
+/// Every PBO in the `addons` folder of a mod, only the headers are read.
+pub fn read_pbo_infos(folder: &Path) -> Vec<PboInfo> {
+    get_pbos(folder)
+        .into_iter()
+        .map(|pbo| PboInfo {
+            file_name: pbo.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            prefix: PboReader::open(&pbo)
+                .ok()
+                .and_then(|reader| reader.prefix().map(|prefix| prefix.to_string()))
+                .filter(|prefix| !prefix.trim().is_empty()),
+        })
+        .collect()
+}
+
+/// The name a mod gives itself in `meta.cpp` or `mod.cpp`.
+pub fn read_mod_name(folder: &Path) -> Option<String> {
+    ["meta.cpp", "mod.cpp"].iter().find_map(|file| {
+        let content = std::fs::read(folder.join(file)).ok()?;
+        let config = config::parse_bytes(&content).ok()?;
+
+        config
+            .get("name")
+            .and_then(|name| name.as_str())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+    })
+}
+
+/// The published file ids of every workshop item that is downloaded, whether a preset uses it or not.
+pub fn get_installed_mods() -> Vec<i64> {
+    let path = paths::get_steam_path()
+        .join("steamapps")
+        .join("workshop")
+        .join("content")
+        .join(ARMA_CLIENT_APP_ID.to_string());
+
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return vec![];
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_string_lossy().parse().ok())
+        .collect()
+}
+
+/// The folders of the server install that are always loaded: the base game and every DLC
+/// that isn't in `optional_dlcs`, those only load when enabled in the preset.
+pub fn get_game_addon_folders(optional_dlcs: &[String]) -> Vec<PathBuf> {
+    let Some(arma_path) = paths::get_arma_path() else {
+        return vec![];
+    };
+
+    let mut folders = vec![arma_path.clone()];
+
+    if let Ok(entries) = std::fs::read_dir(&arma_path) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if optional_dlcs.iter().any(|dlc| dlc.eq_ignore_ascii_case(&name)) {
+                continue;
+            }
+
+            if entry.path().is_dir() && get_addons_path(&entry.path()).is_some() {
+                folders.push(entry.path());
+            }
+        }
+    }
+
+    folders.sort();
+    folders
+}
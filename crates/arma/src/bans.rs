@@ -0,0 +1,113 @@
+use std::{
+    net::Ipv4Addr,
+    path::{Path, PathBuf},
+};
+
+/// The engine bans, one Steam UID per line. Only read when the server starts.
+pub fn get_engine_bans_path() -> Option<PathBuf> {
+    paths::get_arma_path().map(|arma_path| arma_path.join("ban.txt"))
+}
+
+/// The BattlEye bans of the server started with the given `-profiles` directory, next to its BattlEye config.
+pub fn get_battleye_bans_path(profiles: &Path) -> PathBuf {
+    profiles.join("battleye").join("bans.txt")
+}
+
+/// A line of BattlEye's `bans.txt`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BattlEyeBan {
+    /// Either a GUID or an IPv4 address.
+    pub target: String,
+    /// Unix timestamp, `None` for permanent bans.
+    pub expires_at: Option<i64>,
+    pub reason: String,
+}
+
+/// Steam UIDs are 17 digit numbers, anything else in the file is skipped.
+pub fn parse_engine_bans(content: &str) -> Vec<String> {
+    let mut uids = Vec::new();
+
+    for line in content.lines() {
+        let Some(uid) = line.split_whitespace().next() else {
+            continue;
+        };
+
+        if is_uid(uid) && !uids.iter().any(|other| other == uid) {
+            uids.push(uid.to_string());
+        }
+    }
+
+    uids
+}
+
+pub fn format_engine_bans(uids: &[String]) -> String {
+    uids.iter().map(|uid| format!("{}\n", uid)).collect()
+}
+
+/// Each line is `<GUID or IP> <expiry or -1> <reason>`, comments and lines that don't fit are skipped.
+pub fn parse_battleye_bans(content: &str) -> Vec<BattlEyeBan> {
+    let mut bans: Vec<BattlEyeBan> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line.starts_with("//") {
+            continue;
+        }
+
+        let mut parts = line.splitn(3, char::is_whitespace);
+
+        let (Some(target), Some(expires_at)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+
+        if !is_guid(target) && !is_ip(target) {
+            continue;
+        }
+
+        let Ok(expires_at) = expires_at.parse::<i64>() else {
+            continue;
+        };
+
+        let target = target.to_lowercase();
+
+        if bans.iter().any(|ban| ban.target == target) {
+            continue;
+        }
+
+        bans.push(BattlEyeBan {
+            target,
+            expires_at: (expires_at >= 0).then_some(expires_at),
+            reason: parts.next().unwrap_or_default().trim().to_string(),
+        });
+    }
+
+    bans
+}
+
+pub fn format_battleye_bans(bans: &[BattlEyeBan]) -> String {
+    bans.iter()
+        .map(|ban| {
+            let expires_at = ban.expires_at.unwrap_or(-1);
+
+            // a reason with a line break would end up as a line of its own
+            let reason = ban.reason.lines().collect::<Vec<_>>().join(" ");
+
+            format!("{} {} {}\n", ban.target, expires_at, reason.trim())
+        })
+        .collect()
+}
+
+pub fn is_uid(value: &str) -> bool {
+    value.len() == 17 && value.chars().all(|c| c.is_ascii_digit())
+}
+
+/// BattlEye GUIDs are the hex MD5 of the Steam UID.
+pub fn is_guid(value: &str) -> bool {
+    value.len() == 32 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// BattlEye only knows IPv4.
+pub fn is_ip(value: &str) -> bool {
+    value.parse::<Ipv4Addr>().is_ok()
+}
@@ -0,0 +1,145 @@
+use std::{fmt, net::IpAddr, path::Path};
+
+const BATTLEYE_CONFIG: &str = "beserver_x64.cfg";
+
+/// How to reach the RCon of a server, read from the BattlEye server config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RconSettings {
+    pub password: String,
+    /// BattlEye listens on the game port when no port is set.
+    pub port: Option<u16>,
+    pub ip: Option<IpAddr>,
+}
+
+/// Reads the RCon settings of the server started with the given `-profiles` directory.
+/// Only the instance's own BattlEye folder counts, that is where `prepare_battleye` writes the config
+/// and a shared one might belong to another server.
+/// While running BattlEye renames `beserver_x64.cfg` to `beserver_x64_active_<random>.cfg`, that one wins.
+pub fn read_rcon_settings(profiles: &Path) -> Option<RconSettings> {
+    let entries = std::fs::read_dir(profiles.join("battleye")).ok()?;
+
+    let mut configs = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_lowercase();
+
+            if name == BATTLEYE_CONFIG {
+                Some((1, path))
+            } else if name.starts_with("beserver_x64_active_") && name.ends_with(".cfg") {
+                Some((0, path))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    configs.sort();
+
+    configs
+        .into_iter()
+        .find_map(|(_, path)| parse_rcon_settings(&std::fs::read_to_string(path).ok()?))
+}
+
+/// Without a password BattlEye doesn't enable RCon at all.
+pub fn parse_rcon_settings(config: &str) -> Option<RconSettings> {
+    let mut password = None;
+    let mut port = None;
+    let mut ip = None;
+
+    for line in config.lines() {
+        let Some((key, value)) = line.trim().split_once(char::is_whitespace) else {
+            continue;
+        };
+
+        let value = value.trim();
+
+        match key.to_lowercase().as_str() {
+            "rconpassword" if !value.is_empty() => password = Some(value.to_string()),
+            "rconport" => port = value.parse().ok(),
+            "rconip" => ip = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    Some(RconSettings {
+        password: password?,
+        port,
+        ip,
+    })
+}
+
+/// The BattlEye server config the manager writes before every start.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BattlEyeConfig {
+    pub rcon: RconSettings,
+    /// Players with a higher ping get kicked.
+    pub max_ping: Option<u32>,
+}
+
+impl fmt::Display for BattlEyeConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "RConPassword {}", self.rcon.password)?;
+
+        if let Some(port) = self.rcon.port {
+            writeln!(f, "RConPort {}", port)?;
+        }
+
+        if let Some(ip) = self.rcon.ip {
+            writeln!(f, "RConIP {}", ip)?;
+        }
+
+        if let Some(max_ping) = self.max_ping {
+            writeln!(f, "MaxPing {}", max_ping)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the config into the BattlEye folder of the server.
+/// BattlEye loads itself from that folder too, so its files are copied over from the server if they are missing,
+/// after that BattlEye keeps its copy up to date by itself.
+pub(crate) fn prepare_battleye(
+    arma_path: &Path,
+    battleye_path: &Path,
+    config: &BattlEyeConfig,
+) -> Result<(), std::io::Error> {
+    std::fs::create_dir_all(battleye_path)?;
+
+    let server_battleye_path = arma_path.join("battleye");
+
+    if server_battleye_path != battleye_path {
+        if let Ok(entries) = std::fs::read_dir(&server_battleye_path) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let target = battleye_path.join(entry.file_name());
+
+                if path.is_file() && !is_battleye_config(&path) && !target.exists() {
+                    std::fs::copy(&path, &target)?;
+                }
+            }
+        }
+    }
+
+    // a server that didn't shut down cleanly leaves its active config behind, which would be read instead of this one
+    for entry in std::fs::read_dir(battleye_path)?.flatten() {
+        let path = entry.path();
+
+        if path.is_file() && is_battleye_config(&path) {
+            std::fs::remove_file(&path)?;
+        }
+    }
+
+    std::fs::write(battleye_path.join(BATTLEYE_CONFIG), config.to_string())
+}
+
+fn is_battleye_config(path: &Path) -> bool {
+    let Some(name) = path.file_name() else {
+        return false;
+    };
+
+    let name = name.to_string_lossy().to_lowercase();
+
+    name == BATTLEYE_CONFIG || (name.starts_with("beserver_x64_active_") && name.ends_with(".cfg"))
+}
@@ -0,0 +1,873 @@
+use std::fmt;
+
+mod rapified;
+
+pub use rapified::{is_rapified, parse_bytes, parse_rapified};
+
+/// A value on the right hand side of a config property.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    /// Anything that isn't quoted, numbers, booleans and bare words.
+    Literal(String),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) | Value::Literal(value) => Some(value),
+            Value::Array(_) => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_str()
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .map(|value| value as i64)
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Literal(value.to_string())
+    }
+}
+
+impl<T> From<Vec<T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(values: Vec<T>) -> Self {
+        Value::Array(values.into_iter().map(|value| value.into()).collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Entry {
+    Property {
+        name: String,
+        value: Value,
+    },
+    /// `name[] += {...}`, extends the array inherited from the parent class.
+    Append {
+        name: String,
+        value: Value,
+    },
+    Class {
+        name: String,
+        parent: Option<String>,
+        /// `None` for forward declarations like `class Foo;`
+        body: Option<Config>,
+    },
+    Delete(String),
+}
+
+impl Entry {
+    pub fn name(&self) -> &str {
+        match self {
+            Entry::Property { name, .. } | Entry::Append { name, .. } | Entry::Class { name, .. } => name,
+            Entry::Delete(name) => name,
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = "\t".repeat(depth);
+
+        match self {
+            Entry::Property {
+                name,
+                value: value @ Value::Array(_),
+            } => writeln!(f, "{}{}[] = {};", indent, name, value),
+            Entry::Property { name, value } => writeln!(f, "{}{} = {};", indent, name, value),
+            Entry::Append { name, value } => writeln!(f, "{}{}[] += {};", indent, name, value),
+            Entry::Class { name, parent, body } => {
+                write!(f, "{}class {}", indent, name)?;
+
+                if let Some(parent) = parent {
+                    write!(f, ": {}", parent)?;
+                }
+
+                match body {
+                    Some(body) if body.entries.is_empty() => writeln!(f, " {{}};"),
+                    Some(body) => {
+                        writeln!(f, "\n{}{{", indent)?;
+                        body.write(f, depth + 1)?;
+                        writeln!(f, "{}}};", indent)
+                    }
+                    None => writeln!(f, ";"),
+                }
+            }
+            Entry::Delete(name) => writeln!(f, "{}delete {};", indent, name),
+        }
+    }
+}
+
+/// A parsed config file, or the body of a class inside one.
+///
+/// Lookups are case insensitive, just like the game does it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub entries: Vec<Entry>,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.entries.iter().find_map(|entry| match entry {
+            Entry::Property { name: n, value } if n.eq_ignore_ascii_case(name) => Some(value),
+            _ => None,
+        })
+    }
+
+    pub fn class(&self, name: &str) -> Option<&Config> {
+        self.entries.iter().find_map(|entry| match entry {
+            Entry::Class { name: n, body, .. } if n.eq_ignore_ascii_case(name) => body.as_ref(),
+            _ => None,
+        })
+    }
+
+    pub fn class_mut(&mut self, name: &str) -> Option<&mut Config> {
+        self.entries.iter_mut().find_map(|entry| match entry {
+            Entry::Class { name: n, body, .. } if n.eq_ignore_ascii_case(name) => body.as_mut(),
+            _ => None,
+        })
+    }
+
+    /// All classes with a body, in the order they are defined.
+    pub fn classes(&self) -> impl Iterator<Item = (&str, &Config)> {
+        self.entries.iter().filter_map(|entry| match entry {
+            Entry::Class {
+                name, body: Some(body), ..
+            } => Some((name.as_str(), body)),
+            _ => None,
+        })
+    }
+
+    /// Sets a property, replacing the first one with the same name or appending it at the end.
+    /// Anything appended to a property of the same name is dropped, the value is all there is.
+    pub fn set(&mut self, name: &str, value: impl Into<Value>) {
+        let value = value.into();
+
+        self.entries
+            .retain(|entry| !matches!(entry, Entry::Append { name: n, .. } if n.eq_ignore_ascii_case(name)));
+
+        for entry in self.entries.iter_mut() {
+            if let Entry::Property { name: n, value: v } = entry {
+                if n.eq_ignore_ascii_case(name) {
+                    *v = value;
+                    return;
+                }
+            }
+        }
+
+        self.entries.push(Entry::Property {
+            name: name.to_string(),
+            value,
+        });
+    }
+
+    /// Sets a class body, replacing the first class with the same name or appending it at the end.
+    pub fn set_class(&mut self, name: &str, class: Config) {
+        for entry in self.entries.iter_mut() {
+            if let Entry::Class { name: n, body, .. } = entry {
+                if n.eq_ignore_ascii_case(name) {
+                    *body = Some(class);
+                    return;
+                }
+            }
+        }
+
+        self.entries.push(Entry::Class {
+            name: name.to_string(),
+            parent: None,
+            body: Some(class),
+        });
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        for entry in &self.entries {
+            entry.write(f, depth)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::String(value) => write!(f, "\"{}\"", value.replace('"', "\"\"")),
+            Value::Literal(value) => write!(f, "{}", value),
+            Value::Array(values) => {
+                write!(f, "{{")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a text config, preprocessor directives are skipped, not resolved.
+pub fn parse(input: &str) -> Result<Config, ParseError> {
+    let entries = Parser::new(input).entries(false)?;
+
+    Ok(Config { entries })
+}
+
+/// Replaces top level entries of a text config and keeps everything else as it was written,
+/// comments, preprocessor directives and anything the parser doesn't know included.
+///
+/// Each entry takes the place of the first top level entry with its name, further ones with that name
+/// are dropped and entries that aren't there yet are added at the end.
+pub fn splice(input: &str, entries: &[Entry]) -> Result<String, ParseError> {
+    let mut parser = Parser::new(input);
+    let mut output = String::new();
+    let mut written = vec![false; entries.len()];
+    let mut copied = 0;
+
+    loop {
+        parser.skip_whitespace();
+
+        if parser.peek().is_none() {
+            break;
+        }
+
+        let start = parser.pos;
+        let name = parser.statement_name();
+        parser.skip_statement()?;
+
+        let Some(index) = entries.iter().position(|entry| {
+            name.as_deref()
+                .is_some_and(|name| entry.name().eq_ignore_ascii_case(name))
+        }) else {
+            continue;
+        };
+
+        output.extend(&parser.chars[copied..start]);
+        copied = parser.pos;
+
+        if !written[index] {
+            output.push_str(entries[index].to_string().trim_end());
+            written[index] = true;
+        }
+    }
+
+    output.extend(&parser.chars[copied..]);
+
+    for (entry, _) in entries.iter().zip(written).filter(|(_, written)| !written) {
+        if !output.is_empty() && !output.ends_with('\n') {
+            output.push('\n');
+        }
+
+        output.push_str(&entry.to_string());
+    }
+
+    Ok(output)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            pos: 0,
+            line: 1,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, ParseError> {
+        Err(ParseError {
+            line: self.line,
+            message: message.into(),
+        })
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.bump();
+                Ok(())
+            }
+            Some(c) => self.error(format!("expected '{}', found '{}'", expected, c)),
+            None => self.error(format!("expected '{}', found end of file", expected)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let mut line_start = self.pos == 0 || self.chars.get(self.pos - 1) == Some(&'\n');
+
+        while let Some(c) = self.peek() {
+            if c == '\n' {
+                line_start = true;
+                self.bump();
+            } else if c.is_whitespace() {
+                self.bump();
+            } else if c == '/' && self.peek_at(1) == Some('/') {
+                while !matches!(self.peek(), Some('\n') | None) {
+                    self.bump();
+                }
+            } else if c == '/' && self.peek_at(1) == Some('*') {
+                self.bump();
+                self.bump();
+                while self.peek().is_some() && !(self.peek() == Some('*') && self.peek_at(1) == Some('/')) {
+                    self.bump();
+                }
+                self.bump();
+                self.bump();
+            } else if c == '#' && line_start {
+                // preprocessor directive, lines can be continued with a backslash
+                while let Some(c) = self.peek() {
+                    if c == '\n' && self.chars.get(self.pos - 1) != Some(&'\\') {
+                        break;
+                    }
+                    self.bump();
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, ParseError> {
+        self.skip_whitespace();
+
+        let mut identifier = String::new();
+        while let Some(c) = self.peek() {
+            if !(c.is_alphanumeric() || c == '_') {
+                break;
+            }
+            identifier.push(c);
+            self.bump();
+        }
+
+        if identifier.is_empty() {
+            return match self.peek() {
+                Some(c) => self.error(format!("expected identifier, found '{}'", c)),
+                None => self.error("expected identifier, found end of file"),
+            };
+        }
+
+        Ok(identifier)
+    }
+
+    fn entries(&mut self, in_class: bool) -> Result<Vec<Entry>, ParseError> {
+        let mut entries = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                None if in_class => return self.error("unexpected end of file, missing '}'"),
+                None => break,
+                Some('}') if in_class => {
+                    self.bump();
+                    break;
+                }
+                Some('}') => return self.error("unexpected '}'"),
+                Some(';') => {
+                    self.bump();
+                    continue;
+                }
+                _ => {}
+            }
+
+            let identifier = self.identifier()?;
+
+            if identifier == "class" {
+                let name = self.identifier()?;
+                self.skip_whitespace();
+
+                let mut parent = None;
+                if self.peek() == Some(':') {
+                    self.bump();
+                    parent = Some(self.identifier()?);
+                    self.skip_whitespace();
+                }
+
+                let body = if self.peek() == Some('{') {
+                    self.bump();
+                    Some(Config {
+                        entries: self.entries(true)?,
+                    })
+                } else {
+                    None
+                };
+
+                self.expect(';')?;
+                entries.push(Entry::Class { name, parent, body });
+            } else if identifier == "delete" {
+                let name = self.identifier()?;
+                self.expect(';')?;
+                entries.push(Entry::Delete(name));
+            } else {
+                self.skip_whitespace();
+
+                let mut is_array = false;
+                if self.peek() == Some('[') {
+                    self.bump();
+                    self.expect(']')?;
+                    is_array = true;
+                }
+
+                self.skip_whitespace();
+
+                let append = self.peek() == Some('+');
+                if append {
+                    if !is_array {
+                        return self.error(format!(
+                            "only arrays can be extended with '+=', {} is not one",
+                            identifier
+                        ));
+                    }
+                    self.bump();
+                }
+
+                self.expect('=')?;
+                self.skip_whitespace();
+
+                let value = if is_array || self.peek() == Some('{') {
+                    self.array()?
+                } else {
+                    self.scalar(&[';', '\n'])?
+                };
+
+                self.expect(';')?;
+
+                if append {
+                    entries.push(Entry::Append {
+                        name: identifier,
+                        value,
+                    });
+                } else {
+                    entries.push(Entry::Property {
+                        name: identifier,
+                        value,
+                    });
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// The name of the entry that starts here, without parsing it. `None` if it doesn't look like one.
+    fn statement_name(&mut self) -> Option<String> {
+        let start = self.pos;
+        let mut word = || {
+            self.skip_whitespace();
+
+            let mut word = String::new();
+            while let Some(c) = self.peek().filter(|c| c.is_alphanumeric() || *c == '_') {
+                word.push(c);
+                self.bump();
+            }
+
+            word
+        };
+
+        let first = word();
+        let name = match first.as_str() {
+            "class" | "delete" => word(),
+            _ => first,
+        };
+
+        // only looking, the statement is skipped as a whole afterwards
+        self.line -= self.chars[start..self.pos].iter().filter(|c| **c == '\n').count();
+        self.pos = start;
+
+        Some(name).filter(|name| !name.is_empty())
+    }
+
+    /// Skips to the end of the statement, the ';' at the top level, keeping track of braces, strings and comments.
+    fn skip_statement(&mut self) -> Result<(), ParseError> {
+        let mut depth = 0usize;
+
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                None if depth == 0 => return Ok(()),
+                None => return self.error("unexpected end of file, missing '}'"),
+                Some('"') | Some('\'') => {
+                    self.string()?;
+                }
+                Some('{') => {
+                    depth += 1;
+                    self.bump();
+                }
+                Some('}') if depth == 0 => return self.error("unexpected '}'"),
+                Some('}') => {
+                    depth -= 1;
+                    self.bump();
+                }
+                Some(';') if depth == 0 => {
+                    self.bump();
+                    return Ok(());
+                }
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn scalar(&mut self, terminators: &[char]) -> Result<Value, ParseError> {
+        self.skip_whitespace();
+
+        if self.peek() == Some('"') || self.peek() == Some('\'') {
+            return self.string();
+        }
+
+        let mut literal = String::new();
+        while let Some(c) = self.peek() {
+            if terminators.contains(&c) {
+                break;
+            }
+            literal.push(c);
+            self.bump();
+        }
+
+        let literal = literal.trim();
+
+        if literal.is_empty() {
+            return self.error("expected a value");
+        }
+
+        Ok(Value::Literal(literal.to_string()))
+    }
+
+    fn string(&mut self) -> Result<Value, ParseError> {
+        let Some(quote) = self.bump() else {
+            return self.error("expected a string");
+        };
+
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some(c) if c == quote && self.peek() == Some(quote) => {
+                    self.bump();
+                    value.push(quote);
+                }
+                Some(c) if c == quote => break,
+                Some(c) => value.push(c),
+                None => return self.error("unterminated string"),
+            }
+        }
+
+        Ok(Value::String(value))
+    }
+
+    fn array(&mut self) -> Result<Value, ParseError> {
+        self.expect('{')?;
+
+        let mut values = Vec::new();
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some('}') => {
+                    self.bump();
+                    break;
+                }
+                Some('{') => values.push(self.array()?),
+                Some(_) => values.push(self.scalar(&[',', '}'])?),
+                None => return self.error("unexpected end of file, missing '}'"),
+            }
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some('}') => {}
+                Some(c) => return self.error(format!("expected ',' or '}}', found '{}'", c)),
+                None => return self.error("unexpected end of file, missing '}'"),
+            }
+        }
+
+        Ok(Value::Array(values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MISSIONS: &str = r#"class Missions
+{
+	class Mission1
+	{
+		template = "co10_escape.Altis";
+		difficulty = "Regular";
+		class Params
+		{
+			daytime = 12;
+		};
+	};
+	class Mission2: Mission1
+	{
+		template = "dev.Stratis";
+	};
+};
+"#;
+
+    #[test]
+    fn round_trips_missions() {
+        let config = parse(MISSIONS).unwrap();
+
+        assert_eq!(config.to_string(), MISSIONS);
+
+        let mission = config.class("missions").unwrap().class("Mission1").unwrap();
+        assert_eq!(mission.get("template"), Some(&Value::from("co10_escape.Altis")));
+        assert_eq!(
+            mission.class("Params").unwrap().get("daytime").unwrap().as_i64(),
+            Some(12)
+        );
+
+        let names = config
+            .class("Missions")
+            .unwrap()
+            .classes()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Mission1", "Mission2"]);
+    }
+
+    #[test]
+    fn round_trips_arrays() {
+        let input = "admins[] = {\"76561198000000000\", 123, {1, {}, \"two\"}};\nempty[] = {};\n";
+        let config = parse(input).unwrap();
+
+        assert_eq!(
+            config.get("admins"),
+            Some(&Value::Array(vec![
+                Value::from("76561198000000000"),
+                Value::from(123),
+                Value::Array(vec![Value::from(1), Value::Array(vec![]), Value::from("two")]),
+            ]))
+        );
+        assert_eq!(config.get("empty").unwrap().as_array(), Some(&[][..]));
+        assert_eq!(config.to_string(), input);
+    }
+
+    #[test]
+    fn round_trips_escaped_quotes() {
+        let input = "hostname = \"The \"\"best\"\" server; {really}\";\nmotd[] = {\"Say \"\"hi\"\"\"};\n";
+        let config = parse(input).unwrap();
+
+        assert_eq!(
+            config.get("hostname").unwrap().as_str(),
+            Some(r#"The "best" server; {really}"#)
+        );
+        assert_eq!(config.to_string(), input);
+        assert_eq!(parse(&config.to_string()).unwrap(), config);
+    }
+
+    #[test]
+    fn keeps_appends() {
+        let input = "class Base\n{\n\tmotd[] = {\"a\"};\n};\nclass Derived: Base\n{\n\tmotd[] += {\"b\"};\n};\n";
+        let config = parse(input).unwrap();
+
+        assert_eq!(
+            config.class("Derived").unwrap().entries,
+            vec![Entry::Append {
+                name: "motd".to_string(),
+                value: Value::from(vec!["b"]),
+            }]
+        );
+        assert_eq!(config.class("Derived").unwrap().get("motd"), None);
+        assert_eq!(config.to_string(), input);
+
+        assert!(parse("maxPlayers += 1;").is_err());
+    }
+
+    #[test]
+    fn set_replaces_appends() {
+        let mut config = parse("localClient[] = {\"1.2.3.4\"};\nlocalClient[] += {\"5.6.7.8\"};\n").unwrap();
+        config.set("LOCALCLIENT", vec!["127.0.0.1"]);
+
+        assert_eq!(config.to_string(), "localClient[] = {\"127.0.0.1\"};\n");
+    }
+
+    #[test]
+    fn skips_comments_and_directives() {
+        let input = "// hostname = \"no\";\n#include \"motd.hpp\"\n#define LONG \\\n\tcontinued\n/* maxPlayers = 1; */ maxPlayers = 64; // trailing\n";
+        let config = parse(input).unwrap();
+
+        assert_eq!(config.entries.len(), 1);
+        assert_eq!(config.get("maxPlayers").unwrap().as_i64(), Some(64));
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let error = |input: &str| parse(input).unwrap_err();
+
+        assert_eq!(error("hostname = \"a\";\nmaxPlayers = 1\nclass Missions {};").line, 3);
+        assert_eq!(error("hostname = \"open;\n").message, "unterminated string");
+        assert_eq!(
+            error("class Missions {\n").message,
+            "unexpected end of file, missing '}'"
+        );
+        assert_eq!(error("};").message, "unexpected '}'");
+        assert_eq!(
+            error("admins[] = {\"a\" \"b\"};").message,
+            "expected ',' or '}', found '\"'"
+        );
+        assert_eq!(error("MACRO(1)").message, "expected '=', found '('");
+    }
+
+    #[test]
+    fn splices_managed_entries() {
+        let input = r#"// managed by hand
+#include "motd.hpp"
+hostname = "A ""quoted"" {name};";
+class Missions
+{
+	class Old { template = "old.Altis"; };
+};
+motd[] += {"kept"};
+LOCALCLIENT[] = {"1.2.3.4"};
+UNKNOWN_MACRO(1)
+"#;
+
+        let mut missions = Config::new();
+        missions.set_class("Mission1", Config::new());
+
+        let entries = vec![
+            Entry::Class {
+                name: "Missions".to_string(),
+                parent: None,
+                body: Some(missions),
+            },
+            Entry::Property {
+                name: "localClient".to_string(),
+                value: Value::from(vec!["127.0.0.1"]),
+            },
+            Entry::Property {
+                name: "headlessClients".to_string(),
+                value: Value::from(vec!["127.0.0.1"]),
+            },
+        ];
+
+        let output = splice(input, &entries).unwrap();
+
+        assert_eq!(
+            output,
+            r#"// managed by hand
+#include "motd.hpp"
+hostname = "A ""quoted"" {name};";
+class Missions
+{
+	class Mission1 {};
+};
+motd[] += {"kept"};
+localClient[] = {"127.0.0.1"};
+UNKNOWN_MACRO(1)
+headlessClients[] = {"127.0.0.1"};
+"#
+        );
+    }
+
+    #[test]
+    fn splice_drops_duplicates() {
+        let entries = vec![Entry::Property {
+            name: "headlessClients".to_string(),
+            value: Value::from(vec!["127.0.0.1"]),
+        }];
+
+        let output = splice(
+            "headlessClients[] = {\"a\"};\nhostname = \"x\";\nheadlessClients[] += {\"b\"};",
+            &entries,
+        )
+        .unwrap();
+
+        assert_eq!(output, "headlessClients[] = {\"127.0.0.1\"};\nhostname = \"x\";\n");
+        assert_eq!(splice("", &entries).unwrap(), "headlessClients[] = {\"127.0.0.1\"};\n");
+    }
+
+    #[test]
+    fn splice_rejects_unbalanced_configs() {
+        let entries = vec![Entry::Delete("Missions".to_string())];
+
+        assert!(splice("class Missions {", &entries).is_err());
+        assert!(splice("};", &entries).is_err());
+        assert!(splice("hostname = \"open;", &entries).is_err());
+    }
+}
@@ -0,0 +1,362 @@
+use super::{Config, Entry, ParseError, Value};
+
+/// Rapified configs start with a null byte followed by "raP".
+const SIGNATURE: &[u8; 4] = b"\0raP";
+
+pub fn is_rapified(input: &[u8]) -> bool {
+    input.starts_with(SIGNATURE)
+}
+
+/// Parses a binarized config like the ones in PBOs, `mission.sqm` is usually stored this way.
+pub fn parse_rapified(input: &[u8]) -> Result<Config, ParseError> {
+    if !is_rapified(input) {
+        return Err(error(0, "missing raP signature"));
+    }
+
+    // signature, two reserved integers and the offset of the enum list
+    let mut reader = Reader { input, pos: 16 };
+
+    let _parent = reader.string()?;
+    reader.class_body(0)
+}
+
+/// Parses a config that is either rapified or text.
+pub fn parse_bytes(input: &[u8]) -> Result<Config, ParseError> {
+    if is_rapified(input) {
+        return parse_rapified(input);
+    }
+
+    super::parse(&String::from_utf8_lossy(input))
+}
+
+fn error(pos: usize, message: impl Into<String>) -> ParseError {
+    ParseError {
+        line: 0,
+        message: format!("rapified config at byte {}: {}", pos, message.into()),
+    }
+}
+
+struct Reader<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, ParseError> {
+        let byte = *self
+            .input
+            .get(self.pos)
+            .ok_or_else(|| error(self.pos, "unexpected end"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], ParseError> {
+        let bytes = self
+            .input
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| error(self.pos, "unexpected end"))?;
+        self.pos += N;
+
+        let mut result = [0u8; N];
+        result.copy_from_slice(bytes);
+        Ok(result)
+    }
+
+    fn u32(&mut self) -> Result<u32, ParseError> {
+        self.bytes::<4>().map(u32::from_le_bytes)
+    }
+
+    /// Counts are stored 7 bits at a time, the high bit marks that another byte follows.
+    fn compressed_int(&mut self) -> Result<usize, ParseError> {
+        let mut value = 0usize;
+
+        for shift in (0..35).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7F) as usize) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(error(self.pos, "compressed integer is too long"))
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let rest = self.input.get(self.pos..).unwrap_or_default();
+        let length = rest
+            .iter()
+            .position(|byte| *byte == 0)
+            .ok_or_else(|| error(self.pos, "unterminated string"))?;
+
+        let value = String::from_utf8_lossy(&rest[..length]).to_string();
+        self.pos += length + 1;
+        Ok(value)
+    }
+
+    /// Reads the entries of a class, the parent name has already been read.
+    fn class_body(&mut self, depth: usize) -> Result<Config, ParseError> {
+        // nothing legitimate is nested this deep, but a crafted file could point classes at each other
+        if depth > 64 {
+            return Err(error(self.pos, "classes are nested too deep"));
+        }
+
+        let count = self.compressed_int()?;
+        let mut entries = Vec::new();
+
+        for _ in 0..count {
+            match self.byte()? {
+                0 => {
+                    let name = self.string()?;
+                    let offset = self.u32()? as usize;
+
+                    // the body lives elsewhere in the file, continue here afterwards
+                    let resume = self.pos;
+                    self.pos = offset;
+
+                    let parent = Some(self.string()?).filter(|parent| !parent.is_empty());
+                    let body = self.class_body(depth + 1)?;
+
+                    self.pos = resume;
+
+                    entries.push(Entry::Class {
+                        name,
+                        parent,
+                        body: Some(body),
+                    });
+                }
+                1 => {
+                    let kind = self.byte()?;
+                    let name = self.string()?;
+                    let value = self.scalar(kind)?;
+                    entries.push(Entry::Property { name, value });
+                }
+                2 => {
+                    let name = self.string()?;
+                    let value = self.array()?;
+                    entries.push(Entry::Property { name, value });
+                }
+                3 => {
+                    let name = self.string()?;
+                    entries.push(Entry::Class {
+                        name,
+                        parent: None,
+                        body: None,
+                    });
+                }
+                4 => {
+                    let name = self.string()?;
+                    entries.push(Entry::Delete(name));
+                }
+                5 => {
+                    // `name[] += {}`
+                    let _flags = self.u32()?;
+                    let name = self.string()?;
+                    let value = self.array()?;
+                    entries.push(Entry::Append { name, value });
+                }
+                kind => return Err(error(self.pos - 1, format!("unknown entry type {}", kind))),
+            }
+        }
+
+        Ok(Config { entries })
+    }
+
+    fn scalar(&mut self, kind: u8) -> Result<Value, ParseError> {
+        match kind {
+            0 => Ok(Value::String(self.string()?)),
+            1 => Ok(Value::Literal(f32::from_le_bytes(self.bytes::<4>()?).to_string())),
+            2 => Ok(Value::Literal(i32::from_le_bytes(self.bytes::<4>()?).to_string())),
+            // variables are stored as strings
+            4 => Ok(Value::Literal(self.string()?)),
+            kind => Err(error(self.pos, format!("unknown value type {}", kind))),
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, ParseError> {
+        let count = self.compressed_int()?;
+        let mut values = Vec::new();
+
+        for _ in 0..count {
+            let value = match self.byte()? {
+                3 => self.array()?,
+                kind => self.scalar(kind)?,
+            };
+            values.push(value);
+        }
+
+        Ok(Value::Array(values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> Vec<u8> {
+        let mut buffer = SIGNATURE.to_vec();
+        buffer.extend([0u8; 12]);
+        // no parent for the root class
+        buffer.push(0);
+        buffer
+    }
+
+    fn string(buffer: &mut Vec<u8>, value: &str) {
+        buffer.extend(value.as_bytes());
+        buffer.push(0);
+    }
+
+    /// A class entry whose body is written later, returns where to put its offset.
+    fn class(buffer: &mut Vec<u8>, name: &str) -> usize {
+        buffer.push(0);
+        string(buffer, name);
+        buffer.extend([0u8; 4]);
+        buffer.len() - 4
+    }
+
+    fn point_here(buffer: &mut [u8], at: usize) {
+        let offset = (buffer.len() as u32).to_le_bytes();
+        buffer[at..at + 4].copy_from_slice(&offset);
+    }
+
+    #[test]
+    fn parses_every_entry_type() {
+        let mut buffer = header();
+        buffer.push(8);
+
+        buffer.extend([1, 0]);
+        string(&mut buffer, "hostname");
+        string(&mut buffer, "Server");
+
+        buffer.extend([1, 2]);
+        string(&mut buffer, "maxPlayers");
+        buffer.extend(64i32.to_le_bytes());
+
+        buffer.extend([1, 1]);
+        string(&mut buffer, "timeout");
+        buffer.extend(1.5f32.to_le_bytes());
+
+        buffer.push(2);
+        string(&mut buffer, "admins");
+        buffer.extend([2, 0]);
+        string(&mut buffer, "a");
+        buffer.extend([3, 1, 2]);
+        buffer.extend(7i32.to_le_bytes());
+
+        buffer.push(3);
+        string(&mut buffer, "Forward");
+
+        buffer.push(4);
+        string(&mut buffer, "Deleted");
+
+        buffer.push(5);
+        buffer.extend(1u32.to_le_bytes());
+        string(&mut buffer, "motd");
+        buffer.extend([1, 0]);
+        string(&mut buffer, "hi");
+
+        let missions = class(&mut buffer, "Missions");
+
+        point_here(&mut buffer, missions);
+        string(&mut buffer, "Base");
+        buffer.extend([1, 1, 0]);
+        string(&mut buffer, "template");
+        string(&mut buffer, "co10.Altis");
+
+        let config = parse_rapified(&buffer).unwrap();
+
+        assert_eq!(
+            config.entries,
+            vec![
+                Entry::Property {
+                    name: "hostname".to_string(),
+                    value: Value::from("Server"),
+                },
+                Entry::Property {
+                    name: "maxPlayers".to_string(),
+                    value: Value::from(64),
+                },
+                Entry::Property {
+                    name: "timeout".to_string(),
+                    value: Value::Literal("1.5".to_string()),
+                },
+                Entry::Property {
+                    name: "admins".to_string(),
+                    value: Value::Array(vec![Value::from("a"), Value::Array(vec![Value::from(7)])]),
+                },
+                Entry::Class {
+                    name: "Forward".to_string(),
+                    parent: None,
+                    body: None,
+                },
+                Entry::Delete("Deleted".to_string()),
+                Entry::Append {
+                    name: "motd".to_string(),
+                    value: Value::from(vec!["hi"]),
+                },
+                Entry::Class {
+                    name: "Missions".to_string(),
+                    parent: Some("Base".to_string()),
+                    body: Some(Config {
+                        entries: vec![Entry::Property {
+                            name: "template".to_string(),
+                            value: Value::from("co10.Altis"),
+                        }],
+                    }),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_long_counts() {
+        let mut buffer = header();
+        buffer.extend([1, 2]);
+        string(&mut buffer, "values");
+        // 130 is stored as 0x82 0x01
+        buffer.extend([0x82, 0x01]);
+
+        for i in 0..130i32 {
+            buffer.push(2);
+            buffer.extend(i.to_le_bytes());
+        }
+
+        let config = parse_rapified(&buffer).unwrap();
+
+        assert_eq!(config.get("values").unwrap().as_array().unwrap().len(), 130);
+    }
+
+    #[test]
+    fn rejects_broken_files() {
+        assert!(parse_rapified(b"class Foo {};").is_err());
+
+        let mut buffer = header();
+        buffer.extend([1, 1, 0]);
+        string(&mut buffer, "hostname");
+        buffer.extend(b"unterminated");
+        assert!(parse_rapified(&buffer).is_err());
+
+        let mut buffer = header();
+        buffer.extend([1, 9]);
+        string(&mut buffer, "unknown");
+        assert!(parse_rapified(&buffer)
+            .unwrap_err()
+            .message
+            .contains("unknown entry type 9"));
+
+        // a class whose body is the root class, which contains it again
+        let mut buffer = header();
+        buffer.push(1);
+        let offset = class(&mut buffer, "Loop");
+        buffer[offset..offset + 4].copy_from_slice(&16u32.to_le_bytes());
+        assert!(parse_rapified(&buffer).unwrap_err().message.contains("nested too deep"));
+    }
+
+    #[test]
+    fn parses_text_configs_too() {
+        let config = parse_bytes(b"maxPlayers = 10;").unwrap();
+
+        assert_eq!(config.get("maxPlayers").unwrap().as_i64(), Some(10));
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::{BTreeMap, HashMap};
+
+use api_schema::response::{AddonConflict, ConflictKind, ConflictReport, ConflictingMod, PresetItem};
+
+use crate::{CfgPatch, PboInfo};
+
+/// Finds PBO file names, `CfgPatches` classes and prefixes that more than one loaded mod brings.
+/// The game loads whichever it finds first, so these are the usual cause of mods that only half work.
+pub fn find_conflicts(
+    items: &[PresetItem],
+    patches: &HashMap<i64, Vec<CfgPatch>>,
+    pbos: &HashMap<i64, Vec<PboInfo>>,
+) -> ConflictReport {
+    let mut mods = items
+        .iter()
+        .filter(|item| item.enabled && !item.blacklisted && !item.optional)
+        .collect::<Vec<_>>();
+    mods.sort_by_key(|item| item.position);
+
+    let mut found = Vec::new();
+
+    for item in mods {
+        for pbo in pbos.get(&item.published_file_id).into_iter().flatten() {
+            found.push((ConflictKind::Pbo, pbo.file_name.as_str(), item));
+
+            if let Some(prefix) = &pbo.prefix {
+                found.push((ConflictKind::Prefix, prefix.as_str(), item));
+            }
+        }
+
+        for patch in patches.get(&item.published_file_id).into_iter().flatten() {
+            found.push((ConflictKind::CfgPatches, patch.name.as_str(), item));
+        }
+    }
+
+    // keyed by the name the game compares, the first spelling found is the one reported
+    let mut owners: BTreeMap<(ConflictKind, String), (String, Vec<&PresetItem>)> = BTreeMap::new();
+
+    for (kind, name, item) in found {
+        let key = match kind {
+            ConflictKind::Prefix => pbo::normalize_path(name).trim_end_matches('\\').to_string(),
+            _ => name.to_lowercase(),
+        };
+
+        let (_, items) = owners
+            .entry((kind, key))
+            .or_insert_with(|| (name.to_string(), Vec::new()));
+
+        // a mod bringing the same thing twice only conflicts with itself
+        if !items
+            .iter()
+            .any(|owner| owner.published_file_id == item.published_file_id)
+        {
+            items.push(item);
+        }
+    }
+
+    let conflicts = owners
+        .into_iter()
+        .filter(|(_, (_, found))| found.len() > 1)
+        .map(|((kind, _), (name, found))| AddonConflict {
+            kind,
+            name,
+            mods: found
+                .into_iter()
+                .map(|item| ConflictingMod {
+                    published_file_id: item.published_file_id,
+                    name: item.name.clone(),
+                })
+                .collect(),
+        })
+        .collect();
+
+    ConflictReport { conflicts }
+}
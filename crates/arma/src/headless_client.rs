@@ -0,0 +1,82 @@
+use std::path::{Path, PathBuf};
+
+use process::{Process, ProcessControls};
+
+use crate::SERVER_BINARY;
+
+/// Headless clients always connect to a server on the same machine.
+pub const HEADLESS_CLIENT_ADDRESS: &str = "127.0.0.1";
+
+/// The name of the n-th headless client, starting at 1. Used for `-name` and as the log channel.
+pub fn headless_client_name(index: usize) -> String {
+    format!("hc{}", index)
+}
+
+/// Every headless client gets its own `-profiles` directory inside the one of its server,
+/// that keeps the RPT logs apart and lets us find the process again.
+pub fn headless_client_profiles(profiles: &Path, index: usize) -> PathBuf {
+    profiles.join(headless_client_name(index))
+}
+
+/// A headless client, which is just the server binary started with `-client`.
+pub struct HeadlessClient {
+    pub(crate) index: usize,
+    pub(crate) mods: Option<String>,
+    pub(crate) port: u16,
+    pub(crate) password: Option<String>,
+    pub(crate) profiles: PathBuf,
+}
+
+impl HeadlessClient {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn name(&self) -> String {
+        headless_client_name(self.index)
+    }
+
+    pub fn profiles(&self) -> &Path {
+        &self.profiles
+    }
+
+    /// The arguments passed to the server executable, in order.
+    pub fn arguments(&self) -> Vec<String> {
+        let mut arguments = vec![
+            "-client".to_string(),
+            format!("-connect={}", HEADLESS_CLIENT_ADDRESS),
+            format!("-port={}", self.port),
+        ];
+
+        if let Some(password) = self.password.as_ref().filter(|password| !password.is_empty()) {
+            arguments.push(format!(r#""-password={}""#, password));
+        }
+
+        arguments.push(format!("-name={}", self.name()));
+        arguments.push(format!(r#""-profiles={}""#, self.profiles.to_string_lossy()));
+
+        if let Some(mods) = self.mods.as_ref().filter(|mods| !mods.is_empty()) {
+            arguments.push(mods.clone());
+        }
+
+        arguments.push("-noSound".to_string());
+
+        arguments
+    }
+
+    pub fn run(self) -> Result<ProcessControls, Box<dyn std::error::Error>> {
+        let Some(arma_path) = paths::get_arma_path() else {
+            return Err("Arma 3 Server is not installed".into());
+        };
+
+        std::fs::create_dir_all(&self.profiles)?;
+
+        let mut cmd = Process::new(arma_path.join(SERVER_BINARY));
+
+        for argument in self.arguments() {
+            cmd.arg(argument);
+        }
+
+        Ok(cmd.start()?)
+    }
+}
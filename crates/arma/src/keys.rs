@@ -0,0 +1,162 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use api_schema::response::{DuplicateKey, KeyReport, ModKeyCheck, Preset, UnknownSignature};
+
+use crate::{get_addons_path, get_keys_path, get_mod_path, get_pbos, mod_exists, plan_keys};
+
+/// A key `install_keys` copied into the `keys` folder of the server and where it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstalledKey {
+    pub name: String,
+    pub published_file_id: Option<i64>,
+    pub dlc: Option<String>,
+}
+
+/// Both `.bikey` and `.bisign` files start with the name of the authority that made them.
+pub fn read_authority(path: &Path) -> Option<String> {
+    parse_authority(&std::fs::read(path).ok()?)
+}
+
+pub fn parse_authority(data: &[u8]) -> Option<String> {
+    let end = data.iter().position(|byte| *byte == 0)?;
+
+    let authority = String::from_utf8_lossy(&data[..end]).trim().to_string();
+    (!authority.is_empty()).then_some(authority)
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .is_some_and(|found| found.eq_ignore_ascii_case(extension))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
+
+fn list_files(folder: &Path, extension: &str) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return vec![];
+    };
+
+    let mut files = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && has_extension(path, extension))
+        .collect::<Vec<_>>();
+
+    files.sort();
+    files
+}
+
+/// The keys in the `keys` folder of the server that `install_keys` leaves in place.
+fn kept_keys(managed: &[String]) -> Vec<PathBuf> {
+    let Some(keys_path) = get_keys_path() else {
+        return vec![];
+    };
+
+    list_files(&keys_path, "bikey")
+        .into_iter()
+        .filter(|key| !managed.iter().any(|name| name.eq_ignore_ascii_case(&file_name(key))))
+        .collect()
+}
+
+/// Checks the keys and signatures of every enabled client mod against the keys the server would have after `install_keys`.
+/// `managed` are the keys `install_keys` installed last time, those are replaced.
+/// Server mods are left out, the server never verifies its own addons.
+pub fn key_report(preset: &Preset, managed: &[String]) -> KeyReport {
+    let mods = preset
+        .items
+        .iter()
+        .filter(|item| item.enabled && !item.blacklisted && !item.server_mod)
+        .filter(|item| mod_exists(item.published_file_id))
+        .collect::<Vec<_>>();
+
+    let authorities = plan_keys(preset, managed)
+        .install
+        .iter()
+        .map(|planned| &planned.source)
+        .chain(&kept_keys(managed))
+        .filter(|key| has_extension(key, "bikey"))
+        .filter_map(|key| read_authority(key))
+        .map(|authority| authority.to_lowercase())
+        .collect::<HashSet<_>>();
+
+    let mut report = KeyReport::default();
+    let mut key_owners: BTreeMap<String, Vec<(String, PathBuf)>> = BTreeMap::new();
+
+    for item in mods {
+        let mod_path = get_mod_path(item.published_file_id);
+        let keys = list_files(&mod_path.join("keys"), "bikey");
+
+        for key in &keys {
+            key_owners
+                .entry(file_name(key).to_lowercase())
+                .or_default()
+                .push((item.name.clone(), key.clone()));
+        }
+
+        let signatures = get_addons_path(&mod_path)
+            .map(|addons_path| list_files(&addons_path, "bisign"))
+            .unwrap_or_default();
+
+        let mut unsigned = Vec::new();
+        let mut unknown_signatures = Vec::new();
+
+        for pbo in get_pbos(&mod_path) {
+            let pbo_name = file_name(&pbo);
+
+            // signatures are named <addon>.pbo.<authority>.bisign
+            let prefix = format!("{}.", pbo_name.to_lowercase());
+            let pbo_signatures = signatures
+                .iter()
+                .filter(|signature| file_name(signature).to_lowercase().starts_with(&prefix))
+                .filter_map(|signature| read_authority(signature))
+                .collect::<Vec<_>>();
+
+            if pbo_signatures.is_empty() {
+                unsigned.push(pbo_name);
+                continue;
+            }
+
+            if pbo_signatures
+                .iter()
+                .any(|authority| authorities.contains(&authority.to_lowercase()))
+            {
+                continue;
+            }
+
+            unknown_signatures.extend(pbo_signatures.into_iter().map(|authority| UnknownSignature {
+                pbo: pbo_name.clone(),
+                authority,
+            }));
+        }
+
+        if keys.is_empty() || !unsigned.is_empty() || !unknown_signatures.is_empty() {
+            report.mods.push(ModKeyCheck {
+                published_file_id: item.published_file_id,
+                name: item.name.clone(),
+                keys: keys.iter().map(|key| file_name(key)).collect(),
+                unsigned,
+                unknown_signatures,
+            });
+        }
+    }
+
+    for owners in key_owners.into_values().filter(|owners| owners.len() > 1) {
+        let contents = owners
+            .iter()
+            .map(|(_, path)| std::fs::read(path).unwrap_or_default())
+            .collect::<Vec<_>>();
+
+        report.duplicates.push(DuplicateKey {
+            key: file_name(&owners[0].1),
+            mods: owners.into_iter().map(|(name, _)| name).collect(),
+            identical: contents.windows(2).all(|pair| pair[0] == pair[1]),
+        });
+    }
+
+    report
+}
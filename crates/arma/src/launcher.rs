@@ -0,0 +1,104 @@
+use std::fmt::Write;
+
+use api_schema::response::{Preset, PresetItem};
+
+const WORKSHOP_URL: &str = "https://steamcommunity.com/sharedfiles/filedetails/?id=";
+const STORE_URL: &str = "https://store.steampowered.com/app/";
+
+/// Writes the preset as an Arma 3 Launcher preset, players drag it onto the launcher to load the mods of the server.
+/// Server mods are left out, optional mods get their own list that the launcher doesn't import.
+pub fn launcher_preset(preset: &Preset) -> String {
+    let mut items = preset
+        .items
+        .iter()
+        .filter(|item| item.enabled && !item.blacklisted && !item.server_mod)
+        .collect::<Vec<_>>();
+    items.sort_by_key(|item| item.position);
+
+    let (optional, required): (Vec<_>, Vec<_>) = items.into_iter().partition(|item| item.optional);
+
+    let mut dlcs = preset.dlcs.iter().filter(|dlc| dlc.enabled).collect::<Vec<_>>();
+    dlcs.sort_by_key(|dlc| dlc.position);
+
+    let name = escape(&preset.name);
+
+    let mut html = String::new();
+
+    html.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<html>\n");
+    html.push_str("  <!--Created by Arma 3 Launcher: https://arma3.com-->\n  <head>\n");
+    html.push_str("    <meta name=\"arma:Type\" content=\"preset\" />\n");
+    let _ = writeln!(html, "    <meta name=\"arma:PresetName\" content=\"{}\" />", name);
+    html.push_str("    <meta name=\"generator\" content=\"Arma 3 Launcher - https://arma3.com\" />\n");
+    html.push_str("    <title>Arma 3</title>\n  </head>\n  <body>\n");
+    let _ = writeln!(html, "    <h1>Arma 3  - Preset <strong>{}</strong></h1>", name);
+    html.push_str("    <p class=\"before-list\">\n");
+    html.push_str("      <em>To import this preset, drag this file onto the Launcher window. Or click the MODS tab, then PRESET in the top right, then IMPORT at the bottom, and finally select this file.</em>\n");
+    html.push_str("    </p>\n");
+
+    write_mods(&mut html, "mod-list", "ModContainer", &required);
+
+    if !optional.is_empty() {
+        html.push_str("    <h2>Optional Mods</h2>\n");
+        html.push_str("    <p class=\"before-list\">\n");
+        html.push_str("      <em>The server allows these mods without requiring them, subscribe to the ones you want to use.</em>\n");
+        html.push_str("    </p>\n");
+
+        write_mods(&mut html, "optional-mod-list", "OptionalModContainer", &optional);
+    }
+
+    html.push_str("    <div class=\"dlc-list\">\n      <table>\n");
+    for dlc in dlcs {
+        let url = format!("{}{}", STORE_URL, dlc.app_id);
+
+        html.push_str("        <tr data-type=\"DlcContainer\">\n");
+        let _ = writeln!(
+            html,
+            "          <td data-type=\"DisplayName\">{}</td>",
+            escape(&dlc.name)
+        );
+        let _ = writeln!(
+            html,
+            "          <td>\n            <a href=\"{0}\" data-type=\"Link\">{0}</a>\n          </td>",
+            url
+        );
+        html.push_str("        </tr>\n");
+    }
+    html.push_str("      </table>\n    </div>\n");
+
+    html.push_str("    <div class=\"footer\">\n      <span>Created by Arma 3 Launcher by Bohemia Interactive.</span>\n    </div>\n");
+    html.push_str("  </body>\n</html>\n");
+
+    html
+}
+
+fn write_mods(html: &mut String, class: &str, container: &str, items: &[&PresetItem]) {
+    let _ = writeln!(html, "    <div class=\"{}\">\n      <table>", class);
+
+    for item in items {
+        let url = format!("{}{}", WORKSHOP_URL, item.published_file_id);
+
+        let _ = writeln!(html, "        <tr data-type=\"{}\">", container);
+        let _ = writeln!(
+            html,
+            "          <td data-type=\"DisplayName\">{}</td>",
+            escape(&item.name)
+        );
+        html.push_str("          <td>\n            <span class=\"from-steam\">Steam</span>\n          </td>\n");
+        let _ = writeln!(
+            html,
+            "          <td>\n            <a href=\"{0}\" data-type=\"Link\">{0}</a>\n          </td>",
+            url
+        );
+        html.push_str("        </tr>\n");
+    }
+
+    html.push_str("      </table>\n    </div>\n");
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
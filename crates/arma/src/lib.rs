@@ -1,11 +1,18 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use api_schema::response::Preset;
+use api_schema::response::{LaunchPreview, Preset, RotationMission};
 use process::{Process, ProcessControls};
 
 pub const ARMA_CLIENT_APP_ID: u64 = 107410;
 pub const ARMA_SERVER_APP_ID: u64 = 233780;
 
+pub const DEFAULT_PORT: u16 = 2302;
+
+const SERVER_BINARY: &str = "arma3server_x64.exe";
+
+/// The difficulties a mission in the rotation can be played on.
+pub const DIFFICULTIES: [&str; 4] = ["Recruit", "Regular", "Veteran", "Custom"];
+
 const DEFAULT_CONFIG: &str = include_str!("../server.cfg");
 const DEFAULT_PROFILE: &str = include_str!("../profile.cfg");
 
@@ -22,12 +29,37 @@ pub fn get_mod_path(published_file_id: i64) -> PathBuf {
         .join(published_file_id.to_string())
 }
 
-pub fn is_runnung() -> bool {
-    process::is_running("arma3server_x64.exe")
+/// Whether a server started with the given `-profiles` directory is running.
+pub fn is_running(profiles: &Path) -> bool {
+    process::is_running_with_arg(SERVER_BINARY, &profiles_arg(profiles))
+}
+
+/// Kills the server started with the given `-profiles` directory, other instances are left alone.
+pub fn kill(profiles: &Path) {
+    process::kill_with_arg(SERVER_BINARY, &profiles_arg(profiles));
 }
 
-pub fn kill() {
-    process::kill("arma3server_x64.exe");
+fn profiles_arg(profiles: &Path) -> String {
+    format!("-profiles={}", profiles.to_string_lossy())
+}
+
+/// Whether a mission with the given template is in `mpmissions`, either packed or as a folder.
+pub fn mission_exists(template: &str) -> bool {
+    let Some(arma_path) = paths::get_arma_path() else {
+        return false;
+    };
+
+    let Ok(entries) = std::fs::read_dir(arma_path.join("mpmissions")) else {
+        return false;
+    };
+
+    let template = template.to_lowercase();
+    let packed = format!("{}.pbo", template);
+
+    entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_lowercase())
+        .any(|file| file == template || file == packed)
 }
 
 pub fn get_mod_str(preset: &Preset) -> Result<String, Box<dyn std::error::Error>> {
@@ -39,12 +71,12 @@ pub fn get_mod_str(preset: &Preset) -> Result<String, Box<dyn std::error::Error>
 
     let mods = items
         .iter()
-        .filter(|item| item.enabled && !item.blacklisted && !item.server_mod)
+        .filter(|item| item.enabled && !item.blacklisted && !item.optional && !item.server_mod)
         .collect::<Vec<_>>();
 
     let server_mods = items
         .iter()
-        .filter(|item| item.enabled && !item.blacklisted && item.server_mod)
+        .filter(|item| item.enabled && !item.blacklisted && !item.optional && item.server_mod)
         .collect::<Vec<_>>();
 
     let dlcs = dlcs.iter().filter(|dlc| dlc.enabled).collect::<Vec<_>>();
@@ -89,92 +121,161 @@ pub fn get_mod_str(preset: &Preset) -> Result<String, Box<dyn std::error::Error>
     Ok(mods_str)
 }
 
-pub fn install_keys(preset: &Preset) -> Result<(), std::io::Error> {
-    let Some(arma_path) = paths::get_arma_path() else {
-        return Err(std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            "Arma 3 Server is not installed",
-        ));
-    };
+/// The `keys` folder of the server, every key in it is accepted when verifying signatures.
+pub fn get_keys_path() -> Option<PathBuf> {
+    paths::get_arma_path().map(|arma_path| arma_path.join("keys"))
+}
 
-    let items = preset.items.iter().filter(|item| item.enabled).collect::<Vec<_>>();
+/// The key files of the mods and DLCs of the preset.
+/// Keys with the same file name are only listed once, the first one found wins.
+fn collect_keys(preset: &Preset) -> Vec<(PathBuf, InstalledKey)> {
+    let mut keys: Vec<(PathBuf, InstalledKey)> = Vec::new();
 
-    let arma_keys_path = arma_path.join("keys");
+    let mut key_dirs = preset
+        .items
+        .iter()
+        .filter(|item| item.enabled)
+        .map(|item| {
+            let origin = (Some(item.published_file_id), None);
+            (get_mod_path(item.published_file_id).join("keys"), origin)
+        })
+        .collect::<Vec<_>>();
 
-    if !arma_keys_path.exists() {
-        std::fs::create_dir_all(&arma_keys_path)?;
+    if let Some(arma_path) = paths::get_arma_path() {
+        for dlc in preset.dlcs.iter().filter(|dlc| dlc.enabled) {
+            key_dirs.push((
+                arma_path.join(dlc.key.clone()).join("keys"),
+                (None, Some(dlc.key.clone())),
+            ));
+        }
     }
 
-    // delete all files except "a3.bikey" in the keys dir
-    for entry in std::fs::read_dir(&arma_keys_path)? {
-        let entry = entry?;
+    for (key_dir, (published_file_id, dlc)) in key_dirs {
+        let Ok(entries) = std::fs::read_dir(&key_dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
 
-        let file_name = entry.file_name();
+            if !path.is_file() || keys.iter().any(|(key, _)| key.file_name() == path.file_name()) {
+                continue;
+            }
+
+            let key = InstalledKey {
+                name: entry.file_name().to_string_lossy().to_string(),
+                published_file_id,
+                dlc: dlc.clone(),
+            };
 
-        if file_name != "a3.bikey" {
-            std::fs::remove_file(entry.path())?;
+            keys.push((path, key));
         }
     }
 
-    for item in &items {
-        let mod_path = get_mod_path(item.published_file_id);
-
-        let keys_path = mod_path.join("keys");
+    keys
+}
 
-        if keys_path.exists() {
-            for entry in std::fs::read_dir(&keys_path)? {
-                let entry = entry?;
+/// What `install_keys` does to the `keys` folder of the server, worked out without changing it.
+#[derive(Debug, Clone, Default)]
+pub struct KeyPlan {
+    /// Keys installed last time, they are removed first.
+    pub remove: Vec<String>,
+    /// Keys of the preset the server ends up with.
+    pub install: Vec<PlannedKey>,
+    /// Keys of the preset left out, a different key with the same name was put into the folder by hand.
+    pub shadowed: Vec<String>,
+}
 
-                let file_name = entry.file_name();
+#[derive(Debug, Clone)]
+pub struct PlannedKey {
+    pub source: PathBuf,
+    pub key: InstalledKey,
+    /// False for an identical key that is already there and only taken over.
+    pub copy: bool,
+}
 
-                let arma_key_path = arma_keys_path.join(file_name);
+/// Plans replacing the keys copied on the last start with the keys of the mods and DLCs of the preset.
+/// Only the keys in `managed` are removed, those are the ones a previous `install_keys` returned,
+/// keys uploaded or put into the folder by hand are left alone and win over a mod key with the same name.
+pub fn plan_keys(preset: &Preset, managed: &[String]) -> KeyPlan {
+    let keys_path = get_keys_path();
+    let is_managed = |name: &str| managed.iter().any(|managed| managed.eq_ignore_ascii_case(name));
+
+    let mut plan = KeyPlan {
+        remove: managed
+            .iter()
+            .filter(|name| {
+                keys_path
+                    .as_ref()
+                    .is_some_and(|keys_path| keys_path.join(name).is_file())
+            })
+            .cloned()
+            .collect(),
+        ..Default::default()
+    };
 
-                if !arma_key_path.exists() {
-                    std::fs::copy(entry.path(), arma_key_path)?;
-                }
+    for (source, key) in collect_keys(preset) {
+        // managed keys are gone by the time the keys are copied
+        let existing = keys_path
+            .as_ref()
+            .map(|keys_path| keys_path.join(&key.name))
+            .filter(|existing| existing.is_file() && !is_managed(&key.name));
+
+        match existing {
+            None => plan.install.push(PlannedKey {
+                source,
+                key,
+                copy: true,
+            }),
+            // keys copied before they were tracked are identical to the one of the mod, those are taken over
+            Some(existing) if std::fs::read(&existing).ok() == std::fs::read(&source).ok() => {
+                plan.install.push(PlannedKey {
+                    source,
+                    key,
+                    copy: false,
+                })
             }
+            Some(_) => plan.shadowed.push(key.name),
         }
     }
 
-    Ok(())
+    plan
 }
 
-pub fn install_dlc_keys(preset: &Preset) -> Result<(), std::io::Error> {
-    let Some(arma_path) = paths::get_arma_path() else {
+/// Carries out `plan_keys`, returning the keys to pass as `managed` next time.
+pub fn install_keys(preset: &Preset, managed: &[String]) -> Result<Vec<InstalledKey>, std::io::Error> {
+    let Some(arma_keys_path) = get_keys_path() else {
         return Err(std::io::Error::new(
             std::io::ErrorKind::NotFound,
             "Arma 3 Server is not installed",
         ));
     };
 
-    let arma_keys_path = arma_path.join("keys");
-
     if !arma_keys_path.exists() {
         std::fs::create_dir_all(&arma_keys_path)?;
     }
 
-    let items = preset.dlcs.iter().filter(|item| item.enabled).collect::<Vec<_>>();
-
-    for item in &items {
-        // key path is arma_path/{item.key}/keys, copy to arma_path/keys
-        let key_path = arma_path.join(item.key.clone()).join("keys");
+    let plan = plan_keys(preset, managed);
 
-        if key_path.exists() {
-            for entry in std::fs::read_dir(&key_path)? {
-                let entry = entry?;
-
-                let file_name = entry.file_name();
+    for name in &plan.remove {
+        std::fs::remove_file(arma_keys_path.join(name))?;
+    }
 
-                let arma_key_path = arma_keys_path.join(file_name);
+    let mut installed = Vec::new();
 
-                if !arma_key_path.exists() {
-                    std::fs::copy(entry.path(), arma_key_path)?;
-                }
+    for planned in plan.install {
+        // a key that can't be copied only keeps its mod from being verified, the server can still start
+        if planned.copy {
+            if let Err(e) = std::fs::copy(&planned.source, arma_keys_path.join(&planned.key.name)) {
+                tracing::warn!("Failed to install key {}: {}", planned.source.display(), e);
+                continue;
             }
         }
+
+        installed.push(planned.key);
     }
 
-    Ok(())
+    Ok(installed)
 }
 
 pub fn get_default_parameters() -> Vec<String> {
@@ -198,22 +299,24 @@ pub fn get_default_parameters() -> Vec<String> {
     params
 }
 
-pub fn prepare_config() -> Result<(), std::io::Error> {
-    let config_path = paths::get_config_path();
-    let config_file = config_path.join("server.cfg");
-
+pub fn prepare_config(config_file: &Path) -> Result<(), std::io::Error> {
     if !config_file.exists() {
+        if let Some(parent) = config_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
         std::fs::write(config_file, DEFAULT_CONFIG)?;
     }
 
     Ok(())
 }
 
-pub fn prepare_profile() -> Result<(), std::io::Error> {
-    let profile_path = paths::get_config_path();
-    let profile_file = profile_path.join("profile.cfg");
-
+pub fn prepare_profile(profile_file: &Path) -> Result<(), std::io::Error> {
     if !profile_file.exists() {
+        if let Some(parent) = profile_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
         std::fs::write(profile_file, DEFAULT_PROFILE)?;
     }
 
@@ -224,14 +327,30 @@ pub struct Arma3 {
     mods: Option<String>,
     parameters: Option<Vec<String>>,
     name: String,
+    port: u16,
+    config_file: PathBuf,
+    profile_file: PathBuf,
+    profiles: Option<PathBuf>,
+    headless_clients: usize,
+    missions: Vec<RotationMission>,
+    battleye: Option<BattlEyeConfig>,
 }
 
 impl Default for Arma3 {
     fn default() -> Self {
+        let config_path = paths::get_config_path();
+
         Self {
             mods: None,
             parameters: None,
             name: "server".to_string(),
+            port: DEFAULT_PORT,
+            config_file: config_path.join("server.cfg"),
+            profile_file: config_path.join("profile.cfg"),
+            profiles: None,
+            headless_clients: 0,
+            missions: Vec::new(),
+            battleye: None,
         }
     }
 }
@@ -251,52 +370,281 @@ impl Arma3 {
         self
     }
 
+    /// The profile name, passed as `-name`.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    pub fn config_file(mut self, config_file: impl Into<PathBuf>) -> Self {
+        self.config_file = config_file.into();
+        self
+    }
+
+    pub fn profile_file(mut self, profile_file: impl Into<PathBuf>) -> Self {
+        self.profile_file = profile_file.into();
+        self
+    }
+
+    /// The `-profiles` directory, the profile and RPT logs end up in here.
+    /// It is also what tells instances apart when looking for a running server.
+    pub fn profiles(mut self, profiles: impl Into<PathBuf>) -> Self {
+        self.profiles = Some(profiles.into());
+        self
+    }
+
+    /// How many headless clients connect to this server, they are allowed in the config automatically.
+    pub fn headless_clients(mut self, headless_clients: usize) -> Self {
+        self.headless_clients = headless_clients;
+        self
+    }
+
+    /// The mission rotation, replaces `class Missions` of the config unless it is empty.
+    pub fn missions(mut self, missions: Vec<RotationMission>) -> Self {
+        self.missions = missions;
+        self
+    }
+
+    /// The BattlEye config to write before the start, without one the config that is there is used.
+    pub fn battleye(mut self, battleye: BattlEyeConfig) -> Self {
+        self.battleye = Some(battleye);
+        self
+    }
+
+    /// The headless clients for this server, using the same mods and the password from the config.
+    /// Server mods are left out, those are only meant for the server itself.
+    pub fn build_headless_clients(&self) -> Vec<HeadlessClient> {
+        let password = std::fs::read_to_string(&self.config_file)
+            .ok()
+            .and_then(|config| config::parse(&config).ok())
+            .and_then(|config| config.get("password").and_then(|v| v.as_str()).map(|v| v.to_string()));
+
+        let mods = self
+            .mods
+            .as_ref()
+            .map(|mods| mods.split(r#" "-serverMod="#).next().unwrap_or_default().to_string());
+
+        let profiles = self
+            .profiles
+            .clone()
+            .unwrap_or_else(|| paths::get_instance_path(&self.name));
+
+        (1..=self.headless_clients)
+            .map(|index| HeadlessClient {
+                index,
+                mods: mods.clone(),
+                port: self.port,
+                password: password.clone(),
+                profiles: headless_client_profiles(&profiles, index),
+            })
+            .collect()
+    }
+
+    /// The config the server actually gets, the file is only rewritten if something has to be changed.
+    /// Only the settings the manager controls are replaced, the rest stays exactly as written.
+    pub(crate) fn effective_config(&self, config: &str) -> Result<String, config::ParseError> {
+        let managed = self.managed_config();
+
+        if managed.entries.is_empty() {
+            return Ok(config.to_string());
+        }
+
+        config::splice(config, &managed.entries)
+    }
+
+    /// Everything the manager controls in the config.
+    fn managed_config(&self) -> config::Config {
+        let mut config = config::Config::new();
+
+        if self.headless_clients > 0 {
+            config.set("headlessClients", vec![HEADLESS_CLIENT_ADDRESS]);
+            config.set("localClient", vec![HEADLESS_CLIENT_ADDRESS]);
+        }
+
+        if !self.missions.is_empty() {
+            let mut missions = config::Config::new();
+
+            for (i, mission) in self.missions.iter().enumerate() {
+                let mut class = config::Config::new();
+                class.set("template", mission.template.clone());
+                class.set("difficulty", mission.difficulty.clone());
+
+                if !mission.params.is_empty() {
+                    let mut params = config::Config::new();
+
+                    for param in &mission.params {
+                        params.set(&param.name, param.value);
+                    }
+
+                    class.set_class("Params", params);
+                }
+
+                missions.set_class(&format!("Mission{}", i + 1), class);
+            }
+
+            config.set_class("Missions", missions);
+        }
+
+        config
+    }
+
+    /// Each instance gets its own BattlEye folder in `-profiles`, otherwise they would share one config.
+    fn battleye_path(&self) -> Option<PathBuf> {
+        self.profiles.as_ref().map(|profiles| profiles.join("battleye"))
+    }
+
+    fn config_lock_path(&self) -> PathBuf {
+        self.config_file.with_extension("cfg.lock")
+    }
+
+    fn profile_lock_path(&self) -> Option<PathBuf> {
+        let profile_path = match &self.profiles {
+            Some(profiles) => profiles.join("Users").join(&self.name),
+            None => paths::get_profile_path(&self.name)?,
+        };
+
+        Some(profile_path.join(format!("{}.Arma3Profile", self.name))) // this is different
+    }
+
+    /// The arguments passed to the server executable, in order.
+    pub fn arguments(&self) -> Vec<String> {
+        let mut arguments = vec![format!("-name={}", self.name)];
+
+        if let Some(mods) = self.mods.as_ref().filter(|mods| !mods.is_empty()) {
+            arguments.push(mods.clone());
+        }
+
+        arguments.push(format!(r#""-config={}""#, self.config_lock_path().to_string_lossy()));
+        arguments.push(format!("-port={}", self.port));
+
+        if let Some(profiles) = &self.profiles {
+            arguments.push(format!(r#""{}""#, profiles_arg(profiles)));
+        }
+
+        if let Some(battleye_path) = self.battleye_path().filter(|_| self.battleye.is_some()) {
+            arguments.push(format!(r#""-bepath={}""#, battleye_path.to_string_lossy()));
+        }
+
+        if let Some(parameters) = &self.parameters {
+            arguments.extend(parameters.iter().cloned());
+        }
+
+        arguments
+    }
+
+    /// Resolves everything `run` would do without touching the disk or spawning the server.
+    pub fn preview(&self) -> LaunchPreview {
+        let mut warnings = Vec::new();
+
+        let program = match paths::get_arma_path() {
+            Some(arma_path) => arma_path.join(SERVER_BINARY).to_string_lossy().to_string(),
+            None => {
+                warnings.push("Arma 3 Server is not installed".to_string());
+                SERVER_BINARY.to_string()
+            }
+        };
+
+        let config = match std::fs::read_to_string(&self.config_file) {
+            Ok(config) => config,
+            Err(_) => {
+                warnings.push(format!(
+                    "{} does not exist, the default config will be used",
+                    self.config_file.display()
+                ));
+                DEFAULT_CONFIG.to_string()
+            }
+        };
+
+        let config = match self.effective_config(&config) {
+            Ok(effective) => effective,
+            Err(e) => {
+                warnings.push(format!("The managed settings can not be applied to the config, {}", e));
+                config
+            }
+        };
+
+        if self.mods.as_deref().unwrap_or_default().is_empty() {
+            warnings.push("No mods will be loaded".to_string());
+        }
+
+        let mut arguments = vec![program];
+        arguments.extend(self.arguments());
+
+        LaunchPreview {
+            arguments,
+            config: config.lines().map(|line| line.to_string()).collect(),
+            keys: vec![],
+            warnings,
+        }
+    }
+
     pub fn run(self) -> Result<ProcessControls, Box<dyn std::error::Error>> {
         let Some(arma_path) = paths::get_arma_path() else {
             return Err("Arma 3 Server is not installed".into());
         };
 
-        let config_path = paths::get_config_path();
-
-        let config_file = config_path.join("server.cfg");
-        let config_lock = config_path.join("server.cfg.lock");
+        let config_lock = self.config_lock_path();
 
-        let profile_file = config_path.join("profile.cfg");
-        let Some(mut profile_lock) = paths::get_profile_path(&self.name) else {
+        let Some(profile_lock) = self.profile_lock_path() else {
             unreachable!();
         };
-        profile_lock = profile_lock.join(format!("{}.Arma3Profile", self.name)); // this is different
 
-        if !config_file.exists() {
-            std::fs::write(&config_file, DEFAULT_CONFIG)?;
-        }
+        prepare_config(&self.config_file)?;
+        prepare_profile(&self.profile_file)?;
 
-        if !profile_file.exists() {
-            std::fs::write(&profile_file, DEFAULT_PROFILE)?;
+        if let Some(parent) = profile_lock.parent() {
+            std::fs::create_dir_all(parent)?;
         }
-        // make a copy of the config file, overwrite if exists "server.cfg.lock"
+
+        // write the effective config to "server.cfg.lock", overwrite if exists
         // then pass the path to the config file as a parameter
-        std::fs::copy(&config_file, &config_lock)?;
+        let config = std::fs::read_to_string(&self.config_file)?;
+        std::fs::write(&config_lock, self.effective_config(&config)?)?;
 
         // make a copy of the profile file, overwrite if exists "format!("{}.Arma3Profile", self.name)"
-        std::fs::copy(&profile_file, &profile_lock)?;
+        std::fs::copy(&self.profile_file, &profile_lock)?;
 
-        let mut cmd = Process::new(arma_path.join("arma3server_x64.exe"));
-
-        cmd.arg(format!("-name={}", self.name));
-
-        if let Some(mods) = self.mods {
-            cmd.arg(mods);
+        if let Some(battleye) = &self.battleye {
+            let battleye_path = self.battleye_path().unwrap_or_else(|| arma_path.join("battleye"));
+            prepare_battleye(&arma_path, &battleye_path, battleye)?;
         }
 
-        cmd.arg(format!(r#""-config={}""#, config_lock.to_string_lossy()));
+        let mut cmd = Process::new(arma_path.join(SERVER_BINARY));
 
-        if let Some(parameters) = self.parameters {
-            for parameter in parameters {
-                cmd.arg(parameter);
-            }
+        for argument in self.arguments() {
+            cmd.arg(argument);
         }
 
         Ok(cmd.start()?)
     }
 }
+
+mod addons;
+mod bans;
+mod battleye;
+pub mod config;
+mod conflicts;
+mod headless_client;
+mod keys;
+mod launcher;
+mod load_order;
+mod mission;
+mod preflight;
+mod rules;
+
+pub use addons::*;
+pub use bans::*;
+pub use battleye::*;
+pub use conflicts::*;
+pub use headless_client::*;
+pub use keys::*;
+pub use launcher::*;
+pub use load_order::*;
+pub use mission::*;
+pub use preflight::*;
+pub use rules::*;
@@ -0,0 +1,248 @@
+use std::collections::{HashMap, HashSet};
+
+use api_schema::response::{LoadOrderItem, LoadOrderReport, MissingDependency, PresetItem};
+
+use crate::CfgPatch;
+
+/// Orders the enabled mods the server loads so every mod loads after the mods its `requiredAddons` come from.
+/// Mods keep their current order wherever the dependencies allow it, the sorted mods take over
+/// the positions the enabled mods had so disabled ones stay where they are.
+/// `game_addons` holds the lowercase names of everything the game provides, those are never missing.
+pub fn sort_load_order(
+    items: &[PresetItem],
+    patches: &HashMap<i64, Vec<CfgPatch>>,
+    game_addons: &HashSet<String>,
+) -> LoadOrderReport {
+    let mut mods = items
+        .iter()
+        .filter(|item| item.enabled && !item.blacklisted && !item.optional)
+        .collect::<Vec<_>>();
+    mods.sort_by_key(|item| item.position);
+
+    let patches_of = |item: &PresetItem| {
+        patches
+            .get(&item.published_file_id)
+            .map(|patches| patches.as_slice())
+            .unwrap_or_default()
+    };
+
+    // the first mod providing an addon wins, just like the game does it
+    let mut providers: HashMap<String, usize> = HashMap::new();
+
+    for (i, item) in mods.iter().enumerate() {
+        for patch in patches_of(item) {
+            providers.entry(patch.name.to_lowercase()).or_insert(i);
+        }
+    }
+
+    let mut dependencies = vec![HashSet::new(); mods.len()];
+    let mut missing = Vec::new();
+
+    for (i, item) in mods.iter().enumerate() {
+        let mut reported = HashSet::new();
+
+        for addon in patches_of(item).iter().flat_map(|patch| &patch.required_addons) {
+            let key = addon.to_lowercase();
+
+            if game_addons.contains(&key) {
+                continue;
+            }
+
+            match providers.get(&key) {
+                Some(&j) if j != i => {
+                    dependencies[i].insert(j);
+                }
+                Some(_) => {}
+                None => {
+                    if reported.insert(key) {
+                        missing.push(MissingDependency {
+                            published_file_id: item.published_file_id,
+                            name: item.name.clone(),
+                            addon: addon.clone(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    let cycles = find_cycles(&dependencies)
+        .into_iter()
+        .map(|i| mods[i].name.clone())
+        .collect::<Vec<_>>();
+
+    let mut placed = vec![false; mods.len()];
+    let mut order = Vec::with_capacity(mods.len());
+
+    while order.len() < mods.len() {
+        let ready = (0..mods.len()).find(|&i| !placed[i] && dependencies[i].iter().all(|&j| placed[j]));
+
+        // stuck on a cycle, the first mod of it in the current order goes first
+        let next = ready.unwrap_or_else(|| (0..mods.len()).find(|&i| !placed[i]).unwrap_or_default());
+
+        placed[next] = true;
+        order.push(next);
+    }
+
+    let positions = mods.iter().map(|item| item.position).collect::<Vec<_>>();
+
+    let order = order
+        .into_iter()
+        .zip(positions)
+        .map(|(i, position)| LoadOrderItem {
+            id: mods[i].id,
+            published_file_id: mods[i].published_file_id,
+            name: mods[i].name.clone(),
+            position,
+            previous_position: mods[i].position,
+        })
+        .collect::<Vec<_>>();
+
+    LoadOrderReport {
+        changed: order.iter().any(|item| item.position != item.previous_position),
+        order,
+        cycles,
+        missing,
+    }
+}
+
+/// The mods that can reach themselves through their dependencies.
+fn find_cycles(dependencies: &[HashSet<usize>]) -> Vec<usize> {
+    (0..dependencies.len())
+        .filter(|&start| {
+            let mut stack = dependencies[start].iter().copied().collect::<Vec<_>>();
+            let mut seen = HashSet::new();
+
+            while let Some(i) = stack.pop() {
+                if i == start {
+                    return true;
+                }
+
+                if seen.insert(i) {
+                    stack.extend(dependencies[i].iter().copied());
+                }
+            }
+
+            false
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: i64, name: &str, position: i64) -> PresetItem {
+        PresetItem {
+            id,
+            name: name.to_string(),
+            published_file_id: id * 100,
+            position,
+            enabled: true,
+            blacklisted: false,
+            server_mod: false,
+            optional: false,
+            exists: true,
+        }
+    }
+
+    /// Every mod provides an addon named like itself and requires the given addons.
+    fn patches(items: &[(&PresetItem, Vec<&str>)]) -> HashMap<i64, Vec<CfgPatch>> {
+        items
+            .iter()
+            .map(|(item, required)| {
+                let patch = CfgPatch {
+                    name: item.name.clone(),
+                    required_addons: required.iter().map(|addon| addon.to_string()).collect(),
+                };
+
+                (item.published_file_id, vec![patch])
+            })
+            .collect()
+    }
+
+    fn order(report: &LoadOrderReport) -> Vec<(&str, i64, i64)> {
+        report
+            .order
+            .iter()
+            .map(|item| (item.name.as_str(), item.position, item.previous_position))
+            .collect()
+    }
+
+    #[test]
+    fn loads_dependencies_first() {
+        let ace = item(1, "ace", 1);
+        let cba = item(2, "cba_main", 2);
+        let rhs = item(3, "rhs", 3);
+
+        let patches = patches(&[(&ace, vec!["CBA_Main"]), (&cba, vec![]), (&rhs, vec![])]);
+        let report = sort_load_order(&[ace, cba, rhs], &patches, &HashSet::new());
+
+        assert!(report.changed);
+        assert_eq!(order(&report), vec![("cba_main", 1, 2), ("ace", 2, 1), ("rhs", 3, 3)]);
+        assert!(report.cycles.is_empty());
+        assert!(report.missing.is_empty());
+    }
+
+    #[test]
+    fn keeps_cycles_in_their_order() {
+        let a = item(1, "a", 1);
+        let b = item(2, "b", 2);
+        let c = item(3, "c", 3);
+
+        let patches = patches(&[(&a, vec!["b"]), (&b, vec!["a"]), (&c, vec!["a"])]);
+        let report = sort_load_order(&[a, b, c], &patches, &HashSet::new());
+
+        assert!(!report.changed);
+        assert_eq!(order(&report), vec![("a", 1, 1), ("b", 2, 2), ("c", 3, 3)]);
+        assert_eq!(report.cycles, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn takes_game_addons_as_provided() {
+        let mod_ = item(1, "gm_addon", 1);
+
+        let patches = patches(&[(&mod_, vec!["A3_Data_F", "gm_core", "missing_addon", "MISSING_ADDON"])]);
+        let game_addons = ["a3_data_f", "gm_core"]
+            .into_iter()
+            .map(|addon| addon.to_string())
+            .collect();
+
+        let report = sort_load_order(&[mod_], &patches, &game_addons);
+
+        assert!(!report.changed);
+        assert_eq!(
+            report.missing,
+            vec![MissingDependency {
+                published_file_id: 100,
+                name: "gm_addon".to_string(),
+                addon: "missing_addon".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn leaves_disabled_and_optional_mods_in_place() {
+        let ace = item(1, "ace", 1);
+        let disabled = PresetItem {
+            enabled: false,
+            ..item(2, "disabled", 2)
+        };
+        let optional = PresetItem {
+            optional: true,
+            ..item(3, "optional", 3)
+        };
+        let cba = item(4, "cba_main", 4);
+
+        let patches = patches(&[
+            (&ace, vec!["cba_main"]),
+            (&disabled, vec![]),
+            (&optional, vec![]),
+            (&cba, vec![]),
+        ]);
+        let report = sort_load_order(&[ace, disabled, optional, cba], &patches, &HashSet::new());
+
+        // the sorted mods swap the positions the enabled ones had, the others keep theirs
+        assert_eq!(order(&report), vec![("cba_main", 1, 4), ("ace", 4, 1)]);
+    }
+}
@@ -0,0 +1,377 @@
+use std::{
+    io::{Cursor, Read},
+    path::{Component, Path},
+    time::UNIX_EPOCH,
+};
+
+use pbo::{PboReader, PboWriter};
+
+use crate::config::{self, Config};
+
+/// How much a zipped mission may unpack to, a few hundred megabytes is already a huge mission.
+const MAX_MISSION_SIZE: u64 = 512 * 1024 * 1024;
+
+/// What we can tell about a mission from its PBO, missing values were not set by the author.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MissionMetadata {
+    /// The file name without `.pbo`, which is what the server config refers to.
+    pub name: String,
+    pub world: String,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub min_players: Option<i64>,
+    pub max_players: Option<i64>,
+    pub game_type: Option<String>,
+    /// The addons the mission needs, as listed in `mission.sqm`.
+    pub addons: Vec<String>,
+}
+
+/// Splits a mission file name like `co10_escape.Altis.pbo` into the mission and world name.
+pub fn parse_mission_file_name(file_name: &str) -> Result<(String, String), String> {
+    if file_name.contains(['/', '\\']) || file_name.contains("..") {
+        return Err(format!("{} is not a valid file name", file_name));
+    }
+
+    let Some(stem) = file_name
+        .len()
+        .checked_sub(4)
+        .filter(|i| file_name.is_char_boundary(*i) && file_name[*i..].eq_ignore_ascii_case(".pbo"))
+        .map(|i| &file_name[..i])
+    else {
+        return Err(format!("{} is not a PBO", file_name));
+    };
+
+    let Some((mission, world)) = stem.rsplit_once('.') else {
+        return Err(format!("{} should be named <mission>.<world>.pbo", file_name));
+    };
+
+    if mission.trim().is_empty() || world.trim().is_empty() {
+        return Err(format!("{} should be named <mission>.<world>.pbo", file_name));
+    }
+
+    if !world.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(format!("{} is not a valid world name", world));
+    }
+
+    Ok((mission.to_string(), world.to_string()))
+}
+
+/// Reads the metadata of a packed mission from `description.ext` and `mission.sqm`.
+/// Fails if the file is not a PBO named `<mission>.<world>.pbo` or it has no readable `mission.sqm`.
+pub fn read_mission(file_name: &str, data: &[u8]) -> Result<MissionMetadata, String> {
+    let (_, world) = parse_mission_file_name(file_name)?;
+
+    let mut reader =
+        PboReader::new(Cursor::new(data)).map_err(|e| format!("{} is not a valid PBO: {}", file_name, e))?;
+
+    let sqm = reader
+        .read("mission.sqm")
+        .map_err(|e| format!("Failed to read mission.sqm from {}: {}", file_name, e))?
+        .ok_or_else(|| format!("{} does not contain a mission.sqm", file_name))?;
+
+    let sqm = config::parse_bytes(&sqm).map_err(|e| format!("Failed to parse mission.sqm of {}: {}", file_name, e))?;
+
+    // description.ext is optional and often relies on macros we can't resolve, so it only fills in what it can
+    let description = reader
+        .read("description.ext")
+        .ok()
+        .flatten()
+        .and_then(|description| config::parse_bytes(&description).ok())
+        .unwrap_or_default();
+
+    let scenario = sqm.class("ScenarioData");
+    let intel = sqm.class("Mission").and_then(|mission| mission.class("Intel"));
+    let header = description
+        .class("Header")
+        .or_else(|| scenario.and_then(|scenario| scenario.class("Header")));
+
+    let title = string(Some(&description), "onLoadName")
+        .or_else(|| string(Some(&description), "briefingName"))
+        .or_else(|| string(intel, "briefingName"))
+        .or_else(|| string(scenario, "onLoadName"));
+
+    let author = string(Some(&description), "author").or_else(|| string(scenario, "author"));
+
+    let addons = sqm
+        .get("addons")
+        .and_then(|addons| addons.as_array())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|addon| addon.as_str())
+        .map(|addon| addon.to_string())
+        .collect();
+
+    Ok(MissionMetadata {
+        name: file_name[..file_name.len() - 4].to_string(),
+        world,
+        title,
+        author,
+        min_players: header
+            .and_then(|header| header.get("minPlayers"))
+            .and_then(|v| v.as_i64()),
+        max_players: header
+            .and_then(|header| header.get("maxPlayers"))
+            .and_then(|v| v.as_i64()),
+        game_type: string(header, "gameType"),
+        addons,
+    })
+}
+
+/// Reads the metadata of a mission in `mpmissions`, see `read_mission`.
+pub fn read_mission_file(path: &Path) -> Result<MissionMetadata, String> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
+
+    read_mission(&file_name, &data)
+}
+
+/// Packs a zipped mission folder into a PBO and returns it along with its file name.
+/// The zip can either hold the folder itself, named `<mission>.<world>` like Eden does,
+/// or just its contents, then the zip has to be named that way.
+pub fn pack_mission_zip(file_name: &str, data: &[u8]) -> Result<(String, Vec<u8>), String> {
+    pack_zip(file_name, data, MAX_MISSION_SIZE)
+}
+
+fn pack_zip(file_name: &str, data: &[u8], max_size: u64) -> Result<(String, Vec<u8>), String> {
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(data)).map_err(|e| format!("{} is not a valid zip: {}", file_name, e))?;
+
+    let mut files = Vec::new();
+
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
+
+        if entry.is_dir() {
+            continue;
+        }
+
+        // entries trying to escape the folder are left out
+        let Some(path) = entry.enclosed_name() else {
+            continue;
+        };
+
+        let components = path
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        // resource forks added by macOS
+        if components.first().is_some_and(|first| first == "__MACOSX") {
+            continue;
+        }
+
+        files.push((i, components));
+    }
+
+    let is_sqm = |components: &Vec<String>| {
+        components
+            .last()
+            .is_some_and(|last| last.eq_ignore_ascii_case("mission.sqm"))
+    };
+
+    let Some(depth) = files
+        .iter()
+        .filter(|(_, components)| is_sqm(components))
+        .map(|(_, components)| components.len())
+        .min()
+    else {
+        return Err(format!("{} does not contain a mission.sqm", file_name));
+    };
+
+    let mut roots = files
+        .iter()
+        .filter(|(_, components)| is_sqm(components) && components.len() == depth)
+        .map(|(_, components)| components[..depth - 1].to_vec());
+
+    let root = roots.next().unwrap_or_default();
+
+    if roots.next().is_some() {
+        return Err(format!("{} contains more than one mission", file_name));
+    }
+
+    let name = match root.last() {
+        Some(folder) => folder.clone(),
+        None => file_name
+            .len()
+            .checked_sub(4)
+            .filter(|i| file_name.is_char_boundary(*i) && file_name[*i..].eq_ignore_ascii_case(".zip"))
+            .map(|i| file_name[..i].to_string())
+            .unwrap_or_else(|| file_name.to_string()),
+    };
+
+    let pbo_name = format!("{}.pbo", name);
+    parse_mission_file_name(&pbo_name)
+        .map_err(|_| format!("The mission folder {} should be named <mission>.<world>", name))?;
+
+    let timestamp = UNIX_EPOCH
+        .elapsed()
+        .map(|elapsed| elapsed.as_secs() as u32)
+        .unwrap_or_default();
+
+    let mut writer = PboWriter::new();
+    let mut remaining = max_size;
+
+    for (i, components) in files.iter().filter(|(_, components)| components.starts_with(&root)) {
+        let mut entry = archive
+            .by_index(*i)
+            .map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
+
+        // the sizes in the zip can't be trusted, so stop reading once the limit is exceeded
+        let mut content = Vec::new();
+        (&mut entry)
+            .take(remaining + 1)
+            .read_to_end(&mut content)
+            .map_err(|e| format!("Failed to extract {} from {}: {}", entry.name(), file_name, e))?;
+
+        remaining = remaining
+            .checked_sub(content.len() as u64)
+            .ok_or_else(|| format!("{} is too large to pack", file_name))?;
+
+        writer.add_file(components[root.len()..].join("\\"), timestamp, content);
+    }
+
+    let mut packed = Vec::new();
+    writer
+        .write(&mut packed)
+        .map_err(|e| format!("Failed to pack {}: {}", pbo_name, e))?;
+
+    Ok((pbo_name, packed))
+}
+
+fn string(config: Option<&Config>, name: &str) -> Option<String> {
+    config
+        .and_then(|config| config.get(name))
+        .and_then(|value| value.as_str())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+
+        for (name, content) in files {
+            writer.start_file(*name, Default::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn entries(packed: &[u8]) -> Vec<String> {
+        let reader = PboReader::new(Cursor::new(packed)).unwrap();
+        let mut names = reader
+            .entries()
+            .iter()
+            .map(|entry| entry.name.clone())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn parses_mission_file_names() {
+        assert_eq!(
+            parse_mission_file_name("co10_escape.Altis.PBO"),
+            Ok(("co10_escape".to_string(), "Altis".to_string()))
+        );
+        assert_eq!(
+            parse_mission_file_name("my.mission.Tanoa.pbo"),
+            Ok(("my.mission".to_string(), "Tanoa".to_string()))
+        );
+
+        assert!(parse_mission_file_name("co10_escape.pbo").is_err());
+        assert!(parse_mission_file_name(".Altis.pbo").is_err());
+        assert!(parse_mission_file_name("co10_escape.Altis.zip").is_err());
+        assert!(parse_mission_file_name("co10 escape.Al tis.pbo").is_err());
+        assert!(parse_mission_file_name("../co10_escape.Altis.pbo").is_err());
+        assert!(parse_mission_file_name("missions/co10_escape.Altis.pbo").is_err());
+        assert!(parse_mission_file_name("missions\\co10_escape.Altis.pbo").is_err());
+    }
+
+    #[test]
+    fn packs_the_shallowest_mission_folder() {
+        let data = zip(&[
+            ("export/co10_escape.Altis/mission.sqm", "version = 53;"),
+            ("export/co10_escape.Altis/scripts/init.sqf", "hint 'hi';"),
+            ("export/co10_escape.Altis/backup/mission.sqm", "version = 52;"),
+            ("export/readme.txt", "not part of the mission"),
+            ("__MACOSX/export/co10_escape.Altis/._mission.sqm", ""),
+        ]);
+
+        let (name, packed) = pack_mission_zip("export.zip", &data).unwrap();
+
+        assert_eq!(name, "co10_escape.Altis.pbo");
+        assert_eq!(
+            entries(&packed),
+            vec!["backup\\mission.sqm", "mission.sqm", "scripts\\init.sqf"]
+        );
+    }
+
+    #[test]
+    fn names_flat_zips_after_the_zip() {
+        let data = zip(&[("mission.sqm", "version = 53;")]);
+
+        let (name, packed) = pack_mission_zip("co10_escape.Altis.zip", &data).unwrap();
+
+        assert_eq!(name, "co10_escape.Altis.pbo");
+        assert_eq!(entries(&packed), vec!["mission.sqm"]);
+
+        let error = pack_mission_zip("co10_escape.zip", &data).unwrap_err();
+        assert!(error.contains("should be named <mission>.<world>"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_zips_without_one_mission() {
+        let data = zip(&[("co10_escape.Altis/init.sqf", "")]);
+        let error = pack_mission_zip("missions.zip", &data).unwrap_err();
+        assert!(error.contains("does not contain a mission.sqm"), "{}", error);
+
+        let data = zip(&[
+            ("co10_escape.Altis/mission.sqm", ""),
+            ("co12_escape.Tanoa/mission.sqm", ""),
+        ]);
+        let error = pack_mission_zip("missions.zip", &data).unwrap_err();
+        assert!(error.contains("more than one mission"), "{}", error);
+    }
+
+    #[test]
+    fn leaves_out_entries_escaping_the_folder() {
+        let data = zip(&[
+            ("co10_escape.Altis/mission.sqm", "version = 53;"),
+            ("co10_escape.Altis/../../evil.sqf", ""),
+            ("/etc/passwd", ""),
+        ]);
+
+        let (_, packed) = pack_mission_zip("mission.zip", &data).unwrap();
+
+        assert_eq!(entries(&packed), vec!["mission.sqm"]);
+    }
+
+    #[test]
+    fn rejects_missions_unpacking_too_large() {
+        let data = zip(&[
+            ("co10_escape.Altis/mission.sqm", "version = 53;"),
+            ("co10_escape.Altis/music.ogg", &"a".repeat(1024)),
+        ]);
+
+        let error = pack_zip("mission.zip", &data, 1024).unwrap_err();
+        assert_eq!(error, "mission.zip is too large to pack");
+
+        assert!(pack_zip("mission.zip", &data, 2048).is_ok());
+    }
+}
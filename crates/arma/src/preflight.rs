@@ -0,0 +1,453 @@
+use std::{net::UdpSocket, path::Path};
+
+use api_schema::response::{KeyReport, PreflightReport, Preset, Severity};
+use sysinfo::{DiskExt, System, SystemExt};
+
+use crate::{config, get_mod_path, key_report, mod_exists, Arma3, DEFAULT_CONFIG};
+
+const MIN_FREE_SPACE: u64 = 512 * 1024 * 1024;
+const LOW_FREE_SPACE: u64 = 5 * 1024 * 1024 * 1024;
+
+/// Validates everything needed to start the server up front,
+/// a report containing errors means the server should not be started.
+/// The config is checked the way the server would get it, including the mission rotation.
+/// `managed_keys` are the keys `install_keys` installed last time.
+pub fn preflight(preset: &Preset, server: &Arma3, managed_keys: &[String]) -> PreflightReport {
+    let config_file = &server.config_file;
+
+    let mut report = PreflightReport::default();
+
+    let arma_path = paths::get_arma_path();
+
+    match &arma_path {
+        Some(_) => report.push("Server", Severity::Ok, "Arma 3 Server is installed"),
+        None => report.push("Server", Severity::Error, "Arma 3 Server is not installed"),
+    }
+
+    check_mods(&mut report, preset);
+
+    let config = std::fs::read_to_string(config_file).unwrap_or_else(|_| DEFAULT_CONFIG.to_string());
+
+    // the server gets the config with the managed settings spliced in, the parser only has to understand it for the checks
+    let config = match server.effective_config(&config) {
+        Ok(config) => match config::parse(&config) {
+            Ok(config) => {
+                report.push(
+                    "Config",
+                    Severity::Ok,
+                    format!("{} parsed successfully", config_file.display()),
+                );
+                Some(config)
+            }
+            Err(e) => {
+                report.push(
+                    "Config",
+                    Severity::Warning,
+                    format!(
+                        "{} could not be fully read, the missions and ports it sets are not checked: {}",
+                        config_file.display(),
+                        e
+                    ),
+                );
+                None
+            }
+        },
+        Err(e) => {
+            report.push(
+                "Config",
+                Severity::Error,
+                format!("{} could not be parsed: {}", config_file.display(), e),
+            );
+            None
+        }
+    };
+
+    if let (Some(config), Some(arma_path)) = (&config, &arma_path) {
+        check_missions(&mut report, config, &arma_path.join("mpmissions"));
+    }
+
+    let battleye = config
+        .as_ref()
+        .and_then(|config| config.get("BattlEye"))
+        .and_then(|value| value.as_i64())
+        .unwrap_or(1)
+        == 1;
+
+    check_ports(&mut report, server.port, battleye);
+
+    let verify_signatures = config
+        .as_ref()
+        .and_then(|config| config.get("verifySignatures"))
+        .and_then(|value| value.as_i64())
+        .unwrap_or(0);
+
+    // only the strict mode kicks players for unsigned addons, so only then it is worth reading every signature
+    if verify_signatures == 2 {
+        check_signatures(&mut report, &key_report(preset, managed_keys));
+    }
+
+    if let Some(arma_path) = &arma_path {
+        check_disk_space(&mut report, arma_path);
+    }
+
+    report
+}
+
+fn check_mods(report: &mut PreflightReport, preset: &Preset) {
+    let enabled = preset.items.iter().filter(|item| item.enabled).collect::<Vec<_>>();
+
+    let blacklisted = enabled
+        .iter()
+        .filter(|item| item.blacklisted)
+        .map(|item| item.name.clone())
+        .collect::<Vec<_>>();
+
+    if !blacklisted.is_empty() {
+        report.push(
+            "Blacklist",
+            Severity::Warning,
+            format!(
+                "Enabled but blacklisted, these will not be loaded: {}",
+                blacklisted.join(", ")
+            ),
+        );
+    }
+
+    let enabled = enabled.into_iter().filter(|item| !item.blacklisted).collect::<Vec<_>>();
+
+    let missing = enabled
+        .iter()
+        .filter(|item| !mod_exists(item.published_file_id))
+        .map(|item| item.name.clone())
+        .collect::<Vec<_>>();
+
+    if missing.is_empty() {
+        report.push(
+            "Mods",
+            Severity::Ok,
+            format!("All {} mods are downloaded", enabled.len()),
+        );
+    } else {
+        report.push("Mods", Severity::Error, format!("Missing mods: {}", missing.join(", ")));
+    }
+
+    let without_keys = enabled
+        .iter()
+        .filter(|item| mod_exists(item.published_file_id))
+        .filter(|item| !has_keys(&get_mod_path(item.published_file_id).join("keys")))
+        .map(|item| item.name.clone())
+        .collect::<Vec<_>>();
+
+    if without_keys.is_empty() {
+        report.push("Keys", Severity::Ok, "Every mod provides a key");
+    } else {
+        report.push(
+            "Keys",
+            Severity::Warning,
+            format!("No keys found for: {}", without_keys.join(", ")),
+        );
+    }
+}
+
+fn has_keys(keys_path: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(keys_path) else {
+        return false;
+    };
+
+    entries.flatten().any(|entry| {
+        entry
+            .path()
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("bikey"))
+    })
+}
+
+fn check_signatures(report: &mut PreflightReport, keys: &KeyReport) {
+    let unsigned = keys
+        .mods
+        .iter()
+        .filter(|item| !item.unsigned.is_empty())
+        .map(|item| format!("{} ({})", item.name, item.unsigned.len()))
+        .collect::<Vec<_>>();
+
+    let unknown = keys
+        .mods
+        .iter()
+        .filter(|item| !item.unknown_signatures.is_empty())
+        .map(|item| item.name.clone())
+        .collect::<Vec<_>>();
+
+    if unsigned.is_empty() && unknown.is_empty() {
+        report.push(
+            "Signatures",
+            Severity::Ok,
+            "Every addon is signed with an installed key",
+        );
+    }
+
+    if !unsigned.is_empty() {
+        report.push(
+            "Signatures",
+            Severity::Warning,
+            format!(
+                "Unsigned addons, players loading them are kicked: {}",
+                unsigned.join(", ")
+            ),
+        );
+    }
+
+    if !unknown.is_empty() {
+        report.push(
+            "Signatures",
+            Severity::Warning,
+            format!("Addons signed with a key that is not installed: {}", unknown.join(", ")),
+        );
+    }
+
+    // copies of the same key don't matter, only the first of differing keys is installed
+    let conflicting = keys
+        .duplicates
+        .iter()
+        .filter(|duplicate| !duplicate.identical)
+        .map(|duplicate| format!("{} ({})", duplicate.key, duplicate.mods.join(", ")))
+        .collect::<Vec<_>>();
+
+    if !conflicting.is_empty() {
+        report.push(
+            "Keys",
+            Severity::Warning,
+            format!("Different keys with the same name: {}", conflicting.join(", ")),
+        );
+    }
+}
+
+fn check_missions(report: &mut PreflightReport, config: &config::Config, missions_path: &Path) {
+    let Some(missions) = config.class("Missions") else {
+        return;
+    };
+
+    let available = std::fs::read_dir(missions_path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_lowercase())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let mut missing = Vec::new();
+    let mut count = 0;
+
+    for (name, mission) in missions.classes() {
+        let Some(template) = mission.get("template").and_then(|template| template.as_str()) else {
+            missing.push(format!("{} (no template)", name));
+            continue;
+        };
+
+        count += 1;
+
+        let template = template.to_lowercase();
+        let packed = format!("{}.pbo", template);
+
+        if !available.iter().any(|file| *file == template || *file == packed) {
+            missing.push(template);
+        }
+    }
+
+    if missing.is_empty() {
+        report.push(
+            "Missions",
+            Severity::Ok,
+            format!("All {} missions in the rotation exist", count),
+        );
+    } else {
+        report.push(
+            "Missions",
+            Severity::Error,
+            format!("Missing from mpmissions: {}", missing.join(", ")),
+        );
+    }
+}
+
+fn check_ports(report: &mut PreflightReport, port: u16, battleye: bool) {
+    // the steam query port is always the game port + 1, BattlEye uses the game port + 4
+    let mut ports = vec![("game", port), ("query", port + 1)];
+
+    if battleye {
+        ports.push(("BattlEye", port + 4));
+    }
+
+    let in_use = ports
+        .iter()
+        .filter(|(_, port)| UdpSocket::bind(("0.0.0.0", *port)).is_err())
+        .map(|(name, port)| format!("{} {}", name, port))
+        .collect::<Vec<_>>();
+
+    if in_use.is_empty() {
+        let ports = ports.iter().map(|(_, port)| port.to_string()).collect::<Vec<_>>();
+        report.push("Ports", Severity::Ok, format!("Ports {} are free", ports.join(", ")));
+    } else {
+        report.push("Ports", Severity::Error, format!("Ports in use: {}", in_use.join(", ")));
+    }
+}
+
+fn check_disk_space(report: &mut PreflightReport, arma_path: &Path) {
+    let mut system = System::new();
+    system.refresh_disks_list();
+
+    // the disk with the longest mount point containing the install is the one it lives on
+    let Some(disk) = system
+        .disks()
+        .iter()
+        .filter(|disk| arma_path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+    else {
+        report.push("Disk", Severity::Warning, "Unable to determine free disk space");
+        return;
+    };
+
+    let available = disk.available_space();
+    let message = format!("{} MiB available", available / 1024 / 1024);
+
+    if available < MIN_FREE_SPACE {
+        report.push("Disk", Severity::Error, message);
+    } else if available < LOW_FREE_SPACE {
+        report.push("Disk", Severity::Warning, message);
+    } else {
+        report.push("Disk", Severity::Ok, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api_schema::response::{DuplicateKey, ModKeyCheck, UnknownSignature};
+
+    use super::*;
+
+    fn messages(report: &PreflightReport, severity: Severity) -> Vec<String> {
+        report
+            .checks
+            .iter()
+            .filter(|check| check.severity == severity)
+            .map(|check| check.message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn checks_missions_of_the_rotation() {
+        let missions_path = tempfile::tempdir().unwrap();
+        std::fs::write(missions_path.path().join("co10_Escape.Altis.pbo"), b"").unwrap();
+        std::fs::create_dir(missions_path.path().join("dev.Stratis")).unwrap();
+
+        let config = config::parse(
+            r#"class Missions
+            {
+                class Packed { template = "co10_escape.altis"; };
+                class Unpacked { template = "dev.Stratis"; };
+                class Gone { template = "gone.Tanoa"; };
+                class Broken { difficulty = "Regular"; };
+            };"#,
+        )
+        .unwrap();
+
+        let mut report = PreflightReport::default();
+        check_missions(&mut report, &config, missions_path.path());
+
+        assert_eq!(
+            messages(&report, Severity::Error),
+            vec!["Missing from mpmissions: gone.tanoa, Broken (no template)"]
+        );
+
+        let config = config::parse("class Missions { class Packed { template = \"co10_escape.Altis\"; }; };").unwrap();
+        let mut report = PreflightReport::default();
+        check_missions(&mut report, &config, missions_path.path());
+
+        assert_eq!(
+            messages(&report, Severity::Ok),
+            vec!["All 1 missions in the rotation exist"]
+        );
+
+        // without a rotation the server picks the mission, nothing to check
+        let mut report = PreflightReport::default();
+        check_missions(&mut report, &config::Config::new(), missions_path.path());
+
+        assert!(report.checks.is_empty());
+    }
+
+    #[test]
+    fn checks_ports() {
+        let socket = UdpSocket::bind(("0.0.0.0", 0)).unwrap();
+        let port = socket.local_addr().unwrap().port();
+
+        let mut report = PreflightReport::default();
+        check_ports(&mut report, port, false);
+        assert_eq!(
+            messages(&report, Severity::Error),
+            vec![format!("Ports in use: game {}", port)]
+        );
+
+        // BattlEye takes the game port + 4, only when it is enabled
+        let mut report = PreflightReport::default();
+        check_ports(&mut report, port - 4, true);
+        assert_eq!(
+            messages(&report, Severity::Error),
+            vec![format!("Ports in use: BattlEye {}", port)]
+        );
+
+        let mut report = PreflightReport::default();
+        check_ports(&mut report, port - 4, false);
+        assert!(messages(&report, Severity::Error).is_empty());
+    }
+
+    #[test]
+    fn checks_signatures() {
+        let mut report = PreflightReport::default();
+        check_signatures(&mut report, &KeyReport::default());
+
+        assert_eq!(
+            messages(&report, Severity::Ok),
+            vec!["Every addon is signed with an installed key"]
+        );
+
+        let check = |name: &str, unsigned: Vec<&str>, unknown: Vec<&str>| ModKeyCheck {
+            published_file_id: 1,
+            name: name.to_string(),
+            keys: vec![],
+            unsigned: unsigned.into_iter().map(|pbo| pbo.to_string()).collect(),
+            unknown_signatures: unknown
+                .into_iter()
+                .map(|pbo| UnknownSignature {
+                    pbo: pbo.to_string(),
+                    authority: "someone".to_string(),
+                })
+                .collect(),
+        };
+
+        let duplicate = |key: &str, identical: bool| DuplicateKey {
+            key: key.to_string(),
+            mods: vec!["CBA".to_string(), "ACE".to_string()],
+            identical,
+        };
+
+        let keys = KeyReport {
+            mods: vec![
+                check("CBA", vec!["a.pbo", "b.pbo"], vec![]),
+                check("ACE", vec![], vec!["c.pbo"]),
+            ],
+            duplicates: vec![duplicate("cba.bikey", true), duplicate("ace.bikey", false)],
+        };
+
+        let mut report = PreflightReport::default();
+        check_signatures(&mut report, &keys);
+
+        assert!(messages(&report, Severity::Ok).is_empty());
+        assert_eq!(
+            messages(&report, Severity::Warning),
+            vec![
+                "Unsigned addons, players loading them are kicked: CBA (2)",
+                "Addons signed with a key that is not installed: ACE",
+                "Different keys with the same name: ace.bikey (CBA, ACE)",
+            ]
+        );
+    }
+}
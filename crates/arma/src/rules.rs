@@ -0,0 +1,362 @@
+use std::{collections::HashMap, net::SocketAddr, time::Duration};
+
+use anyhow::{anyhow, bail, Context};
+use api_schema::{
+    a2s::{ArmaDifficulty, ArmaDlc, ArmaMod, ArmaRules, ServerOS},
+    response::{LoadedPresetReport, Preset},
+};
+use tokio::net::UdpSocket;
+
+const SINGLE_PACKET: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+const SPLIT_PACKET: [u8; 4] = [0xFE, 0xFF, 0xFF, 0xFF];
+const A2S_RULES: u8 = 0x56;
+const S2C_CHALLENGE: u8 = 0x41;
+const S2A_RULES: u8 = 0x45;
+
+const AI_LEVELS: [&str; 4] = ["Novice", "Normal", "Expert", "Custom"];
+
+/// The official DLC in the order of their flags, newer ones are reported by their bit.
+const DLCS: [&str; 11] = [
+    "Karts",
+    "Marksmen",
+    "Helicopters",
+    "Zeus",
+    "Apex",
+    "Jets",
+    "Laws of War",
+    "Malden",
+    "Tac-Ops",
+    "Tanks",
+    "Contact",
+];
+
+/// Queries A2S_RULES and returns the raw keys and values, Arma's aren't valid strings.
+pub async fn query_rules(
+    socket: &UdpSocket,
+    addr: SocketAddr,
+    timeout: Duration,
+) -> anyhow::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    tokio::time::timeout(timeout, query(socket, addr))
+        .await
+        .map_err(|_| anyhow!("A2S_RULES of {} timed out", addr))?
+}
+
+async fn query(socket: &UdpSocket, addr: SocketAddr) -> anyhow::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    // the first request is answered with a challenge that has to be sent back
+    let mut challenge = [0xFF; 4];
+
+    loop {
+        let request = [&SINGLE_PACKET[..], &[A2S_RULES], &challenge].concat();
+        socket.send_to(&request, addr).await?;
+
+        let response = receive(socket, addr).await?;
+
+        match response.first() {
+            Some(&S2C_CHALLENGE) if response.len() >= 5 && response[1..5] != challenge => {
+                challenge.copy_from_slice(&response[1..5]);
+            }
+            Some(&S2A_RULES) => return parse_rules(&response[1..]),
+            _ => bail!("Unexpected A2S_RULES response from {}", addr),
+        }
+    }
+}
+
+/// Receives a whole response without its header, putting split packets back together.
+async fn receive(socket: &UdpSocket, addr: SocketAddr) -> anyhow::Result<Vec<u8>> {
+    let mut buffer = [0u8; 1400];
+    let mut parts: HashMap<u8, Vec<u8>> = HashMap::new();
+
+    loop {
+        let (length, from) = socket.recv_from(&mut buffer).await?;
+        let packet = &buffer[..length];
+
+        // answers to earlier queries that timed out may still come in
+        if from != addr || length < 4 {
+            continue;
+        }
+
+        if packet[..4] == SINGLE_PACKET {
+            return Ok(packet[4..].to_vec());
+        }
+
+        if packet[..4] != SPLIT_PACKET || length < 12 {
+            continue;
+        }
+
+        // id, total, number and the size of the packets, Arma doesn't compress them
+        let id = u32::from_le_bytes([packet[4], packet[5], packet[6], packet[7]]);
+        let total = packet[8];
+        let number = packet[9];
+
+        if id & 0x8000_0000 != 0 {
+            bail!("Compressed A2S responses aren't supported");
+        }
+
+        parts.insert(number, packet[12..].to_vec());
+
+        if parts.len() == total as usize {
+            let response = (0..total)
+                .map(|number| parts.remove(&number).context("Split A2S response is missing a packet"))
+                .collect::<anyhow::Result<Vec<_>>>()?
+                .concat();
+
+            if response.len() < 4 || response[..4] != SINGLE_PACKET {
+                bail!("Invalid split A2S response");
+            }
+
+            return Ok(response[4..].to_vec());
+        }
+    }
+}
+
+fn parse_rules(data: &[u8]) -> anyhow::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    let mut reader = Reader::new(data);
+    let count = u16::from_le_bytes([reader.u8()?, reader.u8()?]);
+
+    (0..count)
+        .map(|_| Ok((reader.cstring()?.to_vec(), reader.cstring()?.to_vec())))
+        .collect()
+}
+
+/// Arma spreads a binary blob over rules keyed by two bytes, the 1-based index of the chunk and the number of chunks.
+/// Rules with other keys are skipped.
+pub fn decode_rules(rules: &[(Vec<u8>, Vec<u8>)]) -> anyhow::Result<ArmaRules> {
+    let mut chunks = rules
+        .iter()
+        .filter(|(key, _)| key.len() == 2)
+        .map(|(key, value)| (key[0], key[1], value))
+        .collect::<Vec<_>>();
+
+    chunks.sort_by_key(|(index, _, _)| *index);
+
+    let total = chunks.first().map(|(_, total, _)| *total as usize).unwrap_or_default();
+
+    if total == 0
+        || chunks.len() != total
+        || chunks
+            .iter()
+            .enumerate()
+            .any(|(i, (index, _, _))| *index as usize != i + 1)
+    {
+        bail!("The rules are missing chunks");
+    }
+
+    let data = chunks
+        .iter()
+        .map(|(_, _, value)| value.as_slice())
+        .collect::<Vec<_>>()
+        .concat();
+
+    decode(&unescape(&data)?)
+}
+
+/// 0x01 0x01 is 0x01, 0x01 0x02 is 0x00 and 0x01 0x03 is 0xFF, so the chunks fit into strings.
+pub fn unescape(data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut unescaped = Vec::with_capacity(data.len());
+    let mut bytes = data.iter();
+
+    while let Some(&byte) = bytes.next() {
+        if byte != 0x01 {
+            unescaped.push(byte);
+            continue;
+        }
+
+        match bytes.next() {
+            Some(0x01) => unescaped.push(0x01),
+            Some(0x02) => unescaped.push(0x00),
+            Some(0x03) => unescaped.push(0xFF),
+            _ => bail!("Invalid escape sequence in the rules"),
+        }
+    }
+
+    Ok(unescaped)
+}
+
+/// The layout is the protocol version, overflow flags, 2 bytes of DLC flags, the difficulty, the crosshair,
+/// a hash for every DLC flag set, then the mods and signatures, each list starting with its length.
+fn decode(data: &[u8]) -> anyhow::Result<ArmaRules> {
+    let mut reader = Reader::new(data);
+
+    let protocol = reader.u8()?;
+    let overflow = reader.u8()?;
+    let dlc_flags = u16::from_le_bytes([reader.u8()?, reader.u8()?]);
+    let difficulty = reader.u8()?;
+    let crosshair = reader.u8()? != 0;
+
+    let mut dlcs = Vec::new();
+
+    for bit in (0..16).filter(|bit| dlc_flags & (1 << bit) != 0) {
+        dlcs.push(ArmaDlc {
+            name: DLCS
+                .get(bit)
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("DLC {}", bit)),
+            hash: reader.u32()?,
+        });
+    }
+
+    let mut mods = Vec::new();
+
+    for _ in 0..reader.u8()? {
+        let hash = reader.u32()?;
+        let flags = reader.u8()?;
+        let steam_id = reader.bytes((flags & 0x0F) as usize)?;
+        let name = reader.string()?;
+
+        mods.push(ArmaMod {
+            name,
+            steam_id: steam_id.iter().take(8).rev().fold(0, |id, byte| id << 8 | *byte as u64),
+            hash,
+            dlc: flags & 0x10 != 0,
+        });
+    }
+
+    let mut signatures = Vec::new();
+
+    for _ in 0..reader.u8()? {
+        signatures.push(reader.string()?);
+    }
+
+    let level = (difficulty & 0b111) as usize;
+    let ai_level = (difficulty >> 3 & 0b111) as usize;
+
+    Ok(ArmaRules {
+        protocol,
+        difficulty: ArmaDifficulty {
+            level: crate::DIFFICULTIES
+                .get(level)
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| level.to_string()),
+            ai_level: AI_LEVELS
+                .get(ai_level)
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| ai_level.to_string()),
+            advanced_flight_model: difficulty & 0b0100_0000 != 0,
+            third_person: difficulty & 0b1000_0000 != 0,
+        },
+        crosshair,
+        dlcs,
+        mods,
+        mods_overflow: overflow & 0b01 != 0,
+        signatures,
+        signatures_overflow: overflow & 0b10 != 0,
+        battleye: None,
+        platform: None,
+    })
+}
+
+/// Takes BattlEye and the platform from the keywords of A2S_INFO, like `bt,r210,n0,s7,i2,mf,lf,vt,dt,pw`.
+pub fn apply_keywords(rules: &mut ArmaRules, keywords: &str) {
+    for keyword in keywords.split(',') {
+        let mut chars = keyword.chars();
+
+        match (chars.next(), chars.as_str()) {
+            (Some('b'), "t") => rules.battleye = Some(true),
+            (Some('b'), "f") => rules.battleye = Some(false),
+            (Some('p'), "w") => rules.platform = Some(ServerOS::Windows),
+            (Some('p'), "l") => rules.platform = Some(ServerOS::Linux),
+            (Some('p'), "m") => rules.platform = Some(ServerOS::Mac),
+            _ => {}
+        }
+    }
+}
+
+/// Compares the mods the server reports with the ones the preset starts it with.
+/// Server mods aren't reported and DLC can't be told apart from the official ones, so neither counts as unexpected.
+pub fn check_loaded_preset(preset: &Preset, rules: &ArmaRules) -> LoadedPresetReport {
+    let loaded = |steam_id: i64, name: &str, dlc: bool| {
+        rules.mods.iter().any(|loaded| {
+            loaded.dlc == dlc
+                && ((steam_id > 0 && loaded.steam_id == steam_id as u64) || loaded.name.eq_ignore_ascii_case(name))
+        })
+    };
+
+    let items = preset
+        .items
+        .iter()
+        .filter(|item| item.enabled && !item.blacklisted && !item.optional && !item.server_mod)
+        .collect::<Vec<_>>();
+
+    let dlcs = preset.dlcs.iter().filter(|dlc| dlc.enabled).collect::<Vec<_>>();
+
+    let missing = items
+        .iter()
+        .filter(|item| !loaded(item.published_file_id, &item.name, false))
+        .map(|item| item.name.clone())
+        .chain(
+            dlcs.iter()
+                .filter(|dlc| !loaded(dlc.app_id, &dlc.name, true))
+                .map(|dlc| dlc.name.clone()),
+        )
+        .collect();
+
+    let unexpected = rules
+        .mods
+        .iter()
+        .filter(|loaded| {
+            !loaded.dlc
+                && !items.iter().any(|item| {
+                    (item.published_file_id > 0 && item.published_file_id as u64 == loaded.steam_id)
+                        || item.name.eq_ignore_ascii_case(&loaded.name)
+                })
+        })
+        .map(|loaded| loaded.name.clone())
+        .collect();
+
+    LoadedPresetReport {
+        preset_id: preset.id,
+        preset_name: preset.name.clone(),
+        missing,
+        unexpected,
+        incomplete: rules.mods_overflow,
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn bytes(&mut self, length: usize) -> anyhow::Result<&'a [u8]> {
+        let bytes = self
+            .data
+            .get(self.position..self.position + length)
+            .context("The rules end early")?;
+
+        self.position += length;
+
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> anyhow::Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> anyhow::Result<u32> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// A string prefixed with its length.
+    fn string(&mut self) -> anyhow::Result<String> {
+        let length = self.u8()? as usize;
+        Ok(String::from_utf8_lossy(self.bytes(length)?).into_owned())
+    }
+
+    fn cstring(&mut self) -> anyhow::Result<&'a [u8]> {
+        let length = self.data[self.position..]
+            .iter()
+            .position(|byte| *byte == 0)
+            .context("Unterminated string in the rules")?;
+
+        let string = self.bytes(length)?;
+        self.position += 1;
+
+        Ok(string)
+    }
+}
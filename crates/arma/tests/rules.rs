@@ -0,0 +1,261 @@
+use std::{net::SocketAddr, time::Duration};
+
+use api_schema::{
+    a2s::{ArmaMod, ServerOS},
+    response::{DlcItem, Preset, PresetItem},
+};
+use tokio::net::UdpSocket;
+
+const CHALLENGE: [u8; 4] = [0x11, 0x22, 0x33, 0x44];
+
+/// Protocol 3, mods overflowing, Apex and Tanks, Veteran with expert AI and third person, crosshair on,
+/// CBA from the workshop, Global Mobilization as creator DLC and a key whose hash needs escaping.
+fn blob() -> Vec<u8> {
+    let mut data = vec![3, 0b01];
+    data.extend(((1u16 << 4) | (1 << 9)).to_le_bytes());
+    data.push(2 | 2 << 3 | 0b1000_0000);
+    data.push(1);
+    data.extend(0xAABBCCDDu32.to_le_bytes());
+    data.extend(0x00FF0001u32.to_le_bytes());
+
+    data.push(2);
+    data.extend(0x01020304u32.to_le_bytes());
+    data.push(4);
+    data.extend(&450814997u64.to_le_bytes()[..4]);
+    data.push(3);
+    data.extend(b"CBA");
+    data.extend(0u32.to_le_bytes());
+    data.push(0x10 | 3);
+    data.extend(&1042220u64.to_le_bytes()[..3]);
+    data.push(19);
+    data.extend(b"Global Mobilization");
+
+    data.push(1);
+    data.push(3);
+    data.extend(b"cba");
+
+    data
+}
+
+fn escape(data: &[u8]) -> Vec<u8> {
+    data.iter()
+        .flat_map(|byte| match byte {
+            0x01 => vec![0x01, 0x01],
+            0x00 => vec![0x01, 0x02],
+            0xFF => vec![0x01, 0x03],
+            byte => vec![*byte],
+        })
+        .collect()
+}
+
+/// The escaped blob split into chunks of `size` bytes, keyed the way Arma does.
+fn chunks(size: usize) -> Vec<(Vec<u8>, Vec<u8>)> {
+    let escaped = escape(&blob());
+    let total = escaped.chunks(size).count() as u8;
+
+    escaped
+        .chunks(size)
+        .enumerate()
+        .map(|(i, chunk)| (vec![i as u8 + 1, total], chunk.to_vec()))
+        .collect()
+}
+
+fn rules_response(rules: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+    let mut response = vec![0xFF, 0xFF, 0xFF, 0xFF, 0x45];
+    response.extend((rules.len() as u16).to_le_bytes());
+
+    for (key, value) in rules {
+        response.extend(key);
+        response.push(0);
+        response.extend(value);
+        response.push(0);
+    }
+
+    response
+}
+
+/// A server that wants a challenge and answers with the rules split into packets of `packet_size`, last packet first.
+async fn stand_in(packet_size: usize) -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let mut buffer = [0u8; 1400];
+
+        loop {
+            let (length, client) = socket.recv_from(&mut buffer).await.unwrap();
+            assert_eq!(&buffer[..5], &[0xFF, 0xFF, 0xFF, 0xFF, 0x56]);
+
+            if buffer[5..length] != CHALLENGE {
+                let challenge = [&[0xFF, 0xFF, 0xFF, 0xFF, 0x41][..], &CHALLENGE].concat();
+                socket.send_to(&challenge, client).await.unwrap();
+                continue;
+            }
+
+            let response = rules_response(&chunks(7));
+            let total = response.chunks(packet_size).count() as u8;
+
+            for (number, part) in response.chunks(packet_size).enumerate().rev() {
+                let mut packet = vec![0xFE, 0xFF, 0xFF, 0xFF];
+                packet.extend(7u32.to_le_bytes());
+                packet.push(total);
+                packet.push(number as u8);
+                packet.extend((packet_size as u16).to_le_bytes());
+                packet.extend(part);
+
+                socket.send_to(&packet, client).await.unwrap();
+            }
+        }
+    });
+
+    addr
+}
+
+#[test]
+fn unescapes() {
+    assert_eq!(
+        arma::unescape(&[0x05, 0x01, 0x01, 0x01, 0x02, 0x01, 0x03, 0x06]).unwrap(),
+        vec![0x05, 0x01, 0x00, 0xFF, 0x06]
+    );
+    assert!(arma::unescape(&[0x01, 0x04]).is_err());
+    assert!(arma::unescape(&[0x05, 0x01]).is_err());
+}
+
+#[test]
+fn decodes_rules() {
+    let mut rules = chunks(10);
+    rules.reverse();
+    rules.push((b"someRule".to_vec(), b"1".to_vec()));
+
+    let rules = arma::decode_rules(&rules).unwrap();
+
+    assert_eq!(rules.protocol, 3);
+    assert!(rules.mods_overflow);
+    assert!(!rules.signatures_overflow);
+    assert_eq!(rules.difficulty.level, "Veteran");
+    assert_eq!(rules.difficulty.ai_level, "Expert");
+    assert!(!rules.difficulty.advanced_flight_model);
+    assert!(rules.difficulty.third_person);
+    assert!(rules.crosshair);
+
+    let dlcs = rules
+        .dlcs
+        .iter()
+        .map(|dlc| (dlc.name.as_str(), dlc.hash))
+        .collect::<Vec<_>>();
+    assert_eq!(dlcs, vec![("Apex", 0xAABBCCDD), ("Tanks", 0x00FF0001)]);
+
+    assert_eq!(
+        rules.mods,
+        vec![
+            ArmaMod {
+                name: "CBA".to_string(),
+                steam_id: 450814997,
+                hash: 0x01020304,
+                dlc: false,
+            },
+            ArmaMod {
+                name: "Global Mobilization".to_string(),
+                steam_id: 1042220,
+                hash: 0,
+                dlc: true,
+            },
+        ]
+    );
+    assert_eq!(rules.signatures, vec!["cba".to_string()]);
+}
+
+#[test]
+fn rejects_missing_chunks() {
+    let mut rules = chunks(10);
+    rules.remove(1);
+
+    assert!(arma::decode_rules(&rules).is_err());
+    assert!(arma::decode_rules(&[]).is_err());
+}
+
+#[test]
+fn applies_keywords() {
+    let mut rules = arma::decode_rules(&chunks(10)).unwrap();
+    arma::apply_keywords(&mut rules, "bt,r210,n0,s7,i2,mf,lf,vt,dt,pw");
+
+    assert_eq!(rules.battleye, Some(true));
+    assert_eq!(rules.platform, Some(ServerOS::Windows));
+}
+
+#[test]
+fn checks_loaded_preset() {
+    let item = |id: i64, name: &str, published_file_id: i64, server_mod: bool| PresetItem {
+        id,
+        name: name.to_string(),
+        published_file_id,
+        position: id,
+        enabled: true,
+        blacklisted: false,
+        server_mod,
+        optional: false,
+        exists: true,
+    };
+
+    let dlc = |name: &str, app_id: i64| DlcItem {
+        id: app_id,
+        name: name.to_string(),
+        key: String::default(),
+        app_id,
+        enabled: true,
+        position: 0,
+    };
+
+    let preset = Preset {
+        id: 4,
+        name: "Ops".to_string(),
+        selected: true,
+        whitelist: false,
+        items: vec![
+            item(1, "CBA_A3", 450814997, false),
+            item(2, "ACE", 463939057, false),
+            item(3, "Server", 5, true),
+        ],
+        dlcs: vec![dlc("Global Mobilization", 1042220), dlc("S.O.G. Prairie Fire", 1227700)],
+    };
+
+    let mut rules = arma::decode_rules(&chunks(10)).unwrap();
+    rules.mods.push(ArmaMod {
+        name: "Local Mod".to_string(),
+        steam_id: 0,
+        hash: 1,
+        dlc: false,
+    });
+
+    let report = arma::check_loaded_preset(&preset, &rules);
+
+    assert_eq!(report.preset_id, 4);
+    assert_eq!(
+        report.missing,
+        vec!["ACE".to_string(), "S.O.G. Prairie Fire".to_string()]
+    );
+    assert_eq!(report.unexpected, vec!["Local Mod".to_string()]);
+    assert!(report.incomplete);
+}
+
+#[tokio::test]
+async fn queries_split_rules() {
+    let addr = stand_in(64).await;
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let rules = arma::query_rules(&socket, addr, Duration::from_secs(2)).await.unwrap();
+
+    assert_eq!(rules, chunks(7));
+    assert_eq!(arma::decode_rules(&rules).unwrap().mods.len(), 2);
+}
+
+#[tokio::test]
+async fn times_out() {
+    // nobody answers on this one
+    let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let result = arma::query_rules(&socket, silent.local_addr().unwrap(), Duration::from_millis(200)).await;
+
+    assert!(result.is_err());
+}
@@ -2,7 +2,7 @@ use std::fmt::Display;
 use std::io::Read;
 use std::{rc::Rc, sync::Mutex};
 
-use api_schema::a2s::{Info, Player};
+use api_schema::a2s::{A2sState, ArmaRules, Info, Player};
 use api_schema::{request::*, response::*, *};
 use futures::channel::oneshot;
 use gloo_net::http::{FormData, Request, Response};
@@ -24,6 +24,7 @@ pub struct AuthorizedApi {
     url: &'static str,
     token: ApiToken,
     sse_abort_signals: Rc<Mutex<Vec<oneshot::Sender<()>>>>,
+    instance_abort_signals: Rc<Mutex<Vec<oneshot::Sender<()>>>>,
     loading: RwSignal<Loading>,
 }
 
@@ -53,6 +54,7 @@ impl AuthorizedApi {
             url,
             token,
             sse_abort_signals: Rc::new(Mutex::new(vec![])),
+            instance_abort_signals: Rc::new(Mutex::new(vec![])),
             loading,
         }
     }
@@ -61,7 +63,24 @@ impl AuthorizedApi {
         self.sse_abort_signals.lock().expect("Poisoned Mutex").push(signal);
     }
 
+    /// Same as `add_abort_signal`, but for streams of the selected instance,
+    /// these are aborted separately when switching instances.
+    pub fn add_instance_abort_signal(&self, signal: oneshot::Sender<()>) {
+        self.instance_abort_signals.lock().expect("Poisoned Mutex").push(signal);
+    }
+
+    pub fn run_instance_abort_signals(&self) {
+        let mut signals = self.instance_abort_signals.lock().expect("Poisoned Mutex");
+        for signal in signals.drain(..) {
+            if let Err(e) = signal.send(()) {
+                tracing::error!("Error sending abort signal: {:?}", e);
+            }
+        }
+    }
+
     pub fn run_abort_signals(&self) {
+        self.run_instance_abort_signals();
+
         let mut signals = self.sse_abort_signals.lock().expect("Poisoned Mutex");
         for signal in signals.drain(..) {
             if let Err(e) = signal.send(()) {
@@ -168,25 +187,41 @@ impl AuthorizedApi {
         result
     }
 
-    pub async fn start_arma(&self) -> Result<SimpleResponse> {
+    pub async fn start_arma(&self, instance: i64) -> Result<SimpleResponse> {
         self.loading.set(Loading::Loading(Some("Starting Arma...")));
-        let url = format!("{}/arma/start", self.url);
+        let url = format!("{}/instances/{}/start", self.url, instance);
         let result = self.send(Request::get(&url)).await;
         self.loading.set(Loading::Ready);
         result
     }
 
-    pub async fn stop_arma(&self) -> Result<SimpleResponse> {
+    pub async fn stop_arma(&self, instance: i64) -> Result<SimpleResponse> {
         self.loading.set(Loading::Loading(Some("Stopping Arma...")));
-        let url = format!("{}/arma/stop", self.url);
+        let url = format!("{}/instances/{}/stop", self.url, instance);
         let result = self.send(Request::get(&url)).await;
         self.loading.set(Loading::Ready);
         result
     }
 
-    pub async fn restart_arma(&self) -> Result<SimpleResponse> {
+    pub async fn restart_arma(&self, instance: i64) -> Result<SimpleResponse> {
         self.loading.set(Loading::Loading(Some("Restarting Arma...")));
-        let url = format!("{}/arma/restart", self.url);
+        let url = format!("{}/instances/{}/restart", self.url, instance);
+        let result = self.send(Request::get(&url)).await;
+        self.loading.set(Loading::Ready);
+        result
+    }
+
+    pub async fn launch_preview(&self, instance: i64) -> Result<LaunchPreview> {
+        self.loading.set(Loading::Loading(Some("Loading launch preview...")));
+        let url = format!("{}/instances/{}/launch/preview", self.url, instance);
+        let result = self.send(Request::get(&url)).await;
+        self.loading.set(Loading::Ready);
+        result
+    }
+
+    pub async fn preflight(&self, instance: i64) -> Result<PreflightReport> {
+        self.loading.set(Loading::Loading(Some("Running preflight checks...")));
+        let url = format!("{}/instances/{}/preflight", self.url, instance);
         let result = self.send(Request::get(&url)).await;
         self.loading.set(Loading::Ready);
         result
@@ -200,6 +235,22 @@ impl AuthorizedApi {
         result
     }
 
+    pub async fn get_instance_log(&self, instance: i64, channel: impl Into<String>) -> Result<LogResponse> {
+        self.loading.set(Loading::Loading(Some("Loading log...")));
+        let url = format!("{}/instances/{}/logs/{}", self.url, instance, channel.into());
+        let result = self.send(Request::get(&url)).await;
+        self.loading.set(Loading::Ready);
+        result
+    }
+
+    pub async fn get_instances(&self) -> Result<Vec<Instance>> {
+        self.loading.set(Loading::Loading(Some("Loading instances...")));
+        let url = format!("{}/instances", self.url);
+        let result = self.send(Request::get(&url)).await;
+        self.loading.set(Loading::Ready);
+        result
+    }
+
     pub async fn get_presets(&self) -> Result<Vec<Preset>> {
         self.loading.set(Loading::Loading(Some("Loading presets...")));
         let url = format!("{}/presets", self.url);
@@ -238,21 +289,47 @@ impl AuthorizedApi {
         self.send(Request::delete(&url).json(item)?).await
     }
 
+    /// The load order the server proposes for the enabled mods, based on their `requiredAddons`.
+    pub async fn get_load_order(&self, preset: i64) -> Result<LoadOrderReport> {
+        let url = format!("{}/presets/{}/load_order", self.url, preset);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn apply_load_order(&self, preset: i64) -> Result<LoadOrderReport> {
+        let url = format!("{}/presets/{}/load_order", self.url, preset);
+        self.send(Request::post(&url)).await
+    }
+
+    pub async fn get_key_report(&self, preset: i64) -> Result<KeyReport> {
+        let url = format!("{}/presets/{}/keys", self.url, preset);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn get_conflicts(&self, preset: i64) -> Result<ConflictReport> {
+        let url = format!("{}/presets/{}/conflicts", self.url, preset);
+        self.send(Request::get(&url)).await
+    }
+
     pub async fn delete_preset(&self, preset: &DeletePresetSchema) -> Result<SimpleResponse> {
         let url = format!("{}/presets", self.url);
         self.send(Request::delete(&url).json(preset)?).await
     }
 
-    pub async fn get_config(&self, channel: impl Into<String>) -> Result<ConfigResponse> {
+    pub async fn get_config(&self, instance: i64, channel: impl Into<String>) -> Result<ConfigResponse> {
         self.loading.set(Loading::Loading(Some("Loading config...")));
-        let url = format!("{}/arma/config/{}", self.url, channel.into());
+        let url = format!("{}/instances/{}/config/{}", self.url, instance, channel.into());
         let result = self.send(Request::get(&url)).await;
         self.loading.set(Loading::Ready);
         result
     }
 
-    pub async fn save_config(&self, channel: impl Into<String>, content: impl Into<String>) -> Result<SimpleResponse> {
-        let url = format!("{}/arma/config/{}", self.url, channel.into());
+    pub async fn save_config(
+        &self,
+        instance: i64,
+        channel: impl Into<String>,
+        content: impl Into<String>,
+    ) -> Result<SimpleResponse> {
+        let url = format!("{}/instances/{}/config/{}", self.url, instance, channel.into());
         self.send(Request::post(&url).json(&UpdateConfigSchema { config: content.into() })?)
             .await
     }
@@ -265,7 +342,41 @@ impl AuthorizedApi {
         result
     }
 
-    pub async fn upload_mission(&self, file: &web_sys::File) -> Result<SimpleResponse> {
+    pub async fn get_mission_rotation(&self, instance: i64) -> Result<Vec<RotationMission>> {
+        self.loading.set(Loading::Loading(Some("Loading mission rotation...")));
+        let url = format!("{}/instances/{}/missions", self.url, instance);
+        let result = self.send(Request::get(&url)).await;
+        self.loading.set(Loading::Ready);
+        result
+    }
+
+    pub async fn save_mission_rotation(
+        &self,
+        instance: i64,
+        missions: Vec<RotationMission>,
+    ) -> Result<Vec<RotationMission>> {
+        let url = format!("{}/instances/{}/missions", self.url, instance);
+        self.send(Request::post(&url).json(&UpdateMissionRotationSchema { missions })?)
+            .await
+    }
+
+    pub async fn get_restart_schedule(&self, instance: i64) -> Result<Vec<i64>> {
+        let url = format!("{}/instances/{}/restarts", self.url, instance);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn save_restart_schedule(&self, instance: i64, minutes: Vec<i64>) -> Result<Vec<i64>> {
+        let url = format!("{}/instances/{}/restarts", self.url, instance);
+        self.send(Request::post(&url).json(&UpdateRestartScheduleSchema { minutes })?)
+            .await
+    }
+
+    pub async fn get_mission_addons(&self, instance: i64) -> Result<MissionAddonReport> {
+        let url = format!("{}/instances/{}/missions/addons", self.url, instance);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn upload_mission(&self, file: &web_sys::File) -> Result<MissionResponse> {
         self.loading.set(Loading::Loading(Some("Uploading, Stand by...")));
 
         let url = format!("{}/arma/mission", self.url);
@@ -280,22 +391,272 @@ impl AuthorizedApi {
         response
     }
 
-    pub async fn get_a2s_info(&self) -> Result<Info> {
+    /// Uploads a new version of a mission, the previous one is archived by the server.
+    pub async fn replace_mission(&self, id: i64, file: &web_sys::File) -> Result<Mission> {
+        self.loading.set(Loading::Loading(Some("Uploading, Stand by...")));
+
+        let url = format!("{}/arma/mission/{}", self.url, id);
+
+        let form_data = FormData::new().expect("This to work");
+        form_data
+            .append_with_blob_and_filename("file", file, &file.name())
+            .expect("This to work");
+
+        let response = self.send(Request::post(&url).body(form_data)).await;
+
+        self.loading.set(Loading::Ready);
+
+        response
+    }
+
+    pub async fn rename_mission(&self, id: i64, name: impl Into<String>) -> Result<Mission> {
+        let url = format!("{}/arma/mission/{}", self.url, id);
+        self.send(Request::patch(&url).json(&RenameMissionSchema { name: name.into() })?)
+            .await
+    }
+
+    pub async fn delete_mission(&self, id: i64) -> Result<SimpleResponse> {
+        let url = format!("{}/arma/mission/{}", self.url, id);
+        self.send(Request::delete(&url)).await
+    }
+
+    /// Downloads go through a plain link, so the token is passed in the query like for the SSE.
+    pub async fn get_server_keys(&self) -> Result<Vec<ServerKey>> {
+        let url = format!("{}/keys", self.url);
+        self.send(Request::get(&url)).await
+    }
+
+    /// Uploads a key that stays in the keys folder until it is deleted again.
+    pub async fn upload_key(&self, file: &web_sys::File) -> Result<ServerKey> {
+        let url = format!("{}/keys", self.url);
+
+        let form_data = FormData::new().expect("This to work");
+        form_data
+            .append_with_blob_and_filename("file", file, &file.name())
+            .expect("This to work");
+
+        self.send(Request::post(&url).body(form_data)).await
+    }
+
+    pub async fn delete_key(&self, name: &str) -> Result<SimpleResponse> {
+        let url = format!("{}/keys/{}", self.url, js_sys::encode_uri_component(name));
+        self.send(Request::delete(&url)).await
+    }
+
+    pub fn mission_download_url(&self, id: i64) -> String {
+        format!("{}/arma/mission/{}/download?token={}", self.url, id, self.token.token)
+    }
+
+    pub fn preset_export_url(&self, id: i64) -> String {
+        format!("{}/presets/{}/export?token={}", self.url, id, self.token.token)
+    }
+
+    pub async fn set_preset_whitelist(&self, preset: i64, enabled: bool) -> Result<SimpleResponse> {
+        let url = format!("{}/presets/{}/whitelist", self.url, preset);
+        self.send(Request::patch(&url).json(&PresetWhitelistSchema { enabled })?)
+            .await
+    }
+
+    pub async fn get_a2s_info(&self, instance: i64) -> Result<Info> {
         self.loading.set(Loading::Loading(Some("Loading server info...")));
-        let url = format!("{}/a2s/info", self.url);
+        let url = format!("{}/instances/{}/a2s/info", self.url, instance);
         let result = self.send(Request::get(&url)).await;
         self.loading.set(Loading::Ready);
         result
     }
 
-    pub async fn get_a2s_players(&self) -> Result<Vec<Player>> {
+    pub async fn get_a2s_players(&self, instance: i64) -> Result<Vec<Player>> {
         self.loading.set(Loading::Loading(Some("Loading players...")));
-        let url = format!("{}/a2s/players", self.url);
+        let url = format!("{}/instances/{}/a2s/players", self.url, instance);
         let result = self.send(Request::get(&url)).await;
         self.loading.set(Loading::Ready);
         result
     }
 
+    pub async fn get_a2s_state(&self, instance: i64) -> Result<A2sState> {
+        let url = format!("{}/instances/{}/a2s/state", self.url, instance);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn get_a2s_rules(&self, instance: i64) -> Result<Option<ArmaRules>> {
+        let url = format!("{}/instances/{}/a2s/rules", self.url, instance);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn check_loaded_preset(&self, instance: i64) -> Result<LoadedPresetReport> {
+        let url = format!("{}/instances/{}/a2s/rules/preset", self.url, instance);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn get_rcon_players(&self, instance: i64) -> Result<Vec<RconPlayer>> {
+        let url = format!("{}/instances/{}/players", self.url, instance);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn kick_player(&self, instance: i64, schema: &KickPlayerSchema) -> Result<SimpleResponse> {
+        let url = format!("{}/instances/{}/players/kick", self.url, instance);
+        self.send(Request::post(&url).json(schema)?).await
+    }
+
+    pub async fn ban_player(&self, instance: i64, schema: &BanPlayerSchema) -> Result<SimpleResponse> {
+        let url = format!("{}/instances/{}/players/ban", self.url, instance);
+        self.send(Request::post(&url).json(schema)?).await
+    }
+
+    pub async fn send_message(&self, instance: i64, schema: &SendMessageSchema) -> Result<SimpleResponse> {
+        let url = format!("{}/instances/{}/players/message", self.url, instance);
+        self.send(Request::post(&url).json(schema)?).await
+    }
+
+    pub async fn lock_server(&self, instance: i64, locked: bool) -> Result<SimpleResponse> {
+        let url = format!("{}/instances/{}/lock", self.url, instance);
+        self.send(Request::post(&url).json(&LockServerSchema { locked })?).await
+    }
+
+    pub async fn get_audit_log(&self, instance: i64) -> Result<Vec<AuditEntry>> {
+        let url = format!("{}/instances/{}/audit", self.url, instance);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn get_messages(&self, instance: i64, query: &MessageQuery) -> Result<Vec<ServerMessage>> {
+        let mut params = Vec::new();
+
+        if let Some(search) = &query.search {
+            params.push(format!("search={}", js_sys::encode_uri_component(search)));
+        }
+
+        if let Some(kind) = query.kind {
+            params.push(format!("kind={:?}", kind));
+        }
+
+        if let Some(before) = query.before {
+            params.push(format!("before={}", before));
+        }
+
+        if let Some(limit) = query.limit {
+            params.push(format!("limit={}", limit));
+        }
+
+        let url = format!("{}/instances/{}/messages?{}", self.url, instance, params.join("&"));
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn get_announcements(&self, instance: i64) -> Result<Vec<Announcement>> {
+        let url = format!("{}/instances/{}/announcements", self.url, instance);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn create_announcement(&self, instance: i64, schema: &AnnouncementSchema) -> Result<Announcement> {
+        let url = format!("{}/instances/{}/announcements", self.url, instance);
+        self.send(Request::post(&url).json(schema)?).await
+    }
+
+    pub async fn update_announcement(
+        &self,
+        instance: i64,
+        id: i64,
+        schema: &AnnouncementSchema,
+    ) -> Result<Announcement> {
+        let url = format!("{}/instances/{}/announcements/{}", self.url, instance, id);
+        self.send(Request::patch(&url).json(schema)?).await
+    }
+
+    pub async fn delete_announcement(&self, instance: i64, id: i64) -> Result<SimpleResponse> {
+        let url = format!("{}/instances/{}/announcements/{}", self.url, instance, id);
+        self.send(Request::delete(&url)).await
+    }
+
+    pub async fn get_whitelist_settings(&self, instance: i64) -> Result<WhitelistSettings> {
+        let url = format!("{}/instances/{}/whitelist", self.url, instance);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn update_whitelist_settings(
+        &self,
+        instance: i64,
+        schema: &UpdateWhitelistSettingsSchema,
+    ) -> Result<SimpleResponse> {
+        let url = format!("{}/instances/{}/whitelist", self.url, instance);
+        self.send(Request::post(&url).json(schema)?).await
+    }
+
+    pub async fn get_sessions(&self, instance: i64, query: &SessionQuery) -> Result<Vec<PlayerSession>> {
+        let mut params = Vec::new();
+
+        if let Some(player) = &query.player {
+            params.push(format!("player={}", js_sys::encode_uri_component(player)));
+        }
+
+        if let Some(limit) = query.limit {
+            params.push(format!("limit={}", limit));
+        }
+
+        let url = format!("{}/instances/{}/sessions?{}", self.url, instance, params.join("&"));
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn get_daily_stats(&self, instance: i64, days: i64) -> Result<Vec<DailyPlayerStats>> {
+        let url = format!("{}/instances/{}/stats/daily?days={}", self.url, instance, days);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn get_playtime(&self, instance: i64, days: i64, limit: i64) -> Result<Vec<PlayerPlaytime>> {
+        let url = format!(
+            "{}/instances/{}/stats/playtime?days={}&limit={}",
+            self.url, instance, days, limit
+        );
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn get_bans(&self) -> Result<Vec<Ban>> {
+        let url = format!("{}/bans", self.url);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn create_ban(&self, schema: &CreateBanSchema) -> Result<Ban> {
+        let url = format!("{}/bans", self.url);
+        self.send(Request::post(&url).json(schema)?).await
+    }
+
+    pub async fn delete_ban(&self, id: i64) -> Result<SimpleResponse> {
+        let url = format!("{}/bans/{}", self.url, id);
+        self.send(Request::delete(&url)).await
+    }
+
+    pub async fn import_bans(&self, schema: &ImportBansSchema) -> Result<SimpleResponse> {
+        let url = format!("{}/bans/import", self.url);
+        self.send(Request::post(&url).json(schema)?).await
+    }
+
+    pub fn ban_export_url(&self, format: BanListFormat) -> String {
+        let format = match format {
+            BanListFormat::Engine => "Engine",
+            BanListFormat::BattlEye => "BattlEye",
+        };
+
+        format!("{}/bans/export/{}?token={}", self.url, format, self.token.token)
+    }
+
+    pub async fn get_whitelist(&self) -> Result<Vec<WhitelistEntry>> {
+        let url = format!("{}/whitelist", self.url);
+        self.send(Request::get(&url)).await
+    }
+
+    pub async fn create_whitelist_entry(&self, schema: &CreateWhitelistEntrySchema) -> Result<WhitelistEntry> {
+        let url = format!("{}/whitelist", self.url);
+        self.send(Request::post(&url).json(schema)?).await
+    }
+
+    pub async fn delete_whitelist_entry(&self, id: i64) -> Result<SimpleResponse> {
+        let url = format!("{}/whitelist/{}", self.url, id);
+        self.send(Request::delete(&url)).await
+    }
+
+    pub async fn import_whitelist(&self, schema: &ImportWhitelistSchema) -> Result<SimpleResponse> {
+        let url = format!("{}/whitelist/import", self.url);
+        self.send(Request::post(&url).json(schema)?).await
+    }
+
     pub fn token(&self) -> &ApiToken {
         &self.token
     }
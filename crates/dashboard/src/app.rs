@@ -101,6 +101,36 @@ pub fn App(cx: Scope) -> impl IntoView {
                             view! { cx, <Missions /> }
                         }
                     />
+                    <Route path=Page::Players.path()
+                        view=move |cx| {
+                            view! { cx, <Players /> }
+                        }
+                    />
+                    <Route path=Page::Chat.path()
+                        view=move |cx| {
+                            view! { cx, <Chat /> }
+                        }
+                    />
+                    <Route path=Page::Announcements.path()
+                        view=move |cx| {
+                            view! { cx, <Announcements /> }
+                        }
+                    />
+                    <Route path=Page::Statistics.path()
+                        view=move |cx| {
+                            view! { cx, <Statistics /> }
+                        }
+                    />
+                    <Route path=Page::Bans.path()
+                        view=move |cx| {
+                            view! { cx, <Bans /> }
+                        }
+                    />
+                    <Route path=Page::Whitelist.path()
+                        view=move |cx| {
+                            view! { cx, <Whitelist /> }
+                        }
+                    />
                 </Route>
                 <Route
                     path=Page::Login.path()
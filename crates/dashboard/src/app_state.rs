@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use api_schema::a2s::{Info, Player, PlayerOrInfo};
+use api_schema::a2s::{A2sState, Info, Player, PlayerOrInfo};
 use api_schema::{request::*, response::*};
 use derive_more::Display;
 use gloo_storage::{LocalStorage, Storage};
@@ -26,10 +26,12 @@ pub enum Loading {
 
 pub type LogData = HashMap<String, Vec<String>>;
 pub type ConfigData = HashMap<String, Vec<String>>;
-pub type MissionData = Vec<String>;
+pub type MissionData = Vec<Mission>;
 
 pub type PresetList = Vec<Preset>;
 
+const INSTANCE_STORAGE_KEY: &str = "instance";
+
 #[derive(Clone, Copy)]
 pub struct AppState {
     pub theme: RwSignal<Theme>,
@@ -37,9 +39,14 @@ pub struct AppState {
     pub user: RwSignal<Option<FilteredUser>>,
     pub api: RwSignal<Option<AuthorizedApi>>,
     pub status: RwSignal<Option<Status>>,
+    pub instances: RwSignal<Vec<Instance>>,
+    /// The instance the dashboard is showing, remembered across reloads.
+    pub instance: RwSignal<Option<i64>>,
     pub log: RwSignal<LogData>,
     pub players: RwSignal<Vec<Player>>,
     pub server_info: RwSignal<Option<Info>>,
+    /// Info and players are only known while the server is reachable.
+    pub a2s_state: RwSignal<A2sState>,
     pub presets: RwSignal<PresetList>,
     pub config: RwSignal<ConfigData>,
     pub missions: RwSignal<MissionData>,
@@ -54,9 +61,12 @@ impl AppState {
             user: create_rw_signal(cx, None),
             api: create_rw_signal(cx, None),
             status: create_rw_signal(cx, None),
+            instances: create_rw_signal(cx, Default::default()),
+            instance: create_rw_signal(cx, None),
             log: create_rw_signal(cx, Default::default()),
             players: create_rw_signal(cx, Default::default()),
             server_info: create_rw_signal(cx, Default::default()),
+            a2s_state: create_rw_signal(cx, Default::default()),
             presets: create_rw_signal(cx, Default::default()),
             config: create_rw_signal(cx, Default::default()),
             missions: create_rw_signal(cx, Default::default()),
@@ -70,9 +80,12 @@ impl AppState {
             self.api.set(None);
             self.user.set(None);
             self.status.set(None);
+            self.instances.set(Default::default());
+            self.instance.set(None);
             self.log.set(Default::default());
             self.players.set(Default::default());
             self.server_info.set(Default::default());
+            self.a2s_state.set(Default::default());
             self.presets.set(Default::default());
             self.config.set(Default::default());
             self.missions.set(Default::default());
@@ -98,8 +111,11 @@ impl AppState {
 
         let user_signal = self.user;
         let status_signal = self.status;
+        let instances_signal = self.instances;
+        let instance_signal = self.instance;
         let log_signal = self.log;
         let info_signal = self.server_info;
+        let a2s_state_signal = self.a2s_state;
         let players_signal = self.players;
         let preset_signal = self.presets;
         let config_signal = self.config;
@@ -129,10 +145,49 @@ impl AppState {
                     // deffo confirmed signed in at this point, so we can load everything else in parallel
                     set_status(cx, &api, &status_signal).await;
                     setup_logs(cx, &api, &log_signal).await;
-                    setup_a2s(cx, &api, &info_signal, &players_signal).await;
                     setup_presets(cx, &api, &preset_signal, &status_signal, &loading_signal).await;
-                    setup_config(cx, &api, &config_signal).await;
                     setup_missions(cx, &api, &mission_signal).await;
+                    setup_instances(&api, &instances_signal, &instance_signal).await;
+
+                    // everything scoped to an instance is reloaded whenever the selected instance changes
+                    create_effect(cx, move |_| {
+                        let Some(instance) = instance_signal.get() else {
+                            return;
+                        };
+
+                        let _ = LocalStorage::set(INSTANCE_STORAGE_KEY, instance);
+
+                        api.run_instance_abort_signals();
+                        // the server log and one for each headless client
+                        let channels = instances_signal.with(|instances| {
+                            let headless_clients = instances
+                                .iter()
+                                .find(|i| i.id == instance)
+                                .map(|i| i.headless_clients as usize)
+                                .unwrap_or_default();
+
+                            std::iter::once("arma".to_string())
+                                .chain((1..=headless_clients).map(|index| format!("hc{}", index)))
+                                .collect::<Vec<_>>()
+                        });
+
+                        log_signal.update(|l| {
+                            for channel in channels.iter() {
+                                l.insert(channel.clone(), vec![]);
+                            }
+                        });
+                        config_signal.set(Default::default());
+                        info_signal.set(None);
+                        a2s_state_signal.set(Default::default());
+                        players_signal.set(Default::default());
+
+                        let api = api.clone();
+                        spawn_local(async move {
+                            setup_instance_logs(cx, &api, instance, channels, &log_signal).await;
+                            setup_a2s(cx, &api, instance, &info_signal, &players_signal, &a2s_state_signal).await;
+                            setup_config(cx, &api, instance, &config_signal).await;
+                        });
+                    });
 
                     // only do this if we are on Login page
                     if route.path() == crate::pages::Page::Login.path().trim_start_matches('/') {
@@ -202,15 +257,6 @@ async fn setup_logs(cx: Scope, api: &AuthorizedApi, log_signal: &RwSignal<LogDat
     let api = api.clone();
 
     // grab steamcmd latest log
-    if let Ok(new_data) = api.get_log("arma").await {
-        log_signal.update(|l| {
-            if !l.contains_key("arma") {
-                l.insert("arma".into(), vec![]);
-            }
-            l.get_mut("arma").unwrap().extend(new_data.log.clone())
-        });
-    }
-
     if let Ok(new_data) = api.get_log("steamcmd").await {
         log_signal.update(|l| {
             if !l.contains_key("steamcmd") {
@@ -224,46 +270,102 @@ async fn setup_logs(cx: Scope, api: &AuthorizedApi, log_signal: &RwSignal<LogDat
     let abort_signal = create_sse(
         cx,
         "logs",
-        vec!["steamcmd".to_string(), "arma".to_string()],
-        move |channel, data: Vec<String>| {
+        vec!["steamcmd".to_string()],
+        move |channel, data: Vec<String>| append_log(&log_signal, channel, data),
+    );
+
+    api.add_abort_signal(abort_signal);
+}
+
+async fn setup_instance_logs(
+    cx: Scope,
+    api: &AuthorizedApi,
+    instance: i64,
+    channels: Vec<String>,
+    log_signal: &RwSignal<LogData>,
+) {
+    let api = api.clone();
+
+    for channel in channels.iter() {
+        if let Ok(new_data) = api.get_instance_log(instance, channel).await {
             log_signal.update(|l| {
-                if !l.contains_key(&channel) {
-                    tracing::error!("Log for {} not found!", channel);
-                    return;
-                }
-                for line in data.iter() {
-                    l.get_mut(&channel).unwrap().push(line.clone());
-                }
+                l.insert(channel.clone(), new_data.log.clone());
             });
-        },
+        }
+    }
+
+    let log_signal = *log_signal;
+    let abort_signal = create_sse(
+        cx,
+        format!("instances/{}/logs", instance),
+        channels,
+        move |channel, data: Vec<String>| append_log(&log_signal, channel, data),
     );
 
-    api.add_abort_signal(abort_signal);
+    api.add_instance_abort_signal(abort_signal);
+}
+
+fn append_log(log_signal: &RwSignal<LogData>, channel: String, data: Vec<String>) {
+    log_signal.update(|l| {
+        if !l.contains_key(&channel) {
+            tracing::error!("Log for {} not found!", channel);
+            return;
+        }
+        for line in data.iter() {
+            l.get_mut(&channel).unwrap().push(line.clone());
+        }
+    });
+}
+
+async fn setup_instances(
+    api: &AuthorizedApi,
+    instances_signal: &RwSignal<Vec<Instance>>,
+    instance_signal: &RwSignal<Option<i64>>,
+) {
+    let Ok(instances) = api.get_instances().await else {
+        return;
+    };
+
+    // keep the remembered instance if it still exists
+    let stored: Option<i64> = LocalStorage::get(INSTANCE_STORAGE_KEY).ok();
+    let selected = stored
+        .filter(|id| instances.iter().any(|instance| instance.id == *id))
+        .or_else(|| instances.first().map(|instance| instance.id));
+
+    instances_signal.set(instances);
+    instance_signal.set(selected);
 }
 
 async fn setup_a2s(
     cx: Scope,
     api: &AuthorizedApi,
+    instance: i64,
     info_signal: &RwSignal<Option<Info>>,
     players_signal: &RwSignal<Vec<Player>>,
+    state_signal: &RwSignal<A2sState>,
 ) {
     let aapi = api.clone();
 
     let players_signal = *players_signal;
     let info_signal = *info_signal;
+    let state_signal = *state_signal;
 
-    if let Ok(new_data) = api.get_a2s_info().await {
+    if let Ok(state) = api.get_a2s_state(instance).await {
+        state_signal.set(state);
+    }
+
+    if let Ok(new_data) = api.get_a2s_info(instance).await {
         info_signal.set(Some(new_data));
     }
 
-    if let Ok(new_data) = api.get_a2s_players().await {
+    if let Ok(new_data) = api.get_a2s_players(instance).await {
         players_signal.set(new_data);
     }
 
     let abort_signal = create_sse(
         cx,
-        "a2s",
-        vec!["info".to_string(), "players".to_string()],
+        format!("instances/{}/a2s", instance),
+        vec!["info".to_string(), "players".to_string(), "state".to_string()],
         move |channel, data| match channel.as_str() {
             "info" => {
                 let PlayerOrInfo::Info(info) = data else {
@@ -281,10 +383,24 @@ async fn setup_a2s(
 
                 players_signal.set(players);
             }
+            "state" => {
+                let PlayerOrInfo::State(state) = data else {
+                    // invalid type, ignore
+                    return;
+                };
+
+                // the server discarded what it knew, so should we
+                if state != A2sState::Reachable {
+                    info_signal.set(None);
+                    players_signal.set(Default::default());
+                }
+
+                state_signal.set(state);
+            }
             _ => {}
         },
     );
-    api.add_abort_signal(abort_signal);
+    api.add_instance_abort_signal(abort_signal);
 }
 
 async fn setup_presets(
@@ -451,18 +567,18 @@ async fn setup_presets(
     api.add_abort_signal(abort_signal);
 }
 
-async fn setup_config(cx: Scope, api: &AuthorizedApi, config_signal: &RwSignal<ConfigData>) {
+async fn setup_config(cx: Scope, api: &AuthorizedApi, instance: i64, config_signal: &RwSignal<ConfigData>) {
     let api = api.clone();
     let config_signal = *config_signal;
 
-    if let Ok(new_data) = api.get_config("server.cfg").await {
+    if let Ok(new_data) = api.get_config(instance, "server.cfg").await {
         config_signal.update(|l| {
             l.insert("server.cfg".into(), vec![]);
             l.get_mut("server.cfg").unwrap().extend(new_data.config.clone())
         });
     }
 
-    if let Ok(new_data) = api.get_config("profile.cfg").await {
+    if let Ok(new_data) = api.get_config(instance, "profile.cfg").await {
         config_signal.update(|l| {
             l.insert("profile.cfg".into(), vec![]);
             l.get_mut("profile.cfg").unwrap().extend(new_data.config.clone())
@@ -471,7 +587,7 @@ async fn setup_config(cx: Scope, api: &AuthorizedApi, config_signal: &RwSignal<C
 
     let abort_signal = create_sse(
         cx,
-        "arma/config",
+        format!("instances/{}/config", instance),
         vec!["server.cfg".to_string(), "profile.cfg".to_string()],
         move |channel, data: Vec<String>| {
             config_signal.update(|l| {
@@ -483,7 +599,7 @@ async fn setup_config(cx: Scope, api: &AuthorizedApi, config_signal: &RwSignal<C
         },
     );
 
-    api.add_abort_signal(abort_signal);
+    api.add_instance_abort_signal(abort_signal);
 }
 
 async fn setup_missions(cx: Scope, api: &AuthorizedApi, mission_signal: &RwSignal<MissionData>) {
@@ -52,7 +52,7 @@ pub fn Dropzone(cx: Scope) -> impl IntoView {
     view! { cx,
         <input type="checkbox" id="my-modal-5" class="dropzone-toggle" checked=move || is_over_drop_zone.get() />
         <div class="dropzone">
-            "Drop the mods, the presets, or missions here."
+            "Drop the mods, the presets, missions or keys here."
         </div>
     }
     .into_view(cx)
@@ -79,14 +79,38 @@ async fn validate_and_upload(cx: Scope, api: &AuthorizedApi, file: &File) -> Res
         }
     }
 
-    if name.ends_with(".pbo") {
-        api.upload_mission(file).await.unwrap();
+    // zips are unpacked mission folders, the server packs them
+    if name.ends_with(".pbo") || name.ends_with(".zip") {
+        let uploaded = api.upload_mission(file).await?;
+
+        let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+        app_state.missions.update(|missions| {
+            for mission in uploaded.missions {
+                missions.retain(|existing| existing.name != mission.name);
+                missions.push(mission);
+            }
+
+            missions.sort_by_key(|mission| mission.name.to_lowercase());
+        });
 
         use_navigate(cx)("/console/missions", Default::default()).expect("Presets route");
 
         return Ok(());
     }
 
+    if name.to_lowercase().ends_with(".bikey") {
+        let key = api.upload_key(file).await?;
+
+        let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+        app_state.toast(
+            cx,
+            format!("Key {} uploaded", key.name),
+            Some(super::ToastStyle::Success),
+        );
+
+        return Ok(());
+    }
+
     let app_state = use_context::<AppState>(cx).expect("AppState to exist");
     app_state.toast(cx, "Invalid file type", Some(super::ToastStyle::Error));
 
@@ -36,7 +36,10 @@ where
         let content = element.get().expect("textarea to exist").value();
         async move {
             let api = app_state.api.get_untracked().expect("api to exist");
-            api.save_config(channel, content).await;
+            let Some(instance) = app_state.instance.get_untracked() else {
+                return;
+            };
+            api.save_config(instance, channel, content).await;
         }
     });
 
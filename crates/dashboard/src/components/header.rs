@@ -6,7 +6,7 @@ use leptos_router::*;
 use crate::{
     app::API_TOKEN_STORAGE_KEY,
     app_state::AppState,
-    components::{ServerButtons, ThemeSelect},
+    components::{InstanceSelect, ServerButtons, ThemeSelect},
     pages::Page,
 };
 
@@ -59,6 +59,7 @@ pub fn Header(cx: Scope) -> impl IntoView {
                     <label for="left-sidebar-drawer" class="btn btn-primary drawer-button lg:hidden">
                         <i class="fa fa-bars" /> // hamburger menu icon
                     </label>
+                    <InstanceSelect />
                     <ServerButtons />
                 </div>
 
@@ -0,0 +1,42 @@
+use api_schema::response::State;
+use leptos::*;
+
+use crate::app_state::AppState;
+
+#[component]
+pub fn InstanceSelect(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("there to be an AppState");
+    let instances = app_state.instances;
+    let instance = app_state.instance;
+    let status = app_state.status;
+
+    let selected_name = Signal::derive(cx, move || {
+        let selected = instance.get()?;
+        instances.get().into_iter().find(|i| i.id == selected).map(|i| i.name)
+    });
+
+    view! { cx,
+        <div class="dropdown">
+            <label class="btn gap-1 normal-case btn-ghost" tabindex="0" title="Instance">
+                <i class="fa fa-server"></i>
+                {move || selected_name.get().unwrap_or_else(|| "Loading...".to_string())}
+                <i class="fa fa-caret-down"></i>
+            </label>
+            <ul tabindex="0" class="dropdown-content menu p-2 shadow-lg bg-base-100 rounded-box w-52 mt-2">
+                {move || instances.get().into_iter().map(|i| {
+                    let id = i.id;
+                    let running = move || status.get().map(|status| status.arma(id) != State::Stopped).unwrap_or(false);
+
+                    view! { cx,
+                        <li>
+                            <div class="flex flex-1 grow items-center justify-between" onClick="document.activeElement.blur();" on:click=move |_| instance.set(Some(id))>
+                                <a href="#">{i.name.clone()}</a>
+                                <span class="badge badge-sm" class:badge-success=running>{i.port}</span>
+                            </div>
+                        </li>
+                    }
+                }).collect::<Vec<_>>()}
+            </ul>
+        </div>
+    }
+}
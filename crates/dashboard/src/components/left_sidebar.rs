@@ -48,6 +48,48 @@ pub fn LeftSidebar(cx: Scope) -> impl IntoView {
                     </NavLink>
                 </li>
 
+                <li>
+                    <NavLink href={Page::Players.path()} exact=true class="font-normal">
+                        <i class="fa fa-user-shield"/>
+                        "Players"
+                    </NavLink>
+                </li>
+
+                <li>
+                    <NavLink href={Page::Chat.path()} exact=true class="font-normal">
+                        <i class="fa fa-comments"/>
+                        "Chat"
+                    </NavLink>
+                </li>
+
+                <li>
+                    <NavLink href={Page::Announcements.path()} exact=true class="font-normal">
+                        <i class="fa fa-bullhorn"/>
+                        "Announcements"
+                    </NavLink>
+                </li>
+
+                <li>
+                    <NavLink href={Page::Statistics.path()} exact=true class="font-normal">
+                        <i class="fa fa-chart-column"/>
+                        "Statistics"
+                    </NavLink>
+                </li>
+
+                <li>
+                    <NavLink href={Page::Bans.path()} exact=true class="font-normal">
+                        <i class="fa fa-ban"/>
+                        "Bans"
+                    </NavLink>
+                </li>
+
+                <li>
+                    <NavLink href={Page::Whitelist.path()} exact=true class="font-normal">
+                        <i class="fa fa-user-check"/>
+                        "Whitelist"
+                    </NavLink>
+                </li>
+
                 <div class="divider mt-0 mb-0"></div>
 
                 <li>
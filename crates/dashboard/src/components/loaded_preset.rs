@@ -0,0 +1,118 @@
+use api_schema::{a2s::ArmaRules, response::LoadedPresetReport};
+use leptos::*;
+
+use crate::{app_state::AppState, components::ToastStyle};
+
+#[component]
+pub fn LoadedPreset(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let rules = create_rw_signal(cx, None::<ArmaRules>);
+    let report = create_rw_signal(cx, None::<LoadedPresetReport>);
+
+    // the results belong to the instance they were loaded for
+    create_effect(cx, move |_| {
+        let _ = app_state.instance.get();
+        rules.set(None);
+        report.set(None);
+    });
+
+    let check = create_action(cx, move |()| async move {
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+        let Some(instance) = app_state.instance.get_untracked() else {
+            return;
+        };
+
+        match api.get_a2s_rules(instance).await {
+            Ok(new_rules) => rules.set(new_rules),
+            Err(err) => {
+                rules.set(None);
+                app_state.toast(cx, format!("Unable to load the rules: {err}"), Some(ToastStyle::Error));
+            }
+        }
+
+        match api.check_loaded_preset(instance).await {
+            Ok(new_report) => report.set(Some(new_report)),
+            Err(err) => {
+                report.set(None);
+                app_state.toast(
+                    cx,
+                    format!("Unable to check the loaded preset: {err}"),
+                    Some(ToastStyle::Error),
+                );
+            }
+        }
+    });
+
+    view! { cx,
+        <div class="card w-full bg-base-100 shadow-xl mt-8">
+            <div class="card-body">
+                <div class="flex justify-between">
+                    <h2 class="card-title">"Loaded Preset"</h2>
+                    <button class="btn btn-sm btn-ghost hover:glass" on:click=move |_| check.dispatch(()) title="Check what the server loaded">
+                        <i class="fa fa-rotate"></i>
+                    </button>
+                </div>
+                {move || match rules.get() {
+                    None => view! { cx, <p class="text-sm">"Check which mods the running server reports as loaded."</p> }.into_view(cx),
+                    Some(rules) => view! { cx,
+                        <table class="table table-compact w-full">
+                            <tbody>
+                                <tr>
+                                    <td class="font-semibold">"Difficulty"</td>
+                                    <td>{format!("{} with {} AI", rules.difficulty.level, rules.difficulty.ai_level)}</td>
+                                </tr>
+                                <tr>
+                                    <td class="font-semibold">"BattlEye"</td>
+                                    <td>{match rules.battleye {
+                                        Some(true) => "Enabled",
+                                        Some(false) => "Disabled",
+                                        None => "Unknown",
+                                    }}</td>
+                                </tr>
+                                <tr>
+                                    <td class="font-semibold">"Platform"</td>
+                                    <td>{rules.platform.map(|platform| platform.to_string()).unwrap_or_else(|| "Unknown".to_string())}</td>
+                                </tr>
+                                <tr>
+                                    <td class="font-semibold">"DLC"</td>
+                                    <td class="whitespace-normal">{rules.dlcs.iter().map(|dlc| dlc.name.clone()).collect::<Vec<_>>().join(", ")}</td>
+                                </tr>
+                                <tr>
+                                    <td class="font-semibold">"Mods"</td>
+                                    <td>{rules.mods.len()}{rules.mods_overflow.then_some("+")}</td>
+                                </tr>
+                            </tbody>
+                        </table>
+                    }.into_view(cx),
+                }}
+                {move || report.get().map(|report| {
+                    let matches = report.missing.is_empty() && report.unexpected.is_empty();
+
+                    view! { cx,
+                        {matches.then(|| view! { cx,
+                            <div class="alert alert-success py-2">
+                                <i class="fa fa-circle-check"></i>
+                                <span>{format!("The server runs the mods of {}", report.preset_name)}</span>
+                            </div>
+                        })}
+                        {report.missing.iter().map(|name| view! { cx,
+                            <div class="alert alert-error py-2">
+                                <i class="fa fa-circle-xmark"></i>
+                                <span>{format!("{} is not loaded", name)}</span>
+                            </div>
+                        }).collect::<Vec<_>>()}
+                        {report.unexpected.iter().map(|name| view! { cx,
+                            <div class="alert alert-warning py-2">
+                                <i class="fa fa-triangle-exclamation"></i>
+                                <span>{format!("{} is loaded but not in the preset", name)}</span>
+                            </div>
+                        }).collect::<Vec<_>>()}
+                        {report.incomplete.then(|| view! { cx,
+                            <p class="text-sm">"The server loaded more mods than it reports, so some may be missing only from the report."</p>
+                        })}
+                    }
+                })}
+            </div>
+        </div>
+    }
+}
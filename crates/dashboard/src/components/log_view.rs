@@ -8,12 +8,13 @@ use crate::{api::AuthorizedApi, app::LogData, app_state::AppState, components::P
 #[component]
 pub fn LogView(
     cx: Scope,
-    channel: &'static str,
+    channel: MaybeSignal<String>,
     visible: MaybeSignal<bool>,
     #[prop(optional)] progress: Option<RwSignal<Progress>>,
 ) -> impl IntoView {
     let app_state = use_context::<AppState>(cx).expect("AppState to exist");
     let log_data = app_state.log;
+    let channel = Signal::derive(cx, move || channel.get());
 
     let log_content = create_rw_signal(cx, String::new());
 
@@ -51,7 +52,7 @@ pub fn LogView(
 
     create_effect(cx, move |_| {
         let log_data = log_data.get();
-        let Some(lines) = log_data.get(channel) else {
+        let Some(lines) = log_data.get(&channel.get()) else {
             return; // no logs for this channel
         };
 
@@ -75,7 +76,7 @@ pub fn LogView(
         let log_data = log_data;
         async move {
             log_data.update(move |log| {
-                log.insert(channel.to_string(), vec![]);
+                log.insert(channel.get_untracked(), vec![]);
             });
         }
     });
@@ -0,0 +1,180 @@
+use api_schema::response::{MissionParam, RotationMission};
+use leptos::*;
+
+use crate::{app_state::AppState, components::ToastStyle};
+
+const DIFFICULTIES: [&str; 4] = ["Recruit", "Regular", "Veteran", "Custom"];
+
+/// Params are edited as `name=value` pairs separated by commas.
+fn params_to_string(params: &[MissionParam]) -> String {
+    params
+        .iter()
+        .map(|param| format!("{}={}", param.name, param.value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn params_from_string(params: &str) -> Vec<MissionParam> {
+    params
+        .split(',')
+        .filter_map(|param| {
+            let (name, value) = param.split_once('=')?;
+
+            Some(MissionParam {
+                name: name.trim().to_string(),
+                value: value.trim().parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+#[component]
+pub fn MissionRotation(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let missions = app_state.missions;
+    let rotation = create_rw_signal(cx, Vec::<RotationMission>::new());
+
+    let load_rotation = create_action(cx, move |instance: &i64| {
+        let instance = *instance;
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.get_mission_rotation(instance).await {
+                Ok(missions) => rotation.set(missions),
+                Err(err) => {
+                    rotation.set(vec![]);
+                    app_state.toast(
+                        cx,
+                        format!("Unable to load mission rotation: {err}"),
+                        Some(ToastStyle::Error),
+                    );
+                }
+            }
+        }
+    });
+
+    create_effect(cx, move |_| {
+        if let Some(instance) = app_state.instance.get() {
+            load_rotation.dispatch(instance);
+        }
+    });
+
+    let save_rotation = create_action(cx, move |()| async move {
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+        let Some(instance) = app_state.instance.get_untracked() else {
+            return;
+        };
+
+        match api.save_mission_rotation(instance, rotation.get_untracked()).await {
+            Ok(missions) => {
+                rotation.set(missions);
+                app_state.toast(cx, "Mission rotation saved", Some(ToastStyle::Success));
+            }
+            Err(err) => {
+                app_state.toast(
+                    cx,
+                    format!("Unable to save mission rotation: {err}"),
+                    Some(ToastStyle::Error),
+                );
+            }
+        }
+    });
+
+    let add_mission = move |_| {
+        let Some(template) = missions.get_untracked().first().map(|mission| mission.name.clone()) else {
+            app_state.toast(cx, "Upload a mission first", Some(ToastStyle::Warning));
+            return;
+        };
+
+        rotation.update(|rotation| {
+            rotation.push(RotationMission {
+                template,
+                difficulty: "Regular".to_string(),
+                params: vec![],
+            })
+        });
+    };
+
+    view! { cx,
+        <div class="flex justify-between items-center mt-8">
+            <div class="text-xl font-semibold">"Mission Rotation"</div>
+            <div class="flex gap-2">
+                <button class="btn btn-sm btn-ghost hover:glass" on:click=add_mission title="Add mission">
+                    <i class="fa fa-plus"></i>
+                </button>
+                <button class="btn btn-sm btn-primary" on:click=move |_| save_rotation.dispatch(()) title="Save rotation">
+                    "Save"
+                </button>
+            </div>
+        </div>
+        <div class="divider mt-2"></div>
+        {move || if rotation.get().is_empty() {
+            view! { cx, <p class="text-sm">"No rotation set, the Missions class of server.cfg is used as is."</p> }.into_view(cx)
+        } else {
+            view! { cx,
+                <table class="table table-compact w-full">
+                    <thead>
+                        <tr>
+                            <th>"#"</th>
+                            <th>"Mission"</th>
+                            <th>"Difficulty"</th>
+                            <th>"Params"</th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {rotation.get().into_iter().enumerate().map(|(i, mission)| {
+                            let last = rotation.get_untracked().len() - 1;
+                            let template = mission.template.clone();
+                            let difficulty = mission.difficulty.clone();
+
+                            view! { cx,
+                                <tr>
+                                    <td>{i + 1}</td>
+                                    <td>
+                                        <select class="select select-sm select-bordered w-full" on:change=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            rotation.update(|rotation| rotation[i].template = value);
+                                        }>
+                                            {missions.get().into_iter().map(|mission| {
+                                                let selected = mission.name.eq_ignore_ascii_case(&template);
+                                                view! { cx, <option value=mission.name.clone() selected=selected>{mission.name.clone()}</option> }
+                                            }).collect::<Vec<_>>()}
+                                        </select>
+                                    </td>
+                                    <td>
+                                        <select class="select select-sm select-bordered" on:change=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            rotation.update(|rotation| rotation[i].difficulty = value);
+                                        }>
+                                            {DIFFICULTIES.iter().map(|name| view! { cx,
+                                                <option value=*name selected=*name == difficulty>{*name}</option>
+                                            }).collect::<Vec<_>>()}
+                                        </select>
+                                    </td>
+                                    <td>
+                                        <input class="input input-sm input-bordered w-full" placeholder="Daytime=12, AISkill=3" value=params_to_string(&mission.params) on:change=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            rotation.update(|rotation| rotation[i].params = params_from_string(&value));
+                                        } />
+                                    </td>
+                                    <td class="flex gap-1">
+                                        <button class="btn btn-xs btn-ghost" disabled=i == 0 title="Move up" on:click=move |_| rotation.update(|rotation| rotation.swap(i, i - 1))>
+                                            <i class="fa fa-arrow-up"></i>
+                                        </button>
+                                        <button class="btn btn-xs btn-ghost" disabled=i == last title="Move down" on:click=move |_| rotation.update(|rotation| rotation.swap(i, i + 1))>
+                                            <i class="fa fa-arrow-down"></i>
+                                        </button>
+                                        <button class="btn btn-xs btn-ghost" title="Remove" on:click=move |_| rotation.update(|rotation| { rotation.remove(i); })>
+                                            <i class="fa fa-trash"></i>
+                                        </button>
+                                    </td>
+                                </tr>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            }.into_view(cx)
+        }}
+    }
+}
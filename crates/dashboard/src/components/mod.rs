@@ -3,14 +3,22 @@ mod credentials;
 mod dropzone;
 mod edit_view;
 mod header;
+mod instance_select;
 mod left_sidebar;
+mod loaded_preset;
 mod loading;
 mod log_view;
+mod mission_rotation;
 mod nav_link;
+mod preflight;
+mod preset_conflicts;
 mod preset_dlc;
 mod preset_item;
+mod preset_keys;
 mod progress;
+mod restart_schedule;
 mod server_buttons;
+mod server_keys;
 mod steamcmd_dialog;
 mod theme_select;
 mod toast_container;
@@ -20,14 +28,22 @@ pub use credentials::*;
 pub use dropzone::*;
 pub use edit_view::*;
 pub use header::*;
+pub use instance_select::*;
 pub use left_sidebar::*;
+pub use loaded_preset::*;
 pub use loading::*;
 pub use log_view::*;
+pub use mission_rotation::*;
 pub use nav_link::*;
+pub use preflight::*;
+pub use preset_conflicts::*;
 pub use preset_dlc::*;
 pub use preset_item::*;
+pub use preset_keys::*;
 pub use progress::*;
+pub use restart_schedule::*;
 pub use server_buttons::*;
+pub use server_keys::*;
 pub use steamcmd_dialog::*;
 pub use theme_select::*;
 pub use toast_container::*;
@@ -0,0 +1,99 @@
+use api_schema::response::{LaunchPreview, PreflightReport, Severity};
+use leptos::*;
+
+use crate::{app_state::AppState, components::ToastStyle};
+
+#[component]
+pub fn Preflight(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let preview = create_rw_signal(cx, None::<LaunchPreview>);
+    let report = create_rw_signal(cx, None::<PreflightReport>);
+
+    // the results belong to the instance they were loaded for
+    create_effect(cx, move |_| {
+        let _ = app_state.instance.get();
+        preview.set(None);
+        report.set(None);
+    });
+
+    let load_preview = create_action(cx, move |()| async move {
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+        let Some(instance) = app_state.instance.get_untracked() else {
+            return;
+        };
+
+        match api.preflight(instance).await {
+            Ok(new_report) => report.set(Some(new_report)),
+            Err(err) => {
+                report.set(None);
+                app_state.toast(cx, format!("Unable to run preflight: {err}"), Some(ToastStyle::Error));
+            }
+        }
+
+        match api.launch_preview(instance).await {
+            Ok(new_preview) => preview.set(Some(new_preview)),
+            Err(err) => {
+                preview.set(None);
+                app_state.toast(cx, format!("Unable to preview launch: {err}"), Some(ToastStyle::Error));
+            }
+        }
+    });
+
+    view! { cx,
+        <div class="card w-full bg-base-100 shadow-xl mt-8">
+            <div class="card-body">
+                <div class="flex justify-between">
+                    <h2 class="card-title">"Preflight"</h2>
+                    <button class="btn btn-sm btn-ghost hover:glass" on:click=move |_| load_preview.dispatch(()) title="Run preflight">
+                        <i class="fa fa-rotate"></i>
+                    </button>
+                </div>
+                {move || match report.get() {
+                    None => view! { cx, <p class="text-sm">"Run the preflight to see what the manager will execute."</p> }.into_view(cx),
+                    Some(report) => view! { cx,
+                        <table class="table table-compact w-full">
+                            <tbody>
+                                {report.checks.into_iter().map(|check| {
+                                    let (class, icon) = match check.severity {
+                                        Severity::Ok => ("text-success", "fa fa-circle-check"),
+                                        Severity::Warning => ("text-warning", "fa fa-triangle-exclamation"),
+                                        Severity::Error => ("text-error", "fa fa-circle-xmark"),
+                                    };
+
+                                    view! { cx,
+                                        <tr>
+                                            <td class=class><i class=icon></i></td>
+                                            <td class="font-semibold">{check.name}</td>
+                                            <td class="whitespace-normal">{check.message}</td>
+                                        </tr>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </tbody>
+                        </table>
+                    }.into_view(cx),
+                }}
+                {move || preview.get().map(|preview| view! { cx,
+                    {preview.warnings.into_iter().map(|warning| view! { cx,
+                        <div class="alert alert-warning py-2">
+                            <i class="fa fa-triangle-exclamation"></i>
+                            <span>{warning}</span>
+                        </div>
+                    }).collect::<Vec<_>>()}
+                    <h3 class="font-semibold mt-2">"Command line"</h3>
+                    <pre class="bg-base-200 shadow-inner p-2 whitespace-pre-wrap break-all text-sm">{preview.arguments.join(" ")}</pre>
+                    <h3 class="font-semibold mt-2">{format!("Keys ({})", preview.keys.len())}</h3>
+                    <ul class="text-sm">
+                        {preview.keys.into_iter().map(|key| view! { cx, <li>{key}</li> }).collect::<Vec<_>>()}
+                    </ul>
+                    <div class="collapse collapse-arrow bg-base-200 mt-2">
+                        <input type="checkbox" />
+                        <div class="collapse-title font-semibold">"server.cfg.lock"</div>
+                        <div class="collapse-content">
+                            <pre class="text-sm whitespace-pre-wrap">{preview.config.join("\n")}</pre>
+                        </div>
+                    </div>
+                })}
+            </div>
+        </div>
+    }
+}
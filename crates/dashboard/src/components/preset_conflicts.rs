@@ -0,0 +1,87 @@
+use api_schema::response::{ConflictKind, ConflictReport};
+use leptos::*;
+
+use crate::{app_state::AppState, components::ToastStyle};
+
+#[component]
+pub fn PresetConflicts(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let report = create_rw_signal(cx, ConflictReport::default());
+
+    // reloaded whenever the selected preset or its items change
+    let selected = Signal::derive(cx, move || {
+        app_state
+            .presets
+            .get()
+            .into_iter()
+            .find(|preset| preset.selected)
+            .map(|preset| {
+                let enabled = preset
+                    .items
+                    .iter()
+                    .filter(|item| item.enabled && !item.blacklisted)
+                    .map(|item| item.published_file_id)
+                    .collect::<Vec<_>>();
+
+                (preset.id, enabled)
+            })
+    });
+
+    let load_conflicts = create_action(cx, move |preset: &i64| {
+        let preset = *preset;
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.get_conflicts(preset).await {
+                Ok(new_report) => report.set(new_report),
+                Err(err) => {
+                    report.set(ConflictReport::default());
+                    app_state.toast(
+                        cx,
+                        format!("Unable to check for conflicts: {err}"),
+                        Some(ToastStyle::Error),
+                    );
+                }
+            }
+        }
+    });
+
+    create_effect(cx, move |previous: Option<Option<(i64, Vec<i64>)>>| {
+        let selected = selected.get();
+
+        if previous.as_ref() != Some(&selected) {
+            match &selected {
+                Some((preset, _)) => load_conflicts.dispatch(*preset),
+                None => report.set(ConflictReport::default()),
+            }
+        }
+
+        selected
+    });
+
+    view! { cx,
+        {move || report.get().conflicts.into_iter().map(|conflict| {
+            let kind = match conflict.kind {
+                ConflictKind::Pbo => "PBO",
+                ConflictKind::CfgPatches => "CfgPatches",
+                ConflictKind::Prefix => "Prefix",
+            };
+
+            let mods = conflict
+                .mods
+                .iter()
+                .map(|item| format!("{} ({})", item.name, item.published_file_id))
+                .collect::<Vec<_>>();
+
+            view! { cx,
+                <div class="alert alert-warning py-2 mb-1">
+                    <i class="fa fa-triangle-exclamation"></i>
+                    <span class="whitespace-normal">
+                        <span class="font-semibold">{format!("{} {}", kind, conflict.name)}</span>
+                        {format!(" is in {}", mods.join(", "))}
+                    </span>
+                </div>
+            }
+        }).collect::<Vec<_>>()}
+    }
+}
@@ -19,6 +19,7 @@ pub fn PresetItem(cx: Scope, item: PresetItem) -> impl IntoView {
                 enabled: Some(value),
                 position: None,
                 server_mod: None,
+                optional: None,
             };
 
             // send new value to backend
@@ -53,6 +54,24 @@ pub fn PresetItem(cx: Scope, item: PresetItem) -> impl IntoView {
                 enabled: None,
                 position: None,
                 server_mod: Some(value),
+                optional: None,
+            };
+
+            api.update_preset_item(&schema).await.unwrap();
+        }
+    });
+
+    let toggle_optional = create_action(cx, move |()| {
+        let id = item.id;
+        let value = !item.optional;
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+            let schema = UpdatePresetItemSchema {
+                id,
+                enabled: None,
+                position: None,
+                server_mod: None,
+                optional: Some(value),
             };
 
             api.update_preset_item(&schema).await.unwrap();
@@ -84,6 +103,15 @@ pub fn PresetItem(cx: Scope, item: PresetItem) -> impl IntoView {
                         }}
                         </button>
                     </div>
+                    <div class="h-full text-center flex-0 ml-5">
+                        <button class="btn btn-sm btn-ghost hover:glass" on:click=move |_| toggle_optional.dispatch(())>
+                        {if item.optional {
+                            view! { cx, <i class="fa-solid fa-key" title="Optional Mod, only its keys are installed"></i> }
+                        } else {
+                            view! { cx, <i class="fa-solid fa-cubes" title="Loaded by the Server"></i> }
+                        }}
+                        </button>
+                    </div>
                     <div class="h-full text-center flex-0 ml-5">
                         <button class="btn btn-sm btn-ghost hover:glass" on:click=move |_| toggle_blacklist.dispatch(())>
                         {if item.blacklisted {
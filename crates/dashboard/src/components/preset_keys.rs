@@ -0,0 +1,104 @@
+use api_schema::response::KeyReport;
+use leptos::*;
+
+use crate::{app_state::AppState, components::ToastStyle};
+
+#[component]
+pub fn PresetKeys(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let report = create_rw_signal(cx, None::<KeyReport>);
+
+    let selected = Signal::derive(cx, move || {
+        app_state
+            .presets
+            .get()
+            .iter()
+            .find(|preset| preset.selected)
+            .map(|preset| preset.id)
+    });
+
+    // the results belong to the preset they were loaded for
+    create_effect(cx, move |_| {
+        let _ = selected.get();
+        report.set(None);
+    });
+
+    let load_report = create_action(cx, move |()| async move {
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+        let Some(preset) = selected.get_untracked() else {
+            return;
+        };
+
+        match api.get_key_report(preset).await {
+            Ok(new_report) => report.set(Some(new_report)),
+            Err(err) => {
+                report.set(None);
+                app_state.toast(cx, format!("Unable to check keys: {err}"), Some(ToastStyle::Error));
+            }
+        }
+    });
+
+    view! { cx,
+        <div class="flex justify-between items-center mt-8">
+            <div class="text-xl font-semibold">"Keys"</div>
+            <button class="btn btn-sm btn-ghost hover:glass" on:click=move |_| load_report.dispatch(()) title="Check keys and signatures">
+                <i class="fa fa-rotate"></i>
+            </button>
+        </div>
+        <div class="divider mt-2"></div>
+        {move || match report.get() {
+            None => view! { cx, <p class="text-sm">"Check which mods would get players kicked by verifySignatures."</p> }.into_view(cx),
+            Some(report) if report.mods.is_empty() && report.duplicates.is_empty() => view! { cx,
+                <p class="text-sm text-success">"Every mod brings a key and every addon is signed with one."</p>
+            }.into_view(cx),
+            Some(report) => view! { cx,
+                <table class="table table-compact w-full">
+                    <tbody>
+                        {report.mods.into_iter().map(|item| {
+                            let mut problems = Vec::new();
+
+                            if item.keys.is_empty() {
+                                problems.push("No .bikey".to_string());
+                            }
+
+                            if !item.unsigned.is_empty() {
+                                problems.push(format!("{} unsigned: {}", item.unsigned.len(), item.unsigned.join(", ")));
+                            }
+
+                            if !item.unknown_signatures.is_empty() {
+                                let mut authorities = item.unknown_signatures.iter().map(|signature| signature.authority.clone()).collect::<Vec<_>>();
+                                authorities.dedup();
+                                problems.push(format!("{} signed with unknown keys: {}", item.unknown_signatures.len(), authorities.join(", ")));
+                            }
+
+                            view! { cx,
+                                <tr>
+                                    <td class="text-warning"><i class="fa fa-triangle-exclamation"></i></td>
+                                    <td class="font-semibold whitespace-normal">{item.name}</td>
+                                    <td class="whitespace-normal text-sm">{problems.join("; ")}</td>
+                                </tr>
+                            }
+                        }).collect::<Vec<_>>()}
+                        {report.duplicates.into_iter().map(|duplicate| {
+                            let (class, icon) = if duplicate.identical {
+                                ("text-info", "fa fa-circle-info")
+                            } else {
+                                ("text-warning", "fa fa-triangle-exclamation")
+                            };
+
+                            view! { cx,
+                                <tr>
+                                    <td class=class><i class=icon></i></td>
+                                    <td class="font-semibold whitespace-normal">{duplicate.key}</td>
+                                    <td class="whitespace-normal text-sm">
+                                        {format!("{} in {}", if duplicate.identical { "Same key" } else { "Different keys" }, duplicate.mods.join(", "))}
+                                    </td>
+                                </tr>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            }.into_view(cx),
+        }}
+    }
+}
@@ -0,0 +1,117 @@
+use leptos::*;
+
+use crate::{app_state::AppState, components::ToastStyle};
+
+/// The schedule is stored in UTC, the offset of the browser turns it into local times.
+fn offset_minutes() -> i64 {
+    chrono::Local::now().offset().local_minus_utc() as i64 / 60
+}
+
+/// Local times like `04:00, 16:00` separated by commas.
+fn times_to_string(minutes: &[i64]) -> String {
+    let mut local = minutes
+        .iter()
+        .map(|minute| (minute + offset_minutes()).rem_euclid(24 * 60))
+        .collect::<Vec<_>>();
+    local.sort();
+
+    local
+        .iter()
+        .map(|minute| format!("{:02}:{:02}", minute / 60, minute % 60))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn times_from_string(times: &str) -> Result<Vec<i64>, String> {
+    times
+        .split(',')
+        .map(str::trim)
+        .filter(|time| !time.is_empty())
+        .map(|time| {
+            let parsed = time
+                .split_once(':')
+                .and_then(|(hours, minutes)| Some((hours.parse::<i64>().ok()?, minutes.parse::<i64>().ok()?)))
+                .filter(|(hours, minutes)| (0..24).contains(hours) && (0..60).contains(minutes));
+
+            match parsed {
+                Some((hours, minutes)) => Ok((hours * 60 + minutes - offset_minutes()).rem_euclid(24 * 60)),
+                None => Err(format!("{} is not a time like 04:00", time)),
+            }
+        })
+        .collect()
+}
+
+#[component]
+pub fn RestartSchedule(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let times = create_rw_signal(cx, String::default());
+
+    let load = create_action(cx, move |instance: &i64| {
+        let instance = *instance;
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.get_restart_schedule(instance).await {
+                Ok(minutes) => times.set(times_to_string(&minutes)),
+                Err(err) => app_state.toast(
+                    cx,
+                    format!("Unable to load the restart schedule: {err}"),
+                    Some(ToastStyle::Error),
+                ),
+            }
+        }
+    });
+
+    create_effect(cx, move |_| {
+        if let Some(instance) = app_state.instance.get() {
+            load.dispatch(instance);
+        }
+    });
+
+    let save = create_action(cx, move |()| async move {
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+        let Some(instance) = app_state.instance.get_untracked() else {
+            return;
+        };
+
+        let minutes = match times_from_string(&times.get_untracked()) {
+            Ok(minutes) => minutes,
+            Err(err) => {
+                app_state.toast(cx, err, Some(ToastStyle::Error));
+                return;
+            }
+        };
+
+        match api.save_restart_schedule(instance, minutes).await {
+            Ok(minutes) => {
+                times.set(times_to_string(&minutes));
+                app_state.toast(cx, "Restart schedule saved", Some(ToastStyle::Success));
+            }
+            Err(err) => app_state.toast(
+                cx,
+                format!("Unable to save the restart schedule: {err}"),
+                Some(ToastStyle::Error),
+            ),
+        }
+    });
+
+    view! { cx,
+        <div class="card w-full p-6 bg-base-100 shadow-xl mt-2 mb-4">
+            <div class="text-xl font-semibold inline-block">"Scheduled restarts"</div>
+            <div class="divider mt-2"></div>
+            <div class="flex flex-wrap gap-2">
+                <input
+                    type="text"
+                    placeholder="04:00, 16:00"
+                    class="input input-bordered flex-1"
+                    title="Local times of day the running server restarts at, separated by commas"
+                    prop:value={move || times.get()}
+                    on:input=move |ev| times.set(event_target_value(&ev)) />
+                <button class="btn btn-primary" on:click=move |_| save.dispatch(())>
+                    <i class="fa fa-save"></i>
+                    "Save"
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -12,9 +12,11 @@ pub fn ServerButtons(cx: Scope) -> impl IntoView {
     let app_state = use_context::<AppState>(cx).expect("AppState to exist");
     let status = app_state.status;
     let api = app_state.api;
+    let instance = app_state.instance;
 
     let update_arma = create_action(cx, move |_| {
         let api = api.clone().get_untracked().expect("to have found the api provided");
+        let instance = instance.get_untracked();
         async move {
             match api.update_arma().await {
                 Ok(_) => {
@@ -22,10 +24,10 @@ pub fn ServerButtons(cx: Scope) -> impl IntoView {
                 }
                 Err(err) => {
                     status.update(|status| {
-                        let Some(mut status) = status.as_mut() else {
+                        let (Some(mut status), Some(instance)) = (status.as_mut(), instance) else {
                             return;
                         };
-                        status.arma = State::Stopped;
+                        status.instances.insert(instance, State::Stopped);
                     });
                     tracing::error!("Unable to update arma: {err}");
                     app_state.toast(cx, format!("Unable to update arma: {err}"), Some(ToastStyle::Error));
@@ -36,8 +38,9 @@ pub fn ServerButtons(cx: Scope) -> impl IntoView {
 
     let start_arma = create_action(cx, move |_| {
         let api = api.clone().get_untracked().expect("to have found the api provided");
+        let instance = instance.get_untracked().expect("an instance to be selected");
         async move {
-            match api.start_arma().await {
+            match api.start_arma(instance).await {
                 Ok(_) => {
                     tracing::info!("Started arma!");
                 }
@@ -46,7 +49,7 @@ pub fn ServerButtons(cx: Scope) -> impl IntoView {
                         let Some(mut status) = status.as_mut() else {
                             return;
                         };
-                        status.arma = State::Stopped;
+                        status.instances.insert(instance, State::Stopped);
                     });
                     tracing::error!("Unable to start arma: {err}");
                     app_state.toast(cx, format!("Unable to start arma: {err}"), Some(ToastStyle::Error));
@@ -57,8 +60,9 @@ pub fn ServerButtons(cx: Scope) -> impl IntoView {
 
     let stop_arma = create_action(cx, move |_| {
         let api = api.clone().get_untracked().expect("to have found the api provided");
+        let instance = instance.get_untracked().expect("an instance to be selected");
         async move {
-            match api.stop_arma().await {
+            match api.stop_arma(instance).await {
                 Ok(_) => {
                     tracing::info!("Stopped arma!");
                 }
@@ -67,7 +71,7 @@ pub fn ServerButtons(cx: Scope) -> impl IntoView {
                         let Some(mut status) = status.as_mut() else {
                             return;
                         };
-                        status.arma = State::Stopped;
+                        status.instances.insert(instance, State::Stopped);
                     });
                     tracing::error!("Unable to stop arma: {err}");
                     app_state.toast(cx, format!("Unable to stop arma: {err}"), Some(ToastStyle::Error));
@@ -78,29 +82,32 @@ pub fn ServerButtons(cx: Scope) -> impl IntoView {
 
     let restart_arma = create_action(cx, move |_| {
         let api = api.clone().get_untracked().expect("to have found the api provided");
+        let instance = instance.get_untracked().expect("an instance to be selected");
         async move {
-            // match api.restart_arma().await {
-            //     Ok(_) => {
-            //         tracing::info!("Restarted arma!");
-            //     }
-            //     Err(err) => {
-            //         tracing::error!("Unable to restart arma: {err}");
-                    app_state.toast(cx, format!("Not yet implemented, use Stop and Start instead."), Some(ToastStyle::Warning));
-            //     }
-            // }
+            match api.restart_arma(instance).await {
+                Ok(_) => {
+                    tracing::info!("Restarted arma!");
+                }
+                Err(err) => {
+                    tracing::error!("Unable to restart arma: {err}");
+                    app_state.toast(cx, format!("Unable to restart arma: {err}"), Some(ToastStyle::Error));
+                }
+            }
         }
     });
 
     move || {
-        let Some(status) = status.get() else {
-            // If we don't have a status, we can't do anything
+        let (Some(status), Some(instance)) = (status.get(), instance.get()) else {
+            // If we don't have a status or an instance, we can't do anything
             return view! { cx, <span class="loading loading-bars text-primary"></span> }.into_view(cx)
         };
 
+        let arma = status.arma(instance);
+
         // Disable the buttons if steamcmd is running
         let disabled = status.steamcmd != State::Stopped;
 
-        match status.arma {
+        match arma {
             State::Running => {
                 view ! {
                     cx,
@@ -128,7 +135,7 @@ pub fn ServerButtons(cx: Scope) -> impl IntoView {
             _ => {
                 view! { cx,
                     <span class="loading loading-bars text-primary"></span>
-                    {format!("{:?}", status.arma)}
+                    {format!("{:?}", arma)}
                 }.into_view(cx)
             }
 
@@ -0,0 +1,105 @@
+use api_schema::response::{KeyOrigin, ServerKey};
+use leptos::*;
+
+use crate::{app_state::AppState, components::ToastStyle};
+
+#[component]
+pub fn ServerKeys(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let keys = create_rw_signal(cx, Vec::<ServerKey>::new());
+
+    let load_keys = create_action(cx, move |()| async move {
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+
+        match api.get_server_keys().await {
+            Ok(new_keys) => keys.set(new_keys),
+            Err(err) => app_state.toast(cx, format!("Unable to load keys: {err}"), Some(ToastStyle::Error)),
+        }
+    });
+
+    // the keys of the preset are installed when a server starts
+    create_effect(cx, move |_| {
+        let _ = app_state.presets.get();
+        load_keys.dispatch(());
+    });
+
+    let upload_key = create_action(cx, move |file: &web_sys::File| {
+        let file = file.clone();
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.upload_key(&file).await {
+                Ok(key) => {
+                    app_state.toast(cx, format!("Key {} uploaded", key.name), Some(ToastStyle::Success));
+                    load_keys.dispatch(());
+                }
+                Err(err) => app_state.toast(cx, format!("Unable to upload key: {err}"), Some(ToastStyle::Error)),
+            }
+        }
+    });
+
+    let delete_key = create_action(cx, move |name: &String| {
+        let name = name.clone();
+        async move {
+            if !window()
+                .confirm_with_message(&format!("Delete the key {}?", name))
+                .unwrap_or_default()
+            {
+                return;
+            }
+
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.delete_key(&name).await {
+                Ok(_) => keys.update(|keys| keys.retain(|key| key.name != name)),
+                Err(err) => app_state.toast(cx, format!("Unable to delete key: {err}"), Some(ToastStyle::Error)),
+            }
+        }
+    });
+
+    view! { cx,
+        <div class="flex justify-between items-center mt-8">
+            <div class="text-xl font-semibold">"Server Keys"</div>
+            <label class="btn btn-sm btn-ghost hover:glass" title="Upload a key">
+                <i class="fa fa-upload"></i>
+                <input type="file" accept=".bikey" class="hidden" on:change=move |ev| {
+                    let input = event_target::<web_sys::HtmlInputElement>(&ev);
+                    if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                        upload_key.dispatch(file);
+                    }
+                    input.set_value("");
+                } />
+            </label>
+        </div>
+        <div class="divider mt-2"></div>
+        <table class="table table-compact w-full">
+            <tbody>
+                {move || keys.get().into_iter().map(|key| {
+                    let origin = match key.origin {
+                        KeyOrigin::Game => "Game".to_string(),
+                        KeyOrigin::Mod => format!("Mod {}", key.published_file_id.unwrap_or_default()),
+                        KeyOrigin::Dlc => format!("DLC {}", key.dlc.clone().unwrap_or_default()),
+                        KeyOrigin::Manual => "Uploaded".to_string(),
+                        KeyOrigin::Unmanaged => "Added by hand".to_string(),
+                    };
+
+                    // keys of the preset are replaced on every start, deleting them would not last
+                    let deletable = matches!(key.origin, KeyOrigin::Manual | KeyOrigin::Unmanaged);
+                    let name = key.name.clone();
+
+                    view! { cx,
+                        <tr>
+                            <td class="whitespace-normal" title=key.authority.unwrap_or_default()>{key.name}</td>
+                            <td><span class="badge badge-ghost">{origin}</span></td>
+                            <td>
+                                <button class="btn btn-xs btn-ghost" disabled=!deletable on:click=move |_| delete_key.dispatch(name.clone()) title="Delete">
+                                    <i class="fa fa-trash"></i>
+                                </button>
+                            </td>
+                        </tr>
+                    }
+                }).collect::<Vec<_>>()}
+            </tbody>
+        </table>
+    }
+}
@@ -45,7 +45,7 @@ pub fn SteamCmdDialog(cx: Scope) -> impl IntoView {
                 <h3 class="font-bold text-lg">"steamcmd.log"</h3>
                 <div class="grow shrink bg-base-200 shadow-inner">
                 <ClientOnly>
-                    <LogView channel="steamcmd" visible=checked.into() progress=progress />
+                    <LogView channel="steamcmd".to_string().into() visible=checked.into() progress=progress />
                 </ClientOnly>
             </div>
             <ProgressBar values=progress />
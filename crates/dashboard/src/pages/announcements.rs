@@ -0,0 +1,313 @@
+use api_schema::{request::*, response::*};
+use leptos::*;
+
+use crate::{
+    app_state::AppState,
+    components::{RestartSchedule, ToastStyle},
+};
+
+/// The minutes before a restart the countdown warns at.
+const DEFAULT_WARNINGS: &str = "30, 15, 5, 1";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Interval,
+    At,
+    Countdown,
+}
+
+#[component]
+pub fn Announcements(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let status = app_state.status;
+    let announcements = create_rw_signal(cx, Vec::<Announcement>::new());
+
+    let mode = create_rw_signal(cx, Mode::Interval);
+    let message = create_rw_signal(cx, String::default());
+    let minutes = create_rw_signal(cx, 20i64);
+    let at = create_rw_signal(cx, String::default());
+    let warnings = create_rw_signal(cx, DEFAULT_WARNINGS.to_string());
+
+    let running = Signal::derive(cx, move || {
+        let Some(instance) = app_state.instance.get() else {
+            return false;
+        };
+
+        status
+            .get()
+            .map(|status| status.arma(instance) == State::Running)
+            .unwrap_or(false)
+    });
+
+    let load = create_action(cx, move |_: &()| async move {
+        let Some(instance) = app_state.instance.get_untracked() else {
+            return;
+        };
+
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+
+        match api.get_announcements(instance).await {
+            Ok(list) => announcements.set(list),
+            Err(err) => app_state.toast(
+                cx,
+                format!("Unable to load announcements: {err}"),
+                Some(ToastStyle::Error),
+            ),
+        }
+    });
+
+    create_effect(cx, move |_| {
+        if app_state.instance.get().is_some() {
+            load.dispatch(());
+        }
+    });
+
+    let add = create_action(cx, move |_: &()| async move {
+        let Some(instance) = app_state.instance.get_untracked() else {
+            return;
+        };
+
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+        let text = message.get_untracked();
+
+        let schemas = match mode.get_untracked() {
+            Mode::Interval => vec![AnnouncementSchema {
+                message: text,
+                schedule: AnnouncementSchedule::Interval {
+                    minutes: minutes.get_untracked(),
+                },
+                enabled: true,
+            }],
+            Mode::At => {
+                let Some(timestamp) = parse_time(&at.get_untracked()) else {
+                    app_state.toast(cx, "Pick a date and time", Some(ToastStyle::Error));
+                    return;
+                };
+
+                vec![AnnouncementSchema {
+                    message: text,
+                    schedule: AnnouncementSchedule::At { timestamp },
+                    enabled: true,
+                }]
+            }
+            Mode::Countdown => countdown(&text, &warnings.get_untracked()),
+        };
+
+        if schemas.is_empty() {
+            app_state.toast(
+                cx,
+                "Enter the minutes before the restart to warn at",
+                Some(ToastStyle::Error),
+            );
+            return;
+        }
+
+        for schema in schemas {
+            if let Err(err) = api.create_announcement(instance, &schema).await {
+                app_state.toast(
+                    cx,
+                    format!("Unable to add the announcement: {err}"),
+                    Some(ToastStyle::Error),
+                );
+                break;
+            }
+        }
+
+        message.set(String::default());
+        load.dispatch(());
+    });
+
+    let toggle = create_action(cx, move |announcement: &Announcement| {
+        let announcement = announcement.clone();
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            let schema = AnnouncementSchema {
+                message: announcement.message,
+                schedule: announcement.schedule,
+                enabled: !announcement.enabled,
+            };
+
+            if let Err(err) = api
+                .update_announcement(announcement.instance_id, announcement.id, &schema)
+                .await
+            {
+                app_state.toast(
+                    cx,
+                    format!("Unable to update the announcement: {err}"),
+                    Some(ToastStyle::Error),
+                );
+            }
+
+            load.dispatch(());
+        }
+    });
+
+    let delete = create_action(cx, move |announcement: &Announcement| {
+        let announcement = announcement.clone();
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.delete_announcement(announcement.instance_id, announcement.id).await {
+                Ok(_) => load.dispatch(()),
+                Err(err) => app_state.toast(
+                    cx,
+                    format!("Unable to delete the announcement: {err}"),
+                    Some(ToastStyle::Error),
+                ),
+            }
+        }
+    });
+
+    view! { cx,
+        <RestartSchedule />
+        <div class="card w-full p-6 bg-base-100 shadow-xl mt-2 mb-4">
+            <div class="flex justify-between items-center">
+                <div class="text-xl font-semibold inline-block">"Announcements"</div>
+                <Show when=move || !running.get() fallback=move |_| ()>
+                    <div class="badge badge-warning">"Paused while the server is stopped"</div>
+                </Show>
+            </div>
+            <div class="divider mt-2"></div>
+            <div class="flex flex-wrap gap-2 mb-4">
+                <select class="select select-bordered" on:change=move |ev| {
+                    let selected = match event_target_value(&ev).as_str() {
+                        "at" => Mode::At,
+                        "countdown" => Mode::Countdown,
+                        _ => Mode::Interval,
+                    };
+
+                    // the countdown fills in the minutes left
+                    if selected == Mode::Countdown && message.get_untracked().is_empty() {
+                        message.set("Server restart in {minutes} minutes".to_string());
+                    }
+
+                    mode.set(selected);
+                }>
+                    <option value="interval" selected>"Every"</option>
+                    <option value="at">"Once at"</option>
+                    <option value="countdown">"Before each scheduled restart"</option>
+                </select>
+                <Show when=move || mode.get() == Mode::Interval fallback=move |_| ()>
+                    <input
+                        type="number"
+                        min="1"
+                        max="10080"
+                        class="input input-bordered w-32"
+                        title="Minutes"
+                        prop:value={move || minutes.get().to_string()}
+                        on:input=move |ev| minutes.set(event_target_value(&ev).parse().unwrap_or_default()) />
+                </Show>
+                <Show when=move || mode.get() == Mode::At fallback=move |_| ()>
+                    <input
+                        type="datetime-local"
+                        class="input input-bordered"
+                        prop:value={move || at.get()}
+                        on:input=move |ev| at.set(event_target_value(&ev)) />
+                </Show>
+                <Show when=move || mode.get() == Mode::Countdown fallback=move |_| ()>
+                    <input
+                        type="text"
+                        class="input input-bordered w-40"
+                        title="Minutes before the restart, separated by commas"
+                        prop:value={move || warnings.get()}
+                        on:input=move |ev| warnings.set(event_target_value(&ev)) />
+                </Show>
+                <input
+                    type="text"
+                    placeholder="Message"
+                    class="input input-bordered flex-1"
+                    prop:value={move || message.get()}
+                    on:input=move |ev| message.set(event_target_value(&ev)) />
+                <button
+                    class="btn btn-primary"
+                    disabled=move || message.get().trim().is_empty()
+                    on:click=move |_| add.dispatch(())>
+                    <i class="fa fa-plus"></i>
+                    "Add"
+                </button>
+            </div>
+            <table class="table table-zebra w-full">
+                <thead>
+                    <tr>
+                        <th>"Message"</th>
+                        <th>"When"</th>
+                        <th>"Enabled"</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    <For each={move || announcements.get()} key={|announcement| (announcement.id, announcement.enabled)} view={move |cx, announcement| {
+                        let toggled = announcement.clone();
+                        let deleted = announcement.clone();
+
+                        view! { cx,
+                            <tr>
+                                <td class="whitespace-normal">{announcement.message.clone()}</td>
+                                <td>{schedule(announcement.schedule)}</td>
+                                <td>
+                                    <input
+                                        type="checkbox"
+                                        class="toggle toggle-success"
+                                        prop:checked=announcement.enabled
+                                        on:change=move |_| toggle.dispatch(toggled.clone()) />
+                                </td>
+                                <td>
+                                    <button class="btn btn-sm btn-ghost" on:click=move |_| delete.dispatch(deleted.clone()) title="Delete">
+                                        <i class="fa fa-trash"></i>
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }} />
+                </tbody>
+            </table>
+        </div>
+    }
+}
+
+/// One announcement per warning, `{minutes}` in the message is replaced with the minutes left.
+fn countdown(message: &str, warnings: &str) -> Vec<AnnouncementSchema> {
+    warnings
+        .split(',')
+        .filter_map(|warning| warning.trim().parse::<i64>().ok())
+        .filter(|warning| *warning > 0)
+        .map(|warning| AnnouncementSchema {
+            message: message.replace("{minutes}", &warning.to_string()),
+            schedule: AnnouncementSchedule::BeforeRestart { minutes: warning },
+            enabled: true,
+        })
+        .collect()
+}
+
+fn schedule(schedule: AnnouncementSchedule) -> String {
+    match schedule {
+        AnnouncementSchedule::Interval { minutes: 1 } => "Every minute".to_string(),
+        AnnouncementSchedule::Interval { minutes } => format!("Every {} minutes", minutes),
+        AnnouncementSchedule::At { timestamp } if timestamp <= chrono::Utc::now().timestamp() => {
+            format!("{} (passed)", format_time(timestamp))
+        }
+        AnnouncementSchedule::At { timestamp } => format_time(timestamp),
+        AnnouncementSchedule::BeforeRestart { minutes: 1 } => "A minute before each restart".to_string(),
+        AnnouncementSchedule::BeforeRestart { minutes } => format!("{} minutes before each restart", minutes),
+    }
+}
+
+/// The value of a `datetime-local` input in local time.
+fn parse_time(value: &str) -> Option<i64> {
+    use chrono::{Local, NaiveDateTime, TimeZone};
+
+    let time = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M").ok()?;
+
+    Local.from_local_datetime(&time).single().map(|time| time.timestamp())
+}
+
+fn format_time(timestamp: i64) -> String {
+    use chrono::{Local, TimeZone};
+
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
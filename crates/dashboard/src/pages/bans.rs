@@ -0,0 +1,263 @@
+use api_schema::{request::*, response::*};
+use leptos::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{app_state::AppState, components::ToastStyle};
+
+#[component]
+pub fn Bans(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let bans = create_rw_signal(cx, Vec::<Ban>::new());
+
+    let kind = create_rw_signal(cx, BanKind::Guid);
+    let value = create_rw_signal(cx, String::default());
+    let minutes = create_rw_signal(cx, 0i64);
+    let reason = create_rw_signal(cx, String::default());
+
+    let load = create_action(cx, move |_: &()| async move {
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+
+        match api.get_bans().await {
+            Ok(list) => bans.set(list),
+            Err(err) => app_state.toast(cx, format!("Unable to load bans: {err}"), Some(ToastStyle::Error)),
+        }
+    });
+
+    load.dispatch(());
+
+    let add = create_action(cx, move |_: &()| async move {
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+
+        let schema = CreateBanSchema {
+            kind: kind.get_untracked(),
+            value: value.get_untracked(),
+            minutes: minutes.get_untracked(),
+            reason: reason.get_untracked(),
+        };
+
+        match api.create_ban(&schema).await {
+            Ok(ban) => {
+                app_state.toast(cx, format!("{} banned", ban.value), Some(ToastStyle::Success));
+                value.set(String::default());
+                reason.set(String::default());
+                load.dispatch(());
+            }
+            Err(err) => app_state.toast(cx, format!("Unable to ban: {err}"), Some(ToastStyle::Error)),
+        }
+    });
+
+    let delete = create_action(cx, move |ban: &Ban| {
+        let ban = ban.clone();
+        async move {
+            if !window()
+                .confirm_with_message(&format!("Unban {}?", ban.value))
+                .unwrap_or_default()
+            {
+                return;
+            }
+
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.delete_ban(ban.id).await {
+                Ok(_) => load.dispatch(()),
+                Err(err) => app_state.toast(cx, format!("Unable to unban: {err}"), Some(ToastStyle::Error)),
+            }
+        }
+    });
+
+    let import = create_action(cx, move |(format, file): &(BanListFormat, web_sys::File)| {
+        let format = *format;
+        let file = file.clone();
+        async move {
+            let Some(content) = JsFuture::from(file.text()).await.ok().and_then(|text| text.as_string()) else {
+                app_state.toast(cx, format!("Unable to read {}", file.name()), Some(ToastStyle::Error));
+                return;
+            };
+
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.import_bans(&ImportBansSchema { format, content }).await {
+                Ok(response) => {
+                    app_state.toast(cx, response.response, Some(ToastStyle::Success));
+                    load.dispatch(());
+                }
+                Err(err) => app_state.toast(cx, format!("Unable to import bans: {err}"), Some(ToastStyle::Error)),
+            }
+        }
+    });
+
+    let import_file = move |format: BanListFormat, ev: ev::Event| {
+        let input = event_target::<web_sys::HtmlInputElement>(&ev);
+
+        if let Some(file) = input.files().and_then(|files| files.get(0)) {
+            import.dispatch((format, file));
+        }
+
+        // picking the same file again should import it again
+        input.set_value("");
+    };
+
+    let export_url = move |format: BanListFormat| {
+        app_state
+            .api
+            .get()
+            .map(|api| api.ban_export_url(format))
+            .unwrap_or_default()
+    };
+
+    view! { cx,
+        <div class="card w-full p-6 bg-base-100 shadow-xl mt-2 mb-4">
+            <div class="flex justify-between items-center">
+                <div class="text-xl font-semibold inline-block">"Bans"</div>
+                <div class="dropdown dropdown-end">
+                    <label tabindex="0" class="btn btn-ghost">
+                        <i class="fa fa-file-import"></i>
+                        "Import / Export"
+                    </label>
+                    <ul tabindex="0" class="dropdown-content menu p-2 shadow bg-base-200 rounded-box w-64 z-10">
+                        <li>
+                            <label title="Steam UIDs, one per line">
+                                <i class="fa fa-upload"></i>
+                                "Import ban.txt"
+                                <input type="file" accept=".txt" class="hidden" on:change=move |ev| import_file(BanListFormat::Engine, ev) />
+                            </label>
+                        </li>
+                        <li>
+                            <label title="BattlEye GUIDs and IPs">
+                                <i class="fa fa-upload"></i>
+                                "Import BattlEye bans.txt"
+                                <input type="file" accept=".txt" class="hidden" on:change=move |ev| import_file(BanListFormat::BattlEye, ev) />
+                            </label>
+                        </li>
+                        <li>
+                            <a href={move || export_url(BanListFormat::Engine)} target="_blank">
+                                <i class="fa fa-download"></i>
+                                "Export ban.txt"
+                            </a>
+                        </li>
+                        <li>
+                            <a href={move || export_url(BanListFormat::BattlEye)} target="_blank">
+                                <i class="fa fa-download"></i>
+                                "Export BattlEye bans.txt"
+                            </a>
+                        </li>
+                    </ul>
+                </div>
+            </div>
+            <div class="divider mt-2"></div>
+            <div class="flex flex-wrap gap-2 mb-4">
+                <select class="select select-bordered" on:change=move |ev| {
+                    let selected = match event_target_value(&ev).as_str() {
+                        "uid" => BanKind::Uid,
+                        "ip" => BanKind::Ip,
+                        _ => BanKind::Guid,
+                    };
+
+                    kind.set(selected);
+
+                    // the engine bans forever
+                    if selected == BanKind::Uid {
+                        minutes.set(0);
+                    }
+                }>
+                    <option value="guid" selected>"GUID"</option>
+                    <option value="ip">"IP"</option>
+                    <option value="uid">"Steam UID"</option>
+                </select>
+                <input
+                    type="text"
+                    placeholder="GUID, IP or Steam UID"
+                    class="input input-bordered flex-1 font-mono"
+                    prop:value={move || value.get()}
+                    on:input=move |ev| value.set(event_target_value(&ev)) />
+                <input
+                    type="number"
+                    min="0"
+                    class="input input-bordered w-32"
+                    title="Minutes, 0 bans permanently"
+                    disabled=move || kind.get() == BanKind::Uid
+                    prop:value={move || minutes.get().to_string()}
+                    on:input=move |ev| minutes.set(event_target_value(&ev).parse().unwrap_or_default()) />
+                <input
+                    type="text"
+                    placeholder="Reason"
+                    class="input input-bordered flex-1"
+                    disabled=move || kind.get() == BanKind::Uid
+                    prop:value={move || reason.get()}
+                    on:input=move |ev| reason.set(event_target_value(&ev)) />
+                <button
+                    class="btn btn-error"
+                    disabled=move || value.get().trim().is_empty()
+                    on:click=move |_| add.dispatch(())>
+                    <i class="fa fa-ban"></i>
+                    "Ban"
+                </button>
+            </div>
+            <table class="table table-zebra w-full">
+                <thead>
+                    <tr>
+                        <th>"Type"</th>
+                        <th>"Banned"</th>
+                        <th>"Expires"</th>
+                        <th>"Reason"</th>
+                        <th>"By"</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    <For each={move || bans.get()} key={|ban| (ban.id, ban.created_at)} view={move |cx, ban| {
+                        let unban = ban.clone();
+
+                        view! { cx,
+                            <tr>
+                                <td>{kind_name(ban.kind)}</td>
+                                <td class="font-mono text-xs">{ban.value.clone()}</td>
+                                <td>{expiry(ban.expires_at)}</td>
+                                <td class="whitespace-normal">{ban.reason.clone()}</td>
+                                <td>
+                                    <div>{ban.author.clone()}</div>
+                                    <div class="text-xs opacity-50">{format_time(ban.created_at)}</div>
+                                </td>
+                                <td>
+                                    <button class="btn btn-sm btn-ghost" on:click=move |_| delete.dispatch(unban.clone()) title="Unban">
+                                        <i class="fa fa-trash"></i>
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }} />
+                </tbody>
+            </table>
+        </div>
+    }
+}
+
+fn kind_name(kind: BanKind) -> &'static str {
+    match kind {
+        BanKind::Uid => "Steam UID",
+        BanKind::Guid => "GUID",
+        BanKind::Ip => "IP",
+    }
+}
+
+fn expiry(expires_at: Option<i64>) -> String {
+    let Some(expires_at) = expires_at else {
+        return "Never".to_string();
+    };
+
+    if expires_at <= chrono::Utc::now().timestamp() {
+        return "Expired".to_string();
+    }
+
+    format_time(expires_at)
+}
+
+fn format_time(timestamp: i64) -> String {
+    use chrono::{Local, TimeZone};
+
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,248 @@
+use api_schema::{request::*, response::*};
+use futures::channel::oneshot;
+use leptos::{html::Div, *};
+
+use crate::{app_state::AppState, components::ToastStyle, sse::create_sse};
+
+const PAGE_SIZE: i64 = 100;
+
+const KINDS: [(MessageKind, &str); 7] = [
+    (MessageKind::Chat, "Chat"),
+    (MessageKind::Connect, "Connects"),
+    (MessageKind::Disconnect, "Disconnects"),
+    (MessageKind::Kick, "Kicks"),
+    (MessageKind::Filter, "BattlEye filters"),
+    (MessageKind::Admin, "Admin"),
+    (MessageKind::Other, "Other"),
+];
+
+#[component]
+pub fn Chat(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let status = app_state.status;
+
+    // oldest first, the way they are shown
+    let messages = create_rw_signal(cx, Vec::<ServerMessage>::new());
+    let has_more = create_rw_signal(cx, false);
+    let search = create_rw_signal(cx, String::default());
+    let kind = create_rw_signal(cx, None::<MessageKind>);
+    let message = create_rw_signal(cx, String::default());
+    let sse = store_value(cx, None::<oneshot::Sender<()>>);
+    let list = create_node_ref::<Div>(cx);
+
+    let running = Signal::derive(cx, move || {
+        let Some(instance) = app_state.instance.get() else {
+            return false;
+        };
+
+        status
+            .get()
+            .map(|status| status.arma(instance) == State::Running)
+            .unwrap_or(false)
+    });
+
+    let scroll_down = move || {
+        request_animation_frame(move || {
+            if let Some(list) = list.get_untracked() {
+                list.set_scroll_top(list.scroll_height());
+            }
+        });
+    };
+
+    let matches = move |message: &ServerMessage| {
+        let search = search.get_untracked().trim().to_lowercase();
+
+        kind.get_untracked().is_none_or(|kind| kind == message.kind)
+            && (search.is_empty() || message.message.to_lowercase().contains(&search))
+    };
+
+    // `before` loads the page older than the given message, without it the list starts over
+    let load = create_action(cx, move |before: &Option<i64>| {
+        let before = *before;
+        async move {
+            let Some(instance) = app_state.instance.get_untracked() else {
+                return;
+            };
+
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            let query = MessageQuery {
+                search: Some(search.get_untracked()).filter(|search| !search.trim().is_empty()),
+                kind: kind.get_untracked(),
+                before,
+                limit: Some(PAGE_SIZE),
+            };
+
+            match api.get_messages(instance, &query).await {
+                Ok(mut page) => {
+                    has_more.set(page.len() as i64 == PAGE_SIZE);
+                    page.reverse();
+
+                    if before.is_some() {
+                        messages.update(|messages| {
+                            page.append(messages);
+                            *messages = page;
+                        });
+                    } else {
+                        messages.set(page);
+                        scroll_down();
+                    }
+                }
+                Err(err) => app_state.toast(cx, format!("Unable to load messages: {err}"), Some(ToastStyle::Error)),
+            }
+        }
+    });
+
+    create_effect(cx, move |_| {
+        let instance = app_state.instance.get();
+
+        if let Some(sse) = sse.update_value(|sse| sse.take()).flatten() {
+            let _ = sse.send(());
+        }
+
+        let Some(instance) = instance else {
+            return;
+        };
+
+        load.dispatch(None);
+
+        let abort = create_sse(
+            cx,
+            format!("instances/{}/messages", instance),
+            vec!["message".to_string()],
+            move |_, message: ServerMessage| {
+                if matches(&message) {
+                    messages.update(|messages| messages.push(message));
+                    scroll_down();
+                }
+            },
+        );
+
+        sse.set_value(Some(abort));
+    });
+
+    on_cleanup(cx, move || {
+        if let Some(sse) = sse.update_value(|sse| sse.take()).flatten() {
+            let _ = sse.send(());
+        }
+    });
+
+    let send = create_action(cx, move |text: &String| {
+        let text = text.clone();
+        async move {
+            let Some(instance) = app_state.instance.get_untracked() else {
+                return;
+            };
+
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            let schema = SendMessageSchema {
+                slot: None,
+                guid: None,
+                message: text,
+            };
+
+            match api.send_message(instance, &schema).await {
+                Ok(_) => message.set(String::default()),
+                Err(err) => app_state.toast(cx, format!("Unable to send message: {err}"), Some(ToastStyle::Error)),
+            }
+        }
+    });
+
+    let send_message = move || {
+        let text = message.get_untracked();
+
+        if !text.trim().is_empty() {
+            send.dispatch(text);
+        }
+    };
+
+    view! { cx,
+        <div class="card w-full p-6 bg-base-100 shadow-xl mt-2 mb-4 flex flex-col h-[calc(100vh-8rem)]">
+            <div class="flex flex-wrap justify-between items-center gap-2">
+                <div class="text-xl font-semibold inline-block">"Chat"</div>
+                <div class="flex gap-2">
+                    <input
+                        type="text"
+                        placeholder="Search…"
+                        class="input input-bordered input-sm"
+                        prop:value={move || search.get()}
+                        on:input=move |ev| search.set(event_target_value(&ev))
+                        on:keydown=move |ev| {
+                            if ev.key() == "Enter" {
+                                load.dispatch(None);
+                            }
+                        } />
+                    <select class="select select-bordered select-sm" on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        kind.set(KINDS.iter().find(|(_, name)| *name == value).map(|(kind, _)| *kind));
+                        load.dispatch(None);
+                    }>
+                        <option value="" selected>"Everything"</option>
+                        {KINDS.iter().map(|(_, name)| view! { cx, <option value={*name}>{*name}</option> }).collect::<Vec<_>>()}
+                    </select>
+                    <button class="btn btn-sm btn-ghost" on:click=move |_| load.dispatch(None) title="Search">
+                        <i class="fa fa-magnifying-glass"></i>
+                    </button>
+                </div>
+            </div>
+            <div class="divider mt-2"></div>
+            <div class="flex-1 overflow-y-auto font-mono text-sm" node_ref=list>
+                <Show when=move || has_more.get() fallback=move |_| ()>
+                    <div class="text-center">
+                        <button class="btn btn-xs btn-ghost" on:click=move |_| load.dispatch(messages.get_untracked().first().map(|message| message.id))>
+                            "Load older messages"
+                        </button>
+                    </div>
+                </Show>
+                <For each={move || messages.get()} key={|message| message.id} view={move |cx, message| view! { cx,
+                    <div class={format!("px-2 py-0.5 {}", kind_class(message.kind))}>
+                        <span class="opacity-50 mr-2">{format_time(message.created_at)}</span>
+                        {message.message.clone()}
+                    </div>
+                }} />
+            </div>
+            <div class="join w-full mt-4">
+                <input
+                    type="text"
+                    placeholder="Message to everyone…"
+                    class="input input-bordered join-item flex-1"
+                    disabled=move || !running.get()
+                    prop:value={move || message.get()}
+                    on:input=move |ev| message.set(event_target_value(&ev))
+                    on:keydown=move |ev| {
+                        if ev.key() == "Enter" {
+                            send_message();
+                        }
+                    } />
+                <button
+                    class="btn join-item"
+                    disabled=move || !running.get() || message.get().trim().is_empty()
+                    on:click=move |_| send_message()>
+                    <i class="fa fa-paper-plane"></i>
+                    "Send"
+                </button>
+            </div>
+        </div>
+    }
+}
+
+fn kind_class(kind: MessageKind) -> &'static str {
+    match kind {
+        MessageKind::Chat => "",
+        MessageKind::Connect | MessageKind::Disconnect => "text-info",
+        MessageKind::Kick | MessageKind::Filter => "text-error",
+        MessageKind::Admin => "text-warning",
+        MessageKind::Other => "opacity-70",
+    }
+}
+
+fn format_time(timestamp: i64) -> String {
+    use chrono::{Local, TimeZone};
+
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format("%H:%M:%S").to_string())
+        .unwrap_or_default()
+}
@@ -1,15 +1,30 @@
-use api_schema::{a2s::Player, response::FilteredUser};
+use api_schema::{
+    a2s::{A2sState, Player},
+    response::FilteredUser,
+};
 use leptos::*;
 use leptos_router::*;
 
-use crate::{api::AuthorizedApi, app_state::AppState};
+use crate::{
+    api::AuthorizedApi,
+    app_state::AppState,
+    components::{LoadedPreset, Preflight},
+};
 
 use super::Page;
 
 #[component]
 pub fn Dashboard(cx: Scope) -> impl IntoView {
     let app_state = use_context::<AppState>(cx).expect("AppState to exist");
-    // if None show "Loading..."
+    // if None show why, the server only answers while it is running
+    let placeholder = move || {
+        match app_state.a2s_state.get() {
+            A2sState::Stopped => "Server stopped",
+            A2sState::Unreachable => "Unreachable",
+            A2sState::Waiting | A2sState::Reachable => "Loading...",
+        }
+        .to_string()
+    };
     let server_name = Signal::derive(cx, move || app_state.server_info.get().map(|info| info.name.clone()));
     let mission = Signal::derive(cx, move || app_state.server_info.get().map(|info| info.game.clone()));
     let map = Signal::derive(cx, move || app_state.server_info.get().map(|info| info.map.clone()));
@@ -34,21 +49,21 @@ pub fn Dashboard(cx: Scope) -> impl IntoView {
                 <div class="stat">
                     <div class="stat-title">"Server Name"</div>
                     <div class="stat-desc">
-                        {move || server_name.get().unwrap_or_else(|| "Loading...".to_string())}
+                        {move || server_name.get().unwrap_or_else(placeholder)}
                     </div>
                 </div>
 
                 <div class="stat">
                     <div class="stat-title">"Map"</div>
                     <div class="stat-desc">
-                        {move || map.get().unwrap_or_else(|| "Loading...".to_string())}
+                        {move || map.get().unwrap_or_else(placeholder)}
                     </div>
                 </div>
 
                 <div class="stat">
                     <div class="stat-title">"Mission"</div>
                     <div class="stat-desc">
-                        {move || mission.get().unwrap_or_else(|| "Loading...".to_string())}
+                        {move || mission.get().unwrap_or_else(placeholder)}
                     </div>
                 </div>
 
@@ -88,6 +103,10 @@ pub fn Dashboard(cx: Scope) -> impl IntoView {
                     </div>
                 </div>
             </div>
+
+            <Preflight />
+
+            <LoadedPreset />
         </div>
     }
 }
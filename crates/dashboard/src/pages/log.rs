@@ -1,22 +1,78 @@
-use api_schema::response::FilteredUser;
+use api_schema::response::{FilteredUser, State};
 use leptos::*;
 use leptos_router::*;
 
-use crate::{api::AuthorizedApi, components::*};
+use crate::{api::AuthorizedApi, app_state::AppState, components::*};
 
 use super::Page;
 
 #[component]
 pub fn Log(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let status = app_state.status;
+    let instance = app_state.instance;
+    let instances = app_state.instances;
+
+    let log_channel = create_rw_signal(cx, "arma".to_string()); // default
+
+    let headless_clients = Signal::derive(cx, move || {
+        let selected = instance.get();
+        instances
+            .get()
+            .into_iter()
+            .find(|i| Some(i.id) == selected)
+            .map(|i| i.headless_clients as usize)
+            .unwrap_or_default()
+    });
+
+    // the headless clients of another instance might not exist, go back to the server log
+    create_effect(cx, move |_| {
+        let _ = instance.get();
+        log_channel.set("arma".to_string());
+    });
+
     view! { cx,
         <div class="card w-full flex-1 p-6 bg-base-100 shadow-xl mt-2 mb-4">
             <div class="text-xl font-semibold inline-block">
-                "Arma Logs"
+                <div class="dropdown">
+                    <label class="btn gap-1 normal-case btn-ghost" tabindex="0">
+                        {move || match log_channel.get().as_str() {
+                            "arma" => "Arma Logs".to_string(),
+                            channel => format!("Headless Client {}", channel.trim_start_matches("hc")),
+                        }}
+                        <i class="fa fa-caret-down"></i>
+                    </label>
+                    <ul tabindex="0" class="dropdown-content menu p-2 shadow-lg bg-base-100 rounded-box w-fit text-sm">
+                        <li>
+                            <div class="flex flex-1 grow items-center" onClick="document.activeElement.blur();" on:click=move |_| log_channel.set("arma".to_string())>
+                                <a href="#">"Arma Logs"</a>
+                            </div>
+                        </li>
+                        {move || (1..=headless_clients.get()).map(|index| {
+                            let channel = format!("hc{}", index);
+                            let state = move || {
+                                let (Some(status), Some(instance)) = (status.get(), instance.get()) else {
+                                    return State::Stopped;
+                                };
+                                status.headless_client(instance, index)
+                            };
+
+                            view! { cx,
+                                <li>
+                                    <div class="flex flex-1 grow items-center justify-between gap-2" onClick="document.activeElement.blur();" on:click=move |_| log_channel.set(channel.clone())>
+                                        <a href="#">{format!("Headless Client {}", index)}</a>
+                                        <span class="badge badge-sm" class:badge-success=move || state() == State::Running>{move || format!("{:?}", state())}</span>
+                                    </div>
+                                </li>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </ul>
+                </div>
             </div>
             <div class="divider mt-2"></div>
             <div class="h-full w-full grow bg-base-200 shadow-inner">
                 <ClientOnly>
-                <LogView channel="arma" visible=true.into() />
+                <LogView channel=Signal::derive(cx, move || log_channel.get()).into() visible=true.into() />
                 </ClientOnly>
             </div>
         </div>
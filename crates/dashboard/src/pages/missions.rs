@@ -1,12 +1,91 @@
+use api_schema::response::{Mission, MissionAddonCheck, MissionAddonReport};
 use leptos::*;
 use leptos_router::*;
 
-use crate::app_state::AppState;
+use crate::{
+    app_state::AppState,
+    components::{MissionRotation, ToastStyle},
+};
 
 #[component]
 pub fn Missions(cx: Scope) -> impl IntoView {
     let app_state = use_context::<AppState>(cx).expect("AppState to exist");
     let missions = app_state.missions;
+    let addon_report = create_rw_signal(cx, MissionAddonReport::default());
+
+    let load_addon_report = create_action(cx, move |instance: &i64| {
+        let instance = *instance;
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            // without a preset there is nothing to check against, the missions are listed either way
+            addon_report.set(api.get_mission_addons(instance).await.unwrap_or_default());
+        }
+    });
+
+    create_effect(cx, move |_| {
+        // check again whenever a mission is added, replaced or removed
+        missions.with(|_| ());
+
+        if let Some(instance) = app_state.instance.get() {
+            load_addon_report.dispatch(instance);
+        }
+    });
+
+    let rename_mission = create_action(cx, move |mission: &Mission| {
+        let mission = mission.clone();
+        async move {
+            let Ok(Some(name)) = window().prompt_with_message_and_default("New mission name", &mission.name) else {
+                return;
+            };
+
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.rename_mission(mission.id, name).await {
+                Ok(renamed) => update_mission(missions, renamed),
+                Err(e) => app_state.toast(cx, format!("Failed to rename mission: {}", e), Some(ToastStyle::Error)),
+            }
+        }
+    });
+
+    let replace_mission = create_action(cx, move |(id, file): &(i64, web_sys::File)| {
+        let id = *id;
+        let file = file.clone();
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.replace_mission(id, &file).await {
+                Ok(replaced) => {
+                    app_state.toast(
+                        cx,
+                        format!("{} updated to version {}", replaced.name, replaced.version),
+                        Some(ToastStyle::Success),
+                    );
+                    update_mission(missions, replaced);
+                }
+                Err(e) => app_state.toast(cx, format!("Failed to replace mission: {}", e), Some(ToastStyle::Error)),
+            }
+        }
+    });
+
+    let delete_mission = create_action(cx, move |mission: &Mission| {
+        let mission = mission.clone();
+        async move {
+            if !window()
+                .confirm_with_message(&format!("Delete {} and all its previous versions?", mission.name))
+                .unwrap_or_default()
+            {
+                return;
+            }
+
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.delete_mission(mission.id).await {
+                Ok(_) => missions.update(|missions| missions.retain(|m| m.id != mission.id)),
+                Err(e) => app_state.toast(cx, format!("Failed to delete mission: {}", e), Some(ToastStyle::Error)),
+            }
+        }
+    });
 
     view! { cx,
         <div class="card w-full flex-1 p-6 bg-base-100 shadow-xl mt-2 mb-4">
@@ -19,17 +98,116 @@ pub fn Missions(cx: Scope) -> impl IntoView {
                     <thead>
                         <tr>
                             <th>"Mission"</th>
+                            <th>"World"</th>
+                            <th>"Type"</th>
+                            <th>"Players"</th>
+                            <th>"Author"</th>
+                            <th>"Addons"</th>
+                            <th>"Version"</th>
+                            <th></th>
                         </tr>
                     </thead>
                     <tbody>
-                        <For each={move || missions.get()} key={move |mission| mission.clone()} view={move |cx, mission| view! { cx,
+                        <For each={move || missions.get()} key={move |mission| (mission.id, mission.version, mission.name.clone())} view={move |cx, mission| {
+                            let download_url = app_state.api.get_untracked().map(|api| api.mission_download_url(mission.id));
+                            let id = mission.id;
+                            let rename = mission.clone();
+                            let delete = mission.clone();
+                            let name = mission.name.clone();
+
+                            view! { cx,
                             <tr>
-                                <td><input class="input input-ghost w-full" readonly value={mission} /></td>
+                                <td>
+                                    <div class="font-bold">{mission.title.clone().unwrap_or_else(|| mission.name.clone())}</div>
+                                    <div class="text-sm opacity-50">{mission.name.clone()}</div>
+                                </td>
+                                <td>{mission.world.clone()}</td>
+                                <td>{mission.game_type.clone().unwrap_or_default()}</td>
+                                <td>{players(mission.min_players, mission.max_players)}</td>
+                                <td>{mission.author.clone().unwrap_or_default()}</td>
+                                <td title={mission.addons.join("\n")}>
+                                    {mission.addons.len()}
+                                    {move || {
+                                        let check = addon_report
+                                            .get()
+                                            .missions
+                                            .into_iter()
+                                            .find(|check| check.mission == name && !check.playable)?;
+
+                                        Some(view! { cx,
+                                            <span class="badge badge-error ml-2" title={missing_addons(&check)}>
+                                                {format!("{} missing", check.missing.len())}
+                                            </span>
+                                        })
+                                    }}
+                                </td>
+                                <td>{mission.version}</td>
+                                <td class="whitespace-nowrap">
+                                    <a class="btn btn-sm btn-ghost" href=download_url download title="Download">
+                                        <i class="fa fa-download"></i>
+                                    </a>
+                                    <label class="btn btn-sm btn-ghost" title="Replace with a new version">
+                                        <i class="fa fa-upload"></i>
+                                        <input type="file" accept=".pbo" class="hidden" on:change=move |ev| {
+                                            let input = event_target::<web_sys::HtmlInputElement>(&ev);
+                                            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                                                replace_mission.dispatch((id, file));
+                                            }
+                                            input.set_value("");
+                                        } />
+                                    </label>
+                                    <button class="btn btn-sm btn-ghost" on:click=move |_| rename_mission.dispatch(rename.clone()) title="Rename">
+                                        <i class="fa fa-pen"></i>
+                                    </button>
+                                    <button class="btn btn-sm btn-ghost" on:click=move |_| delete_mission.dispatch(delete.clone()) title="Delete">
+                                        <i class="fa fa-trash"></i>
+                                    </button>
+                                </td>
                             </tr>
-                        }}/>
+                        }}}/>
                     </tbody>
                 </table>
+                <MissionRotation />
             </div>
         </div>
     }
 }
+
+fn players(min: Option<i64>, max: Option<i64>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) if min != max => format!("{} - {}", min, max),
+        (_, Some(max)) => max.to_string(),
+        (Some(min), None) => format!("{}+", min),
+        (None, None) => String::new(),
+    }
+}
+
+fn update_mission(missions: RwSignal<Vec<Mission>>, mission: Mission) {
+    missions.update(|missions| {
+        if let Some(existing) = missions.iter_mut().find(|m| m.id == mission.id) {
+            *existing = mission;
+        }
+    });
+}
+
+/// Lists the missing addons along with the downloaded mods that would provide them.
+fn missing_addons(check: &MissionAddonCheck) -> String {
+    check
+        .missing
+        .iter()
+        .map(|missing| {
+            if missing.providers.is_empty() {
+                return format!("{} (not provided by any downloaded mod)", missing.addon);
+            }
+
+            let providers = missing
+                .providers
+                .iter()
+                .map(|provider| format!("{} ({})", provider.name, provider.published_file_id))
+                .collect::<Vec<_>>();
+
+            format!("{}: {}", missing.addon, providers.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -1,26 +1,38 @@
+pub mod announcements;
 pub mod authenticated_base;
+pub mod bans;
 pub mod blank;
+pub mod chat;
 pub mod config;
 pub mod dashboard;
 pub mod log;
 pub mod login;
 pub mod missions;
+pub mod players;
 pub mod presets;
 pub mod profile;
 pub mod register;
+pub mod statistics;
 pub mod users;
+pub mod whitelist;
 
+pub use announcements::*;
 pub use authenticated_base::*;
+pub use bans::*;
 pub use blank::*;
+pub use chat::*;
 pub use config::*;
 pub use dashboard::*;
 pub use log::*;
 pub use login::*;
 pub use missions::*;
+pub use players::*;
 pub use presets::*;
 pub use profile::*;
 pub use register::*;
+pub use statistics::*;
 pub use users::*;
+pub use whitelist::*;
 
 #[derive(Debug, Clone, Copy, Default)]
 pub enum Page {
@@ -35,6 +47,12 @@ pub enum Page {
     Config,
     Presets,
     Missions,
+    Players,
+    Bans,
+    Chat,
+    Announcements,
+    Statistics,
+    Whitelist,
 }
 
 impl Page {
@@ -50,6 +68,12 @@ impl Page {
             Self::Config => "config",
             Self::Presets => "presets",
             Self::Missions => "missions",
+            Self::Players => "players",
+            Self::Bans => "bans",
+            Self::Chat => "chat",
+            Self::Announcements => "announcements",
+            Self::Statistics => "statistics",
+            Self::Whitelist => "whitelist",
         }
     }
 
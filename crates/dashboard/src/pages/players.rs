@@ -0,0 +1,337 @@
+use api_schema::{request::*, response::*};
+use leptos::*;
+
+use crate::{app_state::AppState, components::ToastStyle};
+
+#[component]
+pub fn Players(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let status = app_state.status;
+    let players = create_rw_signal(cx, Vec::<RconPlayer>::new());
+    let audit_log = create_rw_signal(cx, Vec::<AuditEntry>::new());
+    let message = create_rw_signal(cx, String::default());
+
+    let running = Signal::derive(cx, move || {
+        let Some(instance) = app_state.instance.get() else {
+            return false;
+        };
+
+        status
+            .get()
+            .map(|status| status.arma(instance) == State::Running)
+            .unwrap_or(false)
+    });
+
+    let load = create_action(cx, move |instance: &i64| {
+        let instance = *instance;
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            audit_log.set(api.get_audit_log(instance).await.unwrap_or_default());
+
+            if !running.get_untracked() {
+                players.set(vec![]);
+                return;
+            }
+
+            match api.get_rcon_players(instance).await {
+                Ok(list) => players.set(list),
+                Err(err) => {
+                    players.set(vec![]);
+                    app_state.toast(cx, format!("Unable to load players: {err}"), Some(ToastStyle::Error));
+                }
+            }
+        }
+    });
+
+    create_effect(cx, move |_| {
+        // the players are gone once the server stops
+        running.with(|_| ());
+
+        if let Some(instance) = app_state.instance.get() {
+            load.dispatch(instance);
+        }
+    });
+
+    let refresh = move || {
+        if let Some(instance) = app_state.instance.get_untracked() {
+            load.dispatch(instance);
+        }
+    };
+
+    let kick = create_action(cx, move |player: &RconPlayer| {
+        let player = player.clone();
+        async move {
+            let Ok(Some(reason)) = window().prompt_with_message(&format!("Kick {}? Reason:", player.name)) else {
+                return;
+            };
+
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+            let Some(instance) = app_state.instance.get_untracked() else {
+                return;
+            };
+
+            let schema = KickPlayerSchema {
+                slot: player.slot,
+                guid: player.guid.clone(),
+                reason,
+            };
+
+            match api.kick_player(instance, &schema).await {
+                Ok(_) => app_state.toast(cx, format!("{} was kicked", player.name), Some(ToastStyle::Success)),
+                Err(err) => app_state.toast(
+                    cx,
+                    format!("Unable to kick {}: {err}", player.name),
+                    Some(ToastStyle::Error),
+                ),
+            }
+
+            load.dispatch(instance);
+        }
+    });
+
+    let ban = create_action(cx, move |player: &RconPlayer| {
+        let player = player.clone();
+        async move {
+            let Ok(Some(minutes)) = window().prompt_with_message_and_default(
+                &format!("Ban {} for how many minutes? 0 bans permanently.", player.name),
+                "0",
+            ) else {
+                return;
+            };
+
+            let Ok(minutes) = minutes.trim().parse::<i64>() else {
+                app_state.toast(
+                    cx,
+                    format!("{} is not a number of minutes", minutes),
+                    Some(ToastStyle::Error),
+                );
+                return;
+            };
+
+            let Ok(Some(reason)) = window().prompt_with_message("Reason:") else {
+                return;
+            };
+
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+            let Some(instance) = app_state.instance.get_untracked() else {
+                return;
+            };
+
+            let schema = BanPlayerSchema {
+                slot: player.slot,
+                guid: player.guid.clone(),
+                minutes,
+                reason,
+            };
+
+            match api.ban_player(instance, &schema).await {
+                Ok(_) => app_state.toast(cx, format!("{} was banned", player.name), Some(ToastStyle::Success)),
+                Err(err) => app_state.toast(
+                    cx,
+                    format!("Unable to ban {}: {err}", player.name),
+                    Some(ToastStyle::Error),
+                ),
+            }
+
+            load.dispatch(instance);
+        }
+    });
+
+    let send = create_action(cx, move |(player, text): &(Option<RconPlayer>, String)| {
+        let player = player.clone();
+        let text = text.clone();
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+            let Some(instance) = app_state.instance.get_untracked() else {
+                return;
+            };
+
+            let schema = SendMessageSchema {
+                slot: player.as_ref().map(|player| player.slot),
+                guid: player.as_ref().map(|player| player.guid.clone()),
+                message: text,
+            };
+
+            match api.send_message(instance, &schema).await {
+                Ok(_) => {
+                    if player.is_none() {
+                        message.set(String::default());
+                    }
+                }
+                Err(err) => app_state.toast(cx, format!("Unable to send message: {err}"), Some(ToastStyle::Error)),
+            }
+
+            load.dispatch(instance);
+        }
+    });
+
+    let message_player = move |player: RconPlayer| {
+        let Ok(Some(text)) = window().prompt_with_message(&format!("Message to {}:", player.name)) else {
+            return;
+        };
+
+        send.dispatch((Some(player), text));
+    };
+
+    let lock = create_action(cx, move |locked: &bool| {
+        let locked = *locked;
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+            let Some(instance) = app_state.instance.get_untracked() else {
+                return;
+            };
+
+            match api.lock_server(instance, locked).await {
+                Ok(_) if locked => app_state.toast(cx, "Server locked", Some(ToastStyle::Success)),
+                Ok(_) => app_state.toast(cx, "Server unlocked", Some(ToastStyle::Success)),
+                Err(err) => app_state.toast(cx, format!("Unable to lock the server: {err}"), Some(ToastStyle::Error)),
+            }
+
+            load.dispatch(instance);
+        }
+    });
+
+    view! { cx,
+        <div class="card w-full flex-1 p-6 bg-base-100 shadow-xl mt-2 mb-4">
+            <div class="flex justify-between items-center">
+                <div class="text-xl font-semibold inline-block">
+                    {move || format!("Players ({})", players.get().len())}
+                </div>
+                <div class="flex gap-2">
+                    <button class="btn btn-ghost" disabled=move || !running.get() on:click=move |_| lock.dispatch(true) title="Let no new players join">
+                        <i class="fa fa-lock"></i>
+                        "Lock"
+                    </button>
+                    <button class="btn btn-ghost" disabled=move || !running.get() on:click=move |_| lock.dispatch(false) title="Let players join again">
+                        <i class="fa fa-lock-open"></i>
+                        "Unlock"
+                    </button>
+                    <button class="btn btn-ghost" on:click=move |_| refresh() title="Refresh">
+                        <i class="fa fa-rotate"></i>
+                    </button>
+                </div>
+            </div>
+            <div class="divider mt-2"></div>
+            <div class="join w-full mb-4">
+                <input
+                    type="text"
+                    placeholder="Message to everyone…"
+                    class="input input-bordered join-item flex-1"
+                    disabled=move || !running.get()
+                    prop:value={move || message.get()}
+                    on:input=move |ev| message.set(event_target_value(&ev))
+                    on:keydown=move |ev| {
+                        if ev.key() == "Enter" && !message.get_untracked().trim().is_empty() {
+                            send.dispatch((None, message.get_untracked()));
+                        }
+                    } />
+                <button
+                    class="btn join-item"
+                    disabled=move || !running.get() || message.get().trim().is_empty()
+                    on:click=move |_| send.dispatch((None, message.get_untracked()))>
+                    <i class="fa fa-paper-plane"></i>
+                    "Send"
+                </button>
+            </div>
+            <table class="table table-zebra w-full">
+                <thead>
+                    <tr>
+                        <th>"#"</th>
+                        <th>"Name"</th>
+                        <th>"IP"</th>
+                        <th>"Ping"</th>
+                        <th>"GUID"</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || if !running.get() {
+                        view! { cx,
+                            <tr>
+                                <td class="text-center" colspan="6">"The server is not running"</td>
+                            </tr>
+                        }.into_view(cx)
+                    } else if players.get().is_empty() {
+                        view! { cx,
+                            <tr>
+                                <td class="text-center" colspan="6">"No players on the server"</td>
+                            </tr>
+                        }.into_view(cx)
+                    } else {
+                        view! { cx,
+                            <For each={move || players.get()} key={|player| (player.slot, player.guid.clone(), player.lobby)} view={move |cx, player| {
+                                let kick_player = player.clone();
+                                let ban_player = player.clone();
+                                let message_target = player.clone();
+
+                                view! { cx,
+                                    <tr>
+                                        <td>{player.slot}</td>
+                                        <td>
+                                            {player.name.clone()}
+                                            {player.lobby.then(|| view! { cx, <span class="badge badge-ghost ml-2">"Lobby"</span> })}
+                                        </td>
+                                        <td>{format!("{}:{}", player.ip, player.port)}</td>
+                                        <td>{player.ping}</td>
+                                        <td class="font-mono text-xs">
+                                            {player.guid.clone()}
+                                            {(!player.verified).then(|| view! { cx, <span class="badge badge-warning ml-2" title="BattlEye has not verified the GUID yet">"?"</span> })}
+                                        </td>
+                                        <td class="whitespace-nowrap">
+                                            <button class="btn btn-sm btn-ghost" on:click=move |_| message_player(message_target.clone()) title="Send a message">
+                                                <i class="fa fa-comment"></i>
+                                            </button>
+                                            <button class="btn btn-sm btn-ghost" on:click=move |_| kick.dispatch(kick_player.clone()) title="Kick">
+                                                <i class="fa fa-right-from-bracket"></i>
+                                            </button>
+                                            <button class="btn btn-sm btn-ghost hover:bg-error hover:text-error-content" on:click=move |_| ban.dispatch(ban_player.clone()) title="Ban">
+                                                <i class="fa fa-ban"></i>
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            }} />
+                        }.into_view(cx)
+                    }}
+                </tbody>
+            </table>
+        </div>
+        <div class="card w-full md:w-2/6 p-6 bg-base-100 shadow-xl ml-1 mt-2 mb-4">
+            <div class="text-xl font-semibold">"Audit Log"</div>
+            <div class="divider mt-2"></div>
+            <div class="max-w-full h-full overflow-y-auto">
+                <table class="table table-compact w-full">
+                    <tbody>
+                        <For each={move || audit_log.get()} key={|entry| entry.id} view={move |cx, entry| view! { cx,
+                            <tr>
+                                <td>
+                                    <div class="font-bold">{audit_action(&entry)}</div>
+                                    <div class="text-sm whitespace-normal">{entry.details.clone().unwrap_or_default()}</div>
+                                    <div class="text-xs opacity-50">{format!("{} by {}", format_time(entry.created_at), entry.user)}</div>
+                                </td>
+                            </tr>
+                        }} />
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
+
+fn audit_action(entry: &AuditEntry) -> String {
+    match &entry.player {
+        Some(player) => format!("{} {}", entry.action, player),
+        None => entry.action.clone(),
+    }
+}
+
+fn format_time(timestamp: i64) -> String {
+    use chrono::{Local, TimeZone};
+
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
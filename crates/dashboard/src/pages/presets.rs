@@ -4,7 +4,7 @@ use leptos::*;
 
 use crate::{
     app_state::{AppState, Loading},
-    components::{PresetDlc, PresetItem},
+    components::{PresetConflicts, PresetDlc, PresetItem, PresetKeys, ServerKeys, ToastStyle},
 };
 
 #[component]
@@ -78,6 +78,28 @@ pub fn Presets(cx: Scope) -> impl IntoView {
         }
     });
 
+    let toggle_whitelist = create_action(cx, move |()| async move {
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+        let Some(preset) = selected_preset.get_untracked() else {
+            return;
+        };
+
+        let enabled = !preset.whitelist;
+
+        match api.set_preset_whitelist(preset.id, enabled).await {
+            Ok(_) => presets.update(|presets| {
+                if let Some(preset) = presets.iter_mut().find(|item| item.id == preset.id) {
+                    preset.whitelist = enabled;
+                }
+            }),
+            Err(err) => app_state.toast(
+                cx,
+                format!("Unable to update the preset: {err}"),
+                Some(ToastStyle::Error),
+            ),
+        }
+    });
+
     let toggle_hide_blacklisted = create_action(cx, move |()| async move {
         let value = hide_blacklisted.get_untracked();
         LocalStorage::set("hide_blacklisted", !value).unwrap();
@@ -94,6 +116,68 @@ pub fn Presets(cx: Scope) -> impl IntoView {
         api.force_check().await.unwrap();
     });
 
+    let sort_load_order = create_action(cx, move |()| async move {
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+        let Some(preset) = selected_preset.get_untracked() else {
+            return;
+        };
+
+        let report = match api.get_load_order(preset.id).await {
+            Ok(report) => report,
+            Err(err) => {
+                app_state.toast(cx, format!("Unable to sort load order: {err}"), Some(ToastStyle::Error));
+                return;
+            }
+        };
+
+        for missing in &report.missing {
+            app_state.toast(
+                cx,
+                format!(
+                    "{} requires {}, which no enabled mod provides",
+                    missing.name, missing.addon
+                ),
+                Some(ToastStyle::Warning),
+            );
+        }
+
+        if !report.cycles.is_empty() {
+            app_state.toast(
+                cx,
+                format!("These mods depend on each other: {}", report.cycles.join(", ")),
+                Some(ToastStyle::Warning),
+            );
+        }
+
+        if !report.changed {
+            app_state.toast(
+                cx,
+                "Load order already respects all dependencies",
+                Some(ToastStyle::Info),
+            );
+            return;
+        }
+
+        let moved = report
+            .order
+            .iter()
+            .filter(|item| item.position != item.previous_position)
+            .count();
+
+        let confirmed = window()
+            .confirm_with_message(&format!("Move {} mods to load after their dependencies?", moved))
+            .unwrap_or_default();
+
+        if !confirmed {
+            return;
+        }
+
+        match api.apply_load_order(preset.id).await {
+            Ok(_) => app_state.toast(cx, "Load order sorted", Some(ToastStyle::Success)),
+            Err(err) => app_state.toast(cx, format!("Unable to sort load order: {err}"), Some(ToastStyle::Error)),
+        }
+    });
+
     let delete_preset = create_action(cx, move |id: &i64| {
         let id = *id;
         async move {
@@ -208,16 +292,64 @@ pub fn Presets(cx: Scope) -> impl IntoView {
                                     "Force Check"
                                 </a>
                             </li>
+                            <li>
+                                <a
+                                    class="p-2 rounded-box whitespace-nowrap hover:glass"
+                                    href="#"
+                                    onClick="document.activeElement.blur();"
+                                    on:click=move |_| sort_load_order.dispatch(())
+                                    title="Sort the load order by the dependencies of each mod">
+                                    "Auto-sort Load Order"
+                                </a>
+                            </li>
+                            <li>
+                                <a
+                                    class="p-2 rounded-box whitespace-nowrap hover:glass"
+                                    href={move || {
+                                        let api = app_state.api.get()?;
+                                        let preset = selected_preset.get()?;
+                                        Some(api.preset_export_url(preset.id))
+                                    }}
+                                    onClick="document.activeElement.blur();"
+                                    title="Export as a preset for the Arma 3 Launcher">
+                                    "Export"
+                                </a>
+                            </li>
+                            <li>
+                                <a
+                                    class="p-2 rounded-box whitespace-nowrap hover:glass"
+                                    href="#"
+                                    onClick="document.activeElement.blur();"
+                                    on:click=move |_| toggle_whitelist.dispatch(())
+                                    title="Kick players that aren't on the whitelist from every instance running this preset">
+                                    {move || match selected_preset.get() {
+                                        Some(preset) if preset.whitelist => "Stop Enforcing the Whitelist",
+                                        _ => "Enforce the Whitelist",
+                                    }}
+                                </a>
+                            </li>
                         </ul>
                     </div>
                 </div>
             </div>
             <div class="divider mt-2"></div>
+            <PresetConflicts />
             <div class="max-w-full h-full overflow-y-auto">
                 <table class="table table-fixed table-zebra w-full">
                     <tbody>
                         {move || if let Some(filtered_preset) = filtered_preset.get() {
-                            view! { cx, <For each={move || filtered_preset.items.clone()} key={|item| item.id} view={move |cx, item| view! { cx, <PresetItem item=item.clone() /> }.into_view(cx)} /> }.into_view(cx)
+                            let (optional, loaded): (Vec<_>, Vec<_>) = filtered_preset.items.into_iter().partition(|item| item.optional);
+                            let has_optional = !optional.is_empty();
+
+                            view! { cx,
+                                <For each={move || loaded.clone()} key={|item| item.id} view={move |cx, item| view! { cx, <PresetItem item=item.clone() /> }.into_view(cx)} />
+                                <Show when={move || has_optional} fallback={move |_| ()}>
+                                    <tr>
+                                        <td class="font-semibold">"Optional Mods"</td>
+                                    </tr>
+                                </Show>
+                                <For each={move || optional.clone()} key={|item| item.id} view={move |cx, item| view! { cx, <PresetItem item=item.clone() /> }.into_view(cx)} />
+                            }.into_view(cx)
                         } else {
                             view! { cx,
                                 <tr>
@@ -253,6 +385,8 @@ pub fn Presets(cx: Scope) -> impl IntoView {
                     </tbody>
                 </table>
             </div>
+            <PresetKeys />
+            <ServerKeys />
         </div>
     }
 }
@@ -0,0 +1,240 @@
+use api_schema::{request::*, response::*};
+use leptos::*;
+
+use crate::{app_state::AppState, components::ToastStyle};
+
+const RANGES: [(i64, &str); 3] = [(7, "Last 7 days"), (30, "Last 30 days"), (90, "Last 90 days")];
+const TOP_PLAYERS: i64 = 10;
+
+#[component]
+pub fn Statistics(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let days = create_rw_signal(cx, 30);
+    let daily = create_rw_signal(cx, Vec::<DailyPlayerStats>::new());
+    let playtime = create_rw_signal(cx, Vec::<PlayerPlaytime>::new());
+
+    let player = create_rw_signal(cx, None::<PlayerPlaytime>);
+    let sessions = create_rw_signal(cx, Vec::<PlayerSession>::new());
+
+    let load = create_action(cx, move |(instance, days): &(i64, i64)| {
+        let (instance, days) = (*instance, *days);
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.get_daily_stats(instance, days).await {
+                Ok(stats) => daily.set(stats),
+                Err(err) => app_state.toast(
+                    cx,
+                    format!("Unable to load the statistics: {err}"),
+                    Some(ToastStyle::Error),
+                ),
+            }
+
+            match api.get_playtime(instance, days, TOP_PLAYERS).await {
+                Ok(players) => playtime.set(players),
+                Err(err) => app_state.toast(
+                    cx,
+                    format!("Unable to load the playtime: {err}"),
+                    Some(ToastStyle::Error),
+                ),
+            }
+        }
+    });
+
+    create_effect(cx, move |_| {
+        if let Some(instance) = app_state.instance.get() {
+            player.set(None);
+            load.dispatch((instance, days.get()));
+        }
+    });
+
+    let load_history = create_action(cx, move |selected: &PlayerPlaytime| {
+        let selected = selected.clone();
+        async move {
+            let Some(instance) = app_state.instance.get_untracked() else {
+                return;
+            };
+
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            let query = SessionQuery {
+                player: Some(selected.guid.clone().unwrap_or_else(|| selected.name.clone())),
+                limit: None,
+            };
+
+            match api.get_sessions(instance, &query).await {
+                Ok(list) => {
+                    sessions.set(list);
+                    player.set(Some(selected));
+                }
+                Err(err) => app_state.toast(
+                    cx,
+                    format!("Unable to load the sessions: {err}"),
+                    Some(ToastStyle::Error),
+                ),
+            }
+        }
+    });
+
+    view! { cx,
+        <div class="flex justify-end mt-2">
+            <select class="select select-bordered" on:change=move |ev| {
+                if let Ok(value) = event_target_value(&ev).parse() {
+                    days.set(value);
+                }
+            }>
+                {RANGES.iter().map(|(value, label)| view! { cx,
+                    <option value={value.to_string()} selected={*value == days.get_untracked()}>{*label}</option>
+                }).collect::<Vec<_>>()}
+            </select>
+        </div>
+        <div class="grid lg:grid-cols-2 grid-cols-1 gap-4">
+            <div class="card w-full p-6 bg-base-100 shadow-xl mt-2">
+                <div class="text-xl font-semibold inline-block">"Unique Players"</div>
+                <div class="divider mt-2"></div>
+                {move || bar_chart(cx, daily.get().into_iter().map(|day| (day.day, day.unique_players)).collect())}
+            </div>
+            <div class="card w-full p-6 bg-base-100 shadow-xl mt-2">
+                <div class="text-xl font-semibold inline-block">"Peak Players"</div>
+                <div class="divider mt-2"></div>
+                {move || bar_chart(cx, daily.get().into_iter().map(|day| (day.day, day.peak_players)).collect())}
+            </div>
+        </div>
+        <div class="card w-full p-6 bg-base-100 shadow-xl mt-4 mb-4">
+            <div class="text-xl font-semibold inline-block">"Top Playtime"</div>
+            <div class="divider mt-2"></div>
+            <table class="table table-zebra w-full">
+                <thead>
+                    <tr>
+                        <th>"Name"</th>
+                        <th>"GUID"</th>
+                        <th>"Playtime"</th>
+                        <th>"Sessions"</th>
+                        <th>"Last seen"</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    <For each={move || playtime.get()} key={|player| (player.name.clone(), player.guid.clone())} view={move |cx, entry| {
+                        let selected = entry.clone();
+
+                        view! { cx,
+                            <tr class="hover cursor-pointer" on:click=move |_| load_history.dispatch(selected.clone())>
+                                <td>{entry.name.clone()}</td>
+                                <td class="font-mono text-xs">{entry.guid.clone().unwrap_or_default()}</td>
+                                <td>{format_duration(entry.playtime)}</td>
+                                <td>{entry.sessions}</td>
+                                <td>{format_time(entry.last_seen)}</td>
+                            </tr>
+                        }
+                    }} />
+                </tbody>
+            </table>
+            <Show when=move || playtime.get().is_empty() fallback=move |_| ()>
+                <div class="text-center opacity-50 mt-4">"Nobody played in this time"</div>
+            </Show>
+        </div>
+        <Show when=move || player.get().is_some() fallback=move |_| ()>
+            <div class="card w-full p-6 bg-base-100 shadow-xl mt-2 mb-4">
+                <div class="flex justify-between items-center">
+                    <div class="text-xl font-semibold inline-block">
+                        {move || player.get().map(|player| format!("Sessions of {}", player.name))}
+                    </div>
+                    <button class="btn btn-sm btn-ghost" on:click=move |_| player.set(None) title="Close">
+                        <i class="fa fa-xmark"></i>
+                    </button>
+                </div>
+                <div class="divider mt-2"></div>
+                <table class="table table-zebra w-full">
+                    <thead>
+                        <tr>
+                            <th>"Name"</th>
+                            <th>"Mission"</th>
+                            <th>"Joined"</th>
+                            <th>"Left"</th>
+                            <th>"Duration"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <For each={move || sessions.get()} key={|session| (session.id, session.left_at)} view={move |cx, session| {
+                            view! { cx,
+                                <tr>
+                                    <td>{session.name.clone()}</td>
+                                    <td>{session.mission.clone().unwrap_or_default()}</td>
+                                    <td>{format_time(session.joined_at)}</td>
+                                    <td>{format_time(session.left_at)}</td>
+                                    <td>{format_duration(session.duration)}</td>
+                                </tr>
+                            }
+                        }} />
+                    </tbody>
+                </table>
+            </div>
+        </Show>
+    }
+}
+
+/// One bar per day, the highest bar fills the chart.
+fn bar_chart(cx: Scope, values: Vec<(String, i64)>) -> impl IntoView {
+    if values.is_empty() {
+        return view! { cx, <div class="text-center opacity-50">"No players yet"</div> }.into_view(cx);
+    }
+
+    let first = values.first().map(|(day, _)| day.clone()).unwrap_or_default();
+    let last = values.last().map(|(day, _)| day.clone()).unwrap_or_default();
+    let max = values.iter().map(|(_, value)| *value).max().unwrap_or_default().max(1);
+    let width = 100.0 / values.len() as f64;
+
+    let bars = values
+        .into_iter()
+        .enumerate()
+        .map(|(i, (_, value))| {
+            let height = value as f64 / max as f64 * 100.0;
+
+            view! { cx,
+                <rect
+                    class="fill-primary"
+                    x={format!("{}", i as f64 * width + width * 0.1)}
+                    y={format!("{}", 100.0 - height)}
+                    width={format!("{}", width * 0.8)}
+                    height={format!("{}", height)} />
+            }
+        })
+        .collect::<Vec<_>>();
+
+    view! { cx,
+        <div class="flex gap-2">
+            <div class="flex flex-col justify-between text-xs opacity-50">
+                <span>{max}</span>
+                <span>"0"</span>
+            </div>
+            <svg class="w-full h-48" viewBox="0 0 100 100" preserveAspectRatio="none">
+                {bars}
+            </svg>
+        </div>
+        <div class="flex justify-between text-xs opacity-50 ml-6">
+            <span>{first}</span>
+            <span>{last}</span>
+        </div>
+    }
+    .into_view(cx)
+}
+
+fn format_duration(seconds: i64) -> String {
+    let minutes = seconds / 60;
+
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    }
+}
+
+fn format_time(timestamp: i64) -> String {
+    use chrono::{Local, TimeZone};
+
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,293 @@
+use api_schema::{request::*, response::*};
+use leptos::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{app_state::AppState, components::ToastStyle};
+
+#[component]
+pub fn Whitelist(cx: Scope) -> impl IntoView {
+    let app_state = use_context::<AppState>(cx).expect("AppState to exist");
+    let entries = create_rw_signal(cx, Vec::<WhitelistEntry>::new());
+    let settings = create_rw_signal(cx, None::<WhitelistSettings>);
+
+    let enabled = create_rw_signal(cx, false);
+    let message = create_rw_signal(cx, String::default());
+
+    let kind = create_rw_signal(cx, WhitelistKind::Uid);
+    let value = create_rw_signal(cx, String::default());
+    let name = create_rw_signal(cx, String::default());
+
+    let load = create_action(cx, move |_: &()| async move {
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+
+        match api.get_whitelist().await {
+            Ok(list) => entries.set(list),
+            Err(err) => app_state.toast(
+                cx,
+                format!("Unable to load the whitelist: {err}"),
+                Some(ToastStyle::Error),
+            ),
+        }
+    });
+
+    load.dispatch(());
+
+    let load_settings = create_action(cx, move |instance: &i64| {
+        let instance = *instance;
+        async move {
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.get_whitelist_settings(instance).await {
+                Ok(loaded) => {
+                    enabled.set(loaded.enabled);
+                    message.set(loaded.message.clone());
+                    settings.set(Some(loaded));
+                }
+                Err(err) => app_state.toast(
+                    cx,
+                    format!("Unable to load the whitelist settings: {err}"),
+                    Some(ToastStyle::Error),
+                ),
+            }
+        }
+    });
+
+    create_effect(cx, move |_| {
+        if let Some(instance) = app_state.instance.get() {
+            load_settings.dispatch(instance);
+        }
+    });
+
+    let save_settings = create_action(cx, move |_: &()| async move {
+        let Some(instance) = app_state.instance.get_untracked() else {
+            return;
+        };
+
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+
+        let schema = UpdateWhitelistSettingsSchema {
+            enabled: enabled.get_untracked(),
+            message: message.get_untracked(),
+        };
+
+        match api.update_whitelist_settings(instance, &schema).await {
+            Ok(_) => {
+                app_state.toast(cx, "Whitelist settings saved", Some(ToastStyle::Success));
+                load_settings.dispatch(instance);
+            }
+            Err(err) => app_state.toast(
+                cx,
+                format!("Unable to save the whitelist settings: {err}"),
+                Some(ToastStyle::Error),
+            ),
+        }
+    });
+
+    let add = create_action(cx, move |_: &()| async move {
+        let api = app_state.api.get_untracked().expect("there to be an Api");
+
+        let schema = CreateWhitelistEntrySchema {
+            kind: kind.get_untracked(),
+            value: value.get_untracked(),
+            name: name.get_untracked(),
+        };
+
+        match api.create_whitelist_entry(&schema).await {
+            Ok(_) => {
+                value.set(String::default());
+                name.set(String::default());
+                load.dispatch(());
+            }
+            Err(err) => app_state.toast(cx, format!("Unable to add the player: {err}"), Some(ToastStyle::Error)),
+        }
+    });
+
+    let delete = create_action(cx, move |entry: &WhitelistEntry| {
+        let entry = entry.clone();
+        async move {
+            let label = if entry.name.is_empty() {
+                &entry.value
+            } else {
+                &entry.name
+            };
+
+            if !window()
+                .confirm_with_message(&format!("Remove {} from the whitelist?", label))
+                .unwrap_or_default()
+            {
+                return;
+            }
+
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.delete_whitelist_entry(entry.id).await {
+                Ok(_) => load.dispatch(()),
+                Err(err) => app_state.toast(
+                    cx,
+                    format!("Unable to remove the player: {err}"),
+                    Some(ToastStyle::Error),
+                ),
+            }
+        }
+    });
+
+    let import = create_action(cx, move |file: &web_sys::File| {
+        let file = file.clone();
+        async move {
+            let Some(content) = JsFuture::from(file.text()).await.ok().and_then(|text| text.as_string()) else {
+                app_state.toast(cx, format!("Unable to read {}", file.name()), Some(ToastStyle::Error));
+                return;
+            };
+
+            let api = app_state.api.get_untracked().expect("there to be an Api");
+
+            match api.import_whitelist(&ImportWhitelistSchema { content }).await {
+                Ok(response) => {
+                    app_state.toast(cx, response.response, Some(ToastStyle::Success));
+                    load.dispatch(());
+                }
+                Err(err) => app_state.toast(
+                    cx,
+                    format!("Unable to import the whitelist: {err}"),
+                    Some(ToastStyle::Error),
+                ),
+            }
+        }
+    });
+
+    let import_file = move |ev: ev::Event| {
+        let input = event_target::<web_sys::HtmlInputElement>(&ev);
+
+        if let Some(file) = input.files().and_then(|files| files.get(0)) {
+            import.dispatch(file);
+        }
+
+        // picking the same file again should import it again
+        input.set_value("");
+    };
+
+    view! { cx,
+        <div class="card w-full p-6 bg-base-100 shadow-xl mt-2 mb-4">
+            <div class="text-xl font-semibold inline-block">"Whitelist Enforcement"</div>
+            <div class="divider mt-2"></div>
+            <div class="flex flex-wrap items-center gap-4">
+                <label class="label cursor-pointer gap-2">
+                    <input
+                        type="checkbox"
+                        class="toggle toggle-success"
+                        prop:checked={move || enabled.get()}
+                        on:change=move |ev| enabled.set(event_target_checked(&ev)) />
+                    <span class="label-text">"Kick players that aren't on the whitelist"</span>
+                </label>
+                <input
+                    type="text"
+                    placeholder="Kick message"
+                    class="input input-bordered flex-1"
+                    prop:value={move || message.get()}
+                    on:input=move |ev| message.set(event_target_value(&ev)) />
+                <button class="btn btn-primary" on:click=move |_| save_settings.dispatch(())>
+                    <i class="fa fa-floppy-disk"></i>
+                    "Save"
+                </button>
+            </div>
+            <Show
+                when=move || settings.get().map(|settings| settings.preset_enabled).unwrap_or(false)
+                fallback=move |_| ()>
+                <div class="text-sm opacity-70 mt-2">
+                    "The preset this instance runs with enforces the whitelist, so it is enforced either way."
+                </div>
+            </Show>
+        </div>
+        <div class="card w-full p-6 bg-base-100 shadow-xl mt-2 mb-4">
+            <div class="flex justify-between items-center">
+                <div class="text-xl font-semibold inline-block">"Whitelist"</div>
+                <label class="btn btn-ghost" title="A Steam UID or GUID and optionally a name on each line">
+                    <i class="fa fa-file-import"></i>
+                    "Import CSV"
+                    <input type="file" accept=".csv,.txt" class="hidden" on:change=import_file />
+                </label>
+            </div>
+            <div class="divider mt-2"></div>
+            <div class="flex flex-wrap gap-2 mb-4">
+                <select class="select select-bordered" on:change=move |ev| {
+                    kind.set(match event_target_value(&ev).as_str() {
+                        "guid" => WhitelistKind::Guid,
+                        _ => WhitelistKind::Uid,
+                    });
+                }>
+                    <option value="uid" selected>"Steam UID"</option>
+                    <option value="guid">"GUID"</option>
+                </select>
+                <input
+                    type="text"
+                    placeholder="Steam UID or GUID"
+                    class="input input-bordered flex-1 font-mono"
+                    prop:value={move || value.get()}
+                    on:input=move |ev| value.set(event_target_value(&ev)) />
+                <input
+                    type="text"
+                    placeholder="Name"
+                    class="input input-bordered flex-1"
+                    prop:value={move || name.get()}
+                    on:input=move |ev| name.set(event_target_value(&ev)) />
+                <button
+                    class="btn btn-primary"
+                    disabled=move || value.get().trim().is_empty()
+                    on:click=move |_| add.dispatch(())>
+                    <i class="fa fa-plus"></i>
+                    "Add"
+                </button>
+            </div>
+            <table class="table table-zebra w-full">
+                <thead>
+                    <tr>
+                        <th>"Name"</th>
+                        <th>"Type"</th>
+                        <th>"Steam UID / GUID"</th>
+                        <th>"Added by"</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    <For each={move || entries.get()} key={|entry| (entry.id, entry.name.clone())} view={move |cx, entry| {
+                        let removed = entry.clone();
+
+                        view! { cx,
+                            <tr>
+                                <td>{entry.name.clone()}</td>
+                                <td>{kind_name(entry.kind)}</td>
+                                <td class="font-mono text-xs">{entry.value.clone()}</td>
+                                <td>
+                                    <div>{entry.author.clone()}</div>
+                                    <div class="text-xs opacity-50">{format_time(entry.created_at)}</div>
+                                </td>
+                                <td>
+                                    <button class="btn btn-sm btn-ghost" on:click=move |_| delete.dispatch(removed.clone()) title="Remove">
+                                        <i class="fa fa-trash"></i>
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }} />
+                </tbody>
+            </table>
+        </div>
+    }
+}
+
+fn kind_name(kind: WhitelistKind) -> &'static str {
+    match kind {
+        WhitelistKind::Uid => "Steam UID",
+        WhitelistKind::Guid => "GUID",
+    }
+}
+
+fn format_time(timestamp: i64) -> String {
+    use chrono::{Local, TimeZone};
+
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
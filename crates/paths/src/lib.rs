@@ -32,6 +32,29 @@ pub fn get_config_path() -> PathBuf {
     path
 }
 
+/// Working directory of a server instance, passed as `-profiles` so each instance
+/// gets its own profile and RPT logs.
+pub fn get_instance_path(name: impl AsRef<str>) -> PathBuf {
+    let path = get_base_path().join("instances").join(name.as_ref());
+
+    if !path.exists() {
+        std::fs::create_dir_all(&path).unwrap();
+    }
+
+    path
+}
+
+/// Replaced versions of a mission, kept outside of `mpmissions` so the server doesn't pick them up.
+pub fn get_mission_archive_path(mission_id: i64) -> PathBuf {
+    let path = get_base_path().join("mission_archive").join(mission_id.to_string());
+
+    if !path.exists() {
+        std::fs::create_dir_all(&path).unwrap();
+    }
+
+    path
+}
+
 pub fn get_profile_path(name: impl Into<String>) -> Option<PathBuf> {
     // "UserDocuments/Arma 3"
     let path = directories::UserDirs::new().unwrap();
@@ -0,0 +1,76 @@
+//! Reading and writing of PBO archives, the format Arma uses for addons and missions.
+//!
+//! A PBO starts with a list of headers, the first one carries the properties like the prefix.
+//! The file data follows in the same order as the headers and Arma 3 appends a zero byte
+//! and a SHA1 checksum of everything before it.
+
+use std::fmt;
+
+mod lzss;
+mod reader;
+mod writer;
+
+pub use reader::*;
+pub use writer::*;
+
+/// The packing method of the header that is followed by the properties, "Vers" in little endian.
+pub const PACKING_VERSION: u32 = 0x5665_7273;
+/// The packing method of LZSS compressed entries, "Cprs" in little endian.
+pub const PACKING_COMPRESSED: u32 = 0x4370_7273;
+pub const PACKING_UNCOMPRESSED: u32 = 0;
+
+/// A file inside the archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// The path inside the archive, using backslashes like the game does.
+    pub name: String,
+    pub packing_method: u32,
+    /// The size after decompressing, only set for compressed entries.
+    pub original_size: u32,
+    pub timestamp: u32,
+    /// The size of the data stored in the archive.
+    pub data_size: u32,
+    pub(crate) offset: u64,
+}
+
+impl Entry {
+    pub fn is_compressed(&self) -> bool {
+        self.packing_method == PACKING_COMPRESSED
+    }
+
+    /// The size of the file once extracted.
+    pub fn size(&self) -> u32 {
+        match self.is_compressed() {
+            true => self.original_size,
+            false => self.data_size,
+        }
+    }
+}
+
+/// Paths are compared the way the game does it, case insensitive and with either separator.
+pub fn normalize_path(path: &str) -> String {
+    path.replace('/', "\\").trim_start_matches('\\').to_lowercase()
+}
+
+#[derive(Debug)]
+pub enum PboError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl From<std::io::Error> for PboError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl fmt::Display for PboError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PboError::Io(e) => write!(f, "{}", e),
+            PboError::Format(msg) => write!(f, "Invalid PBO: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PboError {}
@@ -0,0 +1,68 @@
+use crate::PboError;
+
+/// A flag byte followed by eight references of 18 bytes each is the most the data can expand.
+const MAX_RATIO: usize = 8 * 18;
+
+/// Decompresses the LZSS variant used by compressed PBO entries.
+///
+/// Every flag byte describes the next eight blocks, a set bit is a literal byte
+/// and a cleared bit a two byte back reference into the already decompressed data.
+/// References pointing before the start of the output produce spaces.
+pub(crate) fn decompress(input: &[u8], original_size: usize) -> Result<Vec<u8>, PboError> {
+    // the size comes from the header, so don't reserve more than the data can possibly expand to
+    if original_size > input.len().div_ceil(17) * MAX_RATIO {
+        return Err(PboError::Format("compressed size is out of range".to_string()));
+    }
+
+    let mut output: Vec<u8> = Vec::with_capacity(original_size);
+    let mut input = input.iter().copied();
+
+    while output.len() < original_size {
+        let Some(flags) = input.next() else {
+            return Err(PboError::Format("compressed data ended early".to_string()));
+        };
+
+        for bit in 0..8 {
+            if output.len() >= original_size {
+                break;
+            }
+
+            if flags & (1 << bit) != 0 {
+                let Some(byte) = input.next() else {
+                    return Err(PboError::Format("compressed data ended early".to_string()));
+                };
+                output.push(byte);
+                continue;
+            }
+
+            let (Some(low), Some(high)) = (input.next(), input.next()) else {
+                return Err(PboError::Format("compressed data ended early".to_string()));
+            };
+
+            let distance = low as usize | ((high as usize & 0xF0) << 4);
+            let length = (high as usize & 0x0F) + 3;
+
+            for _ in 0..length.min(original_size - output.len()) {
+                let byte = match output.len().checked_sub(distance) {
+                    Some(position) if distance > 0 => output[position],
+                    _ => b' ',
+                };
+                output.push(byte);
+            }
+        }
+    }
+
+    // the data is followed by the sum of all bytes
+    let checksum = input.by_ref().take(4).collect::<Vec<_>>();
+
+    if checksum.len() == 4 {
+        let expected = u32::from_le_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+        let actual = output.iter().fold(0u32, |sum, byte| sum.wrapping_add(*byte as u32));
+
+        if expected != actual {
+            return Err(PboError::Format("compressed data checksum mismatch".to_string()));
+        }
+    }
+
+    Ok(output)
+}
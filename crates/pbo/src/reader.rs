@@ -0,0 +1,229 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use sha1::{Digest, Sha1};
+
+use crate::{lzss, normalize_path, Entry, PboError, PACKING_COMPRESSED, PACKING_UNCOMPRESSED, PACKING_VERSION};
+
+/// Reads the headers of a PBO up front, file data is only read when extracting.
+pub struct PboReader<R> {
+    reader: R,
+    properties: Vec<(String, String)>,
+    entries: Vec<Entry>,
+    /// Where the file data ends and the checksum starts.
+    data_end: u64,
+    checksum: Option<[u8; 20]>,
+}
+
+impl PboReader<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PboError> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> PboReader<R> {
+    pub fn new(mut reader: R) -> Result<Self, PboError> {
+        let mut properties = Vec::new();
+        let mut entries = Vec::new();
+        let mut first = true;
+
+        loop {
+            let name = read_string(&mut reader)?;
+            let packing_method = read_u32(&mut reader)?;
+            let original_size = read_u32(&mut reader)?;
+            let _reserved = read_u32(&mut reader)?;
+            let timestamp = read_u32(&mut reader)?;
+            let data_size = read_u32(&mut reader)?;
+
+            if name.is_empty() {
+                // the properties can only follow the very first header
+                if first && packing_method == PACKING_VERSION {
+                    first = false;
+                    read_properties(&mut reader, &mut properties)?;
+                    continue;
+                }
+
+                break;
+            }
+
+            first = false;
+
+            if packing_method != PACKING_UNCOMPRESSED && packing_method != PACKING_COMPRESSED {
+                return Err(PboError::Format(format!(
+                    "unknown packing method {:#x} for {}",
+                    packing_method, name
+                )));
+            }
+
+            entries.push(Entry {
+                name,
+                packing_method,
+                original_size,
+                timestamp,
+                data_size,
+                offset: 0,
+            });
+        }
+
+        let mut offset = reader.stream_position()?;
+
+        for entry in entries.iter_mut() {
+            entry.offset = offset;
+            offset += entry.data_size as u64;
+        }
+
+        let length = reader.seek(SeekFrom::End(0))?;
+
+        if offset > length {
+            return Err(PboError::Format("file data is truncated".to_string()));
+        }
+
+        // a zero byte followed by the SHA1 of everything before it
+        let mut checksum = None;
+
+        if length - offset == 21 {
+            let mut buffer = [0u8; 21];
+            reader.seek(SeekFrom::Start(offset))?;
+            reader.read_exact(&mut buffer)?;
+
+            if buffer[0] == 0 {
+                let mut hash = [0u8; 20];
+                hash.copy_from_slice(&buffer[1..]);
+                checksum = Some(hash);
+            }
+        }
+
+        Ok(Self {
+            reader,
+            properties,
+            entries,
+            data_end: offset,
+            checksum,
+        })
+    }
+
+    pub fn properties(&self) -> &[(String, String)] {
+        &self.properties
+    }
+
+    pub fn property(&self, name: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The path the addon is mounted at, missions usually don't have one.
+    pub fn prefix(&self) -> Option<&str> {
+        self.property("prefix")
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.property("version")
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn entry(&self, name: &str) -> Option<&Entry> {
+        let name = normalize_path(name);
+        self.entries.iter().find(|entry| normalize_path(&entry.name) == name)
+    }
+
+    /// The checksum stored at the end of the file, older PBOs don't have one.
+    pub fn checksum(&self) -> Option<[u8; 20]> {
+        self.checksum
+    }
+
+    /// Extracts a single file, `None` if the archive does not contain it.
+    pub fn read(&mut self, name: &str) -> Result<Option<Vec<u8>>, PboError> {
+        let Some(entry) = self.entry(name).cloned() else {
+            return Ok(None);
+        };
+
+        self.read_entry(&entry).map(Some)
+    }
+
+    pub fn read_entry(&mut self, entry: &Entry) -> Result<Vec<u8>, PboError> {
+        let mut data = vec![0u8; entry.data_size as usize];
+        self.reader.seek(SeekFrom::Start(entry.offset))?;
+        self.reader.read_exact(&mut data)?;
+
+        if entry.is_compressed() {
+            return lzss::decompress(&data, entry.original_size as usize);
+        }
+
+        Ok(data)
+    }
+
+    /// Hashes the archive and compares it with the stored checksum.
+    pub fn verify(&mut self) -> Result<bool, PboError> {
+        let Some(checksum) = self.checksum else {
+            return Ok(false);
+        };
+
+        let mut hasher = Sha1::new();
+        let mut remaining = self.data_end;
+        let mut buffer = [0u8; 8192];
+
+        self.reader.seek(SeekFrom::Start(0))?;
+
+        while remaining > 0 {
+            let length = remaining.min(buffer.len() as u64) as usize;
+            self.reader.read_exact(&mut buffer[..length])?;
+            hasher.update(&buffer[..length]);
+            remaining -= length as u64;
+        }
+
+        Ok(hasher.finalize().as_slice() == checksum)
+    }
+}
+
+fn read_properties(reader: &mut impl Read, properties: &mut Vec<(String, String)>) -> Result<(), PboError> {
+    loop {
+        let key = read_string(reader)?;
+
+        if key.is_empty() {
+            return Ok(());
+        }
+
+        let value = read_string(reader)?;
+        properties.push((key, value));
+    }
+}
+
+fn read_string(reader: &mut impl Read) -> Result<String, PboError> {
+    let mut bytes = Vec::new();
+    let mut byte = [0u8; 1];
+
+    loop {
+        reader.read_exact(&mut byte).map_err(|e| match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => PboError::Format("headers ended early".to_string()),
+            _ => PboError::Io(e),
+        })?;
+
+        if byte[0] == 0 {
+            break;
+        }
+
+        bytes.push(byte[0]);
+    }
+
+    // names are not guaranteed to be UTF-8, old tools wrote whatever codepage they ran on
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, PboError> {
+    let mut bytes = [0u8; 4];
+
+    reader.read_exact(&mut bytes).map_err(|e| match e.kind() {
+        std::io::ErrorKind::UnexpectedEof => PboError::Format("headers ended early".to_string()),
+        _ => PboError::Io(e),
+    })?;
+
+    Ok(u32::from_le_bytes(bytes))
+}
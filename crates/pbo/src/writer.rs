@@ -0,0 +1,212 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    time::UNIX_EPOCH,
+};
+
+use sha1::{Digest, Sha1};
+
+use crate::{normalize_path, PboError, PACKING_UNCOMPRESSED, PACKING_VERSION};
+
+/// The file in a folder that holds the prefix when packing, it is not packed itself.
+pub const PREFIX_FILE: &str = "$PBOPREFIX$";
+
+struct WriterEntry {
+    name: String,
+    timestamp: u32,
+    data: Vec<u8>,
+}
+
+/// Builds an uncompressed PBO in memory and writes it with a checksum.
+#[derive(Default)]
+pub struct PboWriter {
+    properties: Vec<(String, String)>,
+    entries: Vec<WriterEntry>,
+}
+
+impl PboWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a property, replacing an existing one with the same name.
+    pub fn property(&mut self, name: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        let name = name.into();
+        let value = value.into();
+
+        match self
+            .properties
+            .iter_mut()
+            .find(|(key, _)| key.eq_ignore_ascii_case(&name))
+        {
+            Some((_, existing)) => *existing = value,
+            None => self.properties.push((name, value)),
+        }
+
+        self
+    }
+
+    /// Adds a file, replacing an existing one with the same path.
+    pub fn add_file(&mut self, name: impl AsRef<str>, timestamp: u32, data: impl Into<Vec<u8>>) -> &mut Self {
+        let name = name.as_ref().replace('/', "\\").trim_start_matches('\\').to_string();
+        let normalized = normalize_path(&name);
+
+        self.entries.retain(|entry| normalize_path(&entry.name) != normalized);
+        self.entries.push(WriterEntry {
+            name,
+            timestamp,
+            data: data.into(),
+        });
+
+        self
+    }
+
+    /// Writes the archive and returns the checksum appended to it.
+    pub fn write(&self, writer: impl Write) -> Result<[u8; 20], PboError> {
+        let mut writer = HashingWriter {
+            inner: writer,
+            hasher: Sha1::new(),
+        };
+
+        write_header(&mut writer, "", PACKING_VERSION, 0, 0, 0)?;
+
+        for (key, value) in &self.properties {
+            write_string(&mut writer, key)?;
+            write_string(&mut writer, value)?;
+        }
+
+        write_string(&mut writer, "")?;
+
+        for entry in &self.entries {
+            let size = u32::try_from(entry.data.len())
+                .map_err(|_| PboError::Format(format!("{} is too large", entry.name)))?;
+
+            write_header(&mut writer, &entry.name, PACKING_UNCOMPRESSED, 0, entry.timestamp, size)?;
+        }
+
+        write_header(&mut writer, "", 0, 0, 0, 0)?;
+
+        for entry in &self.entries {
+            writer.write_all(&entry.data)?;
+        }
+
+        let mut checksum = [0u8; 20];
+        checksum.copy_from_slice(writer.hasher.finalize_reset().as_slice());
+
+        writer.inner.write_all(&[0])?;
+        writer.inner.write_all(&checksum)?;
+        writer.inner.flush()?;
+
+        Ok(checksum)
+    }
+
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<[u8; 20], PboError> {
+        self.write(BufWriter::new(File::create(path)?))
+    }
+
+    /// Collects every file in a folder, a `$PBOPREFIX$` file sets the prefix.
+    /// Files are added in sorted order so packing the same folder twice gives the same archive.
+    pub fn from_directory(directory: impl AsRef<Path>) -> Result<Self, PboError> {
+        let directory = directory.as_ref();
+        let mut writer = Self::new();
+
+        let mut files = Vec::new();
+        collect_files(directory, &mut files)?;
+        files.sort();
+
+        for file in files {
+            let relative = file
+                .strip_prefix(directory)
+                .map_err(|_| PboError::Format(format!("{} is outside of the folder", file.display())))?;
+
+            let name = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("\\");
+
+            if name == PREFIX_FILE {
+                let prefix = std::fs::read_to_string(&file)?;
+                writer.property("prefix", prefix.trim());
+                continue;
+            }
+
+            let timestamp = std::fs::metadata(&file)?
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_secs() as u32)
+                .unwrap_or_default();
+
+            writer.add_file(name, timestamp, std::fs::read(&file)?);
+        }
+
+        Ok(writer)
+    }
+}
+
+/// Packs a folder into a PBO, see `PboWriter::from_directory`.
+pub fn pack_directory(directory: impl AsRef<Path>, output: impl AsRef<Path>) -> Result<[u8; 20], PboError> {
+    PboWriter::from_directory(directory)?.write_to_file(output)
+}
+
+fn collect_files(directory: &Path, files: &mut Vec<std::path::PathBuf>) -> Result<(), PboError> {
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Hashes everything written through it, the checksum covers the whole archive.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha1,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn write_header(
+    writer: &mut impl Write,
+    name: &str,
+    packing_method: u32,
+    original_size: u32,
+    timestamp: u32,
+    data_size: u32,
+) -> Result<(), PboError> {
+    write_string(writer, name)?;
+
+    for value in [packing_method, original_size, 0, timestamp, data_size] {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn write_string(writer: &mut impl Write, value: &str) -> Result<(), PboError> {
+    if value.contains('\0') {
+        return Err(PboError::Format(format!("{:?} contains a null byte", value)));
+    }
+
+    writer.write_all(value.as_bytes())?;
+    writer.write_all(&[0])?;
+
+    Ok(())
+}
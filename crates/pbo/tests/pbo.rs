@@ -0,0 +1,219 @@
+use std::io::Cursor;
+
+use pbo::{pack_directory, PboError, PboReader, PboWriter, PACKING_COMPRESSED, PACKING_VERSION};
+
+fn write(writer: &PboWriter) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    writer.write(&mut buffer).unwrap();
+    buffer
+}
+
+fn header(buffer: &mut Vec<u8>, name: &str, packing_method: u32, original_size: u32, data_size: u32) {
+    buffer.extend_from_slice(name.as_bytes());
+    buffer.push(0);
+
+    for value in [packing_method, original_size, 0, 0, data_size] {
+        buffer.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+#[test]
+fn round_trip() {
+    let mut writer = PboWriter::new();
+    writer
+        .property("prefix", "x\\test")
+        .property("version", "1.2.3")
+        .add_file("config.cpp", 1_700_000_000, b"class CfgPatches {};".to_vec())
+        .add_file("data/texture.paa", 1_700_000_001, vec![0u8, 1, 2, 3, 255]);
+
+    let buffer = write(&writer);
+    let mut reader = PboReader::new(Cursor::new(buffer)).unwrap();
+
+    assert_eq!(reader.prefix(), Some("x\\test"));
+    assert_eq!(reader.version(), Some("1.2.3"));
+    assert_eq!(reader.entries().len(), 2);
+
+    let entry = reader.entry("data/texture.paa").unwrap();
+    assert_eq!(entry.name, "data\\texture.paa");
+    assert_eq!(entry.timestamp, 1_700_000_001);
+    assert_eq!(entry.size(), 5);
+
+    assert_eq!(reader.read("CONFIG.CPP").unwrap().unwrap(), b"class CfgPatches {};");
+    assert_eq!(
+        reader.read("data\\texture.paa").unwrap().unwrap(),
+        vec![0u8, 1, 2, 3, 255]
+    );
+    assert_eq!(reader.read("missing.sqf").unwrap(), None);
+}
+
+#[test]
+fn checksum_is_verified() {
+    let mut writer = PboWriter::new();
+    writer.add_file("init.sqf", 0, b"hint \"hello\";".to_vec());
+
+    let mut buffer = Vec::new();
+    let checksum = writer.write(&mut buffer).unwrap();
+
+    let mut reader = PboReader::new(Cursor::new(buffer.clone())).unwrap();
+    assert_eq!(reader.checksum(), Some(checksum));
+    assert!(reader.verify().unwrap());
+
+    // flip a byte in the file data, the checksum no longer matches
+    let position = buffer.len() - 22;
+    buffer[position] ^= 0xFF;
+
+    let mut reader = PboReader::new(Cursor::new(buffer)).unwrap();
+    assert!(!reader.verify().unwrap());
+}
+
+#[test]
+fn writing_is_deterministic() {
+    let mut writer = PboWriter::new();
+    writer
+        .add_file("a.sqf", 1, b"a".to_vec())
+        .add_file("b.sqf", 2, b"b".to_vec());
+
+    assert_eq!(write(&writer), write(&writer));
+}
+
+#[test]
+fn adding_a_file_twice_replaces_it() {
+    let mut writer = PboWriter::new();
+    writer
+        .add_file("Scripts\\init.sqf", 0, b"old".to_vec())
+        .add_file("scripts/INIT.sqf", 0, b"new".to_vec());
+
+    let mut reader = PboReader::new(Cursor::new(write(&writer))).unwrap();
+
+    assert_eq!(reader.entries().len(), 1);
+    assert_eq!(reader.read("scripts\\init.sqf").unwrap().unwrap(), b"new");
+}
+
+#[test]
+fn pack_directory_round_trip() {
+    let source = tempfile::tempdir().unwrap();
+    let root = source.path();
+
+    std::fs::create_dir_all(root.join("functions").join("common")).unwrap();
+    std::fs::write(root.join("$PBOPREFIX$"), "z\\addon\\main\n").unwrap();
+    std::fs::write(root.join("config.cpp"), "class CfgPatches { class main {}; };").unwrap();
+    std::fs::write(
+        root.join("functions").join("common").join("fn_log.sqf"),
+        "diag_log _this;",
+    )
+    .unwrap();
+
+    let output = tempfile::tempdir().unwrap();
+    let pbo_path = output.path().join("main.pbo");
+
+    let checksum = pack_directory(root, &pbo_path).unwrap();
+
+    let mut reader = PboReader::open(&pbo_path).unwrap();
+
+    assert_eq!(reader.prefix(), Some("z\\addon\\main"));
+    assert_eq!(reader.checksum(), Some(checksum));
+    assert!(reader.verify().unwrap());
+
+    let names = reader
+        .entries()
+        .iter()
+        .map(|entry| entry.name.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["config.cpp", "functions\\common\\fn_log.sqf"]);
+
+    assert_eq!(
+        reader.read("functions\\common\\fn_log.sqf").unwrap().unwrap(),
+        b"diag_log _this;"
+    );
+}
+
+#[test]
+fn reads_pbo_without_properties_or_checksum() {
+    // older tools skip the properties header and the checksum entirely
+    let mut buffer = Vec::new();
+    header(&mut buffer, "mission.sqm", 0, 0, 4);
+    header(&mut buffer, "", 0, 0, 0);
+    buffer.extend_from_slice(b"test");
+
+    let mut reader = PboReader::new(Cursor::new(buffer)).unwrap();
+
+    assert!(reader.properties().is_empty());
+    assert_eq!(reader.checksum(), None);
+    assert!(!reader.verify().unwrap());
+    assert_eq!(reader.read("mission.sqm").unwrap().unwrap(), b"test");
+}
+
+#[test]
+fn reads_compressed_entries() {
+    // "abcabcabc": three literals followed by a reference 3 back with a length of 6
+    let mut compressed = vec![0b0000_0111, b'a', b'b', b'c', 0x03, 0x03];
+    let sum = b"abcabcabc".iter().map(|byte| *byte as u32).sum::<u32>();
+    compressed.extend_from_slice(&sum.to_le_bytes());
+
+    let mut buffer = Vec::new();
+    header(&mut buffer, "", PACKING_VERSION, 0, 0);
+    buffer.push(0);
+    header(
+        &mut buffer,
+        "description.ext",
+        PACKING_COMPRESSED,
+        9,
+        compressed.len() as u32,
+    );
+    header(&mut buffer, "", 0, 0, 0);
+    buffer.extend_from_slice(&compressed);
+
+    let mut reader = PboReader::new(Cursor::new(buffer)).unwrap();
+
+    let entry = reader.entry("description.ext").unwrap();
+    assert!(entry.is_compressed());
+    assert_eq!(entry.size(), 9);
+
+    assert_eq!(reader.read("description.ext").unwrap().unwrap(), b"abcabcabc");
+}
+
+#[test]
+fn rejects_compressed_entries_claiming_too_much() {
+    let compressed = vec![0b0000_0000, 0x00, 0x0F, 0x00, 0x0F];
+
+    let mut buffer = Vec::new();
+    header(&mut buffer, "", PACKING_VERSION, 0, 0);
+    buffer.push(0);
+    header(
+        &mut buffer,
+        "mission.sqm",
+        PACKING_COMPRESSED,
+        u32::MAX,
+        compressed.len() as u32,
+    );
+    header(&mut buffer, "", 0, 0, 0);
+    buffer.extend_from_slice(&compressed);
+
+    let mut reader = PboReader::new(Cursor::new(buffer)).unwrap();
+
+    // rejected up front instead of reserving 4 GiB and running out of data
+    assert!(matches!(
+        reader.read("mission.sqm"),
+        Err(PboError::Format(message)) if message.contains("out of range")
+    ));
+}
+
+#[test]
+fn rejects_truncated_archives() {
+    let mut writer = PboWriter::new();
+    writer.add_file("init.sqf", 0, vec![1u8; 64]);
+
+    let buffer = write(&writer);
+
+    let truncated = buffer[..buffer.len() - 40].to_vec();
+    assert!(matches!(
+        PboReader::new(Cursor::new(truncated)),
+        Err(PboError::Format(_))
+    ));
+
+    let headers_only = buffer[..10].to_vec();
+    assert!(matches!(
+        PboReader::new(Cursor::new(headers_only)),
+        Err(PboError::Format(_))
+    ));
+}
@@ -27,6 +27,36 @@ pub fn kill(process_name: &str) {
     }
 }
 
+/// Like `is_running`, but only matches processes that were started with the given argument.
+pub fn is_running_with_arg(process_name: &str, arg: &str) -> bool {
+    let mut system = sysinfo::System::new_all();
+    system.refresh_all();
+
+    for process in system.processes_by_name(process_name) {
+        if has_arg(process.cmd(), arg) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Like `kill`, but only kills processes that were started with the given argument.
+pub fn kill_with_arg(process_name: &str, arg: &str) {
+    let mut system = sysinfo::System::new_all();
+    system.refresh_all();
+
+    for process in system.processes_by_name(process_name) {
+        if has_arg(process.cmd(), arg) {
+            process.kill();
+        }
+    }
+}
+
+fn has_arg(cmd: &[String], arg: &str) -> bool {
+    cmd.iter().any(|a| a.trim_matches('"') == arg)
+}
+
 enum ControlMessage {
     Stop,
     Kill,
@@ -0,0 +1,280 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    net::{lookup_host, ToSocketAddrs, UdpSocket},
+    sync::{broadcast, oneshot},
+    task::JoinHandle,
+};
+
+use crate::{
+    packet::{self, ServerPacket},
+    parse_players, Player, RconError,
+};
+
+/// How long to wait for the server to answer the login and each command.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+/// The server drops clients after 45 seconds without a packet.
+pub const DEFAULT_KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+const DISCONNECT_AFTER: Duration = Duration::from_secs(45);
+
+/// Connects to the RCon of a server, the defaults work for any BattlEye server.
+pub struct RconBuilder {
+    timeout: Duration,
+    keep_alive: Duration,
+}
+
+impl Default for RconBuilder {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            keep_alive: DEFAULT_KEEP_ALIVE,
+        }
+    }
+}
+
+impl RconBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    pub async fn connect(self, addr: impl ToSocketAddrs, password: &str) -> Result<RconClient, RconError> {
+        let addr = lookup_host(addr).await?.next().ok_or_else(|| {
+            RconError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "no address to connect to",
+            ))
+        })?;
+
+        let local: SocketAddr = match addr {
+            SocketAddr::V4(_) => ([0, 0, 0, 0], 0).into(),
+            SocketAddr::V6(_) => ([0u16; 8], 0).into(),
+        };
+
+        let socket = UdpSocket::bind(local).await?;
+        socket.connect(addr).await?;
+
+        login(&socket, password, self.timeout).await?;
+
+        let (messages, _) = broadcast::channel(100);
+
+        let shared = Arc::new(Shared {
+            socket,
+            sequence: AtomicU8::new(0),
+            pending: Mutex::new(HashMap::new()),
+            last_received: Mutex::new(Instant::now()),
+            messages,
+        });
+
+        let reader = tokio::spawn(receive(shared.clone()));
+        let keep_alive = tokio::spawn(keep_alive(shared.clone(), self.keep_alive));
+
+        Ok(RconClient {
+            shared,
+            timeout: self.timeout,
+            reader,
+            keep_alive,
+        })
+    }
+}
+
+/// A logged in RCon connection, dropping it stops the keep-alive and the server forgets the client after a while.
+pub struct RconClient {
+    shared: Arc<Shared>,
+    timeout: Duration,
+    reader: JoinHandle<()>,
+    keep_alive: JoinHandle<()>,
+}
+
+struct Shared {
+    socket: UdpSocket,
+    sequence: AtomicU8,
+    pending: Mutex<HashMap<u8, Pending>>,
+    last_received: Mutex<Instant>,
+    messages: broadcast::Sender<String>,
+}
+
+/// A command waiting for its response, collecting the packets of multi-packet responses.
+struct Pending {
+    parts: Vec<Option<Vec<u8>>>,
+    sender: oneshot::Sender<String>,
+}
+
+impl RconClient {
+    /// Connects with the default timeout and keep-alive.
+    pub async fn connect(addr: impl ToSocketAddrs, password: &str) -> Result<Self, RconError> {
+        RconBuilder::new().connect(addr, password).await
+    }
+
+    /// Sends a command and waits for the whole response.
+    /// Commands are not sent again on a timeout, the server may have run it and only the response got lost.
+    pub async fn command(&self, command: &str) -> Result<String, RconError> {
+        let sequence = self.shared.sequence.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+
+        self.shared.pending.lock().unwrap().insert(
+            sequence,
+            Pending {
+                parts: Vec::new(),
+                sender,
+            },
+        );
+
+        if let Err(e) = self.shared.socket.send(&packet::command(sequence, command)).await {
+            self.shared.pending.lock().unwrap().remove(&sequence);
+            return Err(e.into());
+        }
+
+        match tokio::time::timeout(self.timeout, receiver).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err(RconError::Disconnected),
+            Err(_) => {
+                self.shared.pending.lock().unwrap().remove(&sequence);
+                Err(RconError::Timeout)
+            }
+        }
+    }
+
+    /// The players on the server, including the ones still in the lobby.
+    pub async fn players(&self) -> Result<Vec<Player>, RconError> {
+        Ok(parse_players(&self.command("players").await?))
+    }
+
+    /// Messages the server sends on its own, like chat and players connecting.
+    /// Only messages received after subscribing are delivered.
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.shared.messages.subscribe()
+    }
+
+    /// Whether the server still answers, it stops once the server shut down or dropped the client.
+    pub fn is_connected(&self) -> bool {
+        !self.reader.is_finished() && self.shared.last_received.lock().unwrap().elapsed() < DISCONNECT_AFTER
+    }
+}
+
+impl Drop for RconClient {
+    fn drop(&mut self) {
+        self.reader.abort();
+        self.keep_alive.abort();
+    }
+}
+
+async fn login(socket: &UdpSocket, password: &str, timeout: Duration) -> Result<(), RconError> {
+    socket.send(&packet::login(password)).await?;
+
+    let mut buffer = vec![0u8; 65536];
+
+    let response = tokio::time::timeout(timeout, async {
+        loop {
+            let length = socket.recv(&mut buffer).await?;
+
+            if let Some(ServerPacket::Login(success)) = packet::decode(&buffer[..length]) {
+                return Ok::<_, std::io::Error>(success);
+            }
+        }
+    })
+    .await;
+
+    match response {
+        Ok(Ok(true)) => Ok(()),
+        Ok(Ok(false)) => Err(RconError::LoginFailed),
+        Ok(Err(e)) => Err(e.into()),
+        Err(_) => Err(RconError::Timeout),
+    }
+}
+
+async fn receive(shared: Arc<Shared>) {
+    let mut buffer = vec![0u8; 65536];
+    // the server sends a message again when the acknowledgement got lost, it should only be delivered once
+    let mut last_message = None;
+
+    loop {
+        let length = match shared.socket.recv(&mut buffer).await {
+            Ok(length) => length,
+            Err(e) => {
+                tracing::warn!("RCon connection closed: {}", e);
+                break;
+            }
+        };
+
+        let Some(received) = packet::decode(&buffer[..length]) else {
+            tracing::debug!("Dropped invalid RCon packet");
+            continue;
+        };
+
+        *shared.last_received.lock().unwrap() = Instant::now();
+
+        match received {
+            ServerPacket::Command { sequence, part, data } => {
+                let mut pending = shared.pending.lock().unwrap();
+
+                let response = match part {
+                    None => Some(data),
+                    Some((total, index)) => pending.get_mut(&sequence).and_then(|waiting| {
+                        waiting.parts.resize(total as usize, None);
+
+                        if let Some(slot) = waiting.parts.get_mut(index as usize) {
+                            *slot = Some(data);
+                        }
+
+                        waiting
+                            .parts
+                            .iter()
+                            .all(Option::is_some)
+                            .then(|| waiting.parts.iter_mut().flat_map(|part| part.take().unwrap()).collect())
+                    }),
+                };
+
+                // keep-alives and commands that timed out have no one waiting
+                if let Some(response) = response {
+                    if let Some(waiting) = pending.remove(&sequence) {
+                        let _ = waiting.sender.send(String::from_utf8_lossy(&response).to_string());
+                    }
+                }
+            }
+            ServerPacket::Message { sequence, message } => {
+                if let Err(e) = shared.socket.send(&packet::acknowledge(sequence)).await {
+                    tracing::warn!("Failed to acknowledge RCon message: {}", e);
+                }
+
+                if last_message != Some(sequence) {
+                    last_message = Some(sequence);
+                    let _ = shared.messages.send(message);
+                }
+            }
+            ServerPacket::Login(_) => {}
+        }
+    }
+}
+
+async fn keep_alive(shared: Arc<Shared>, period: Duration) {
+    let mut interval = tokio::time::interval(period);
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        let sequence = shared.sequence.fetch_add(1, Ordering::Relaxed);
+        if let Err(e) = shared.socket.send(&packet::command(sequence, "")).await {
+            tracing::warn!("Failed to send RCon keep-alive: {}", e);
+        }
+    }
+}
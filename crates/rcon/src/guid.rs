@@ -0,0 +1,10 @@
+use md5::{Digest, Md5};
+
+/// The BattlEye GUID of a Steam UID, the MD5 hash of `BE` followed by the UID as little-endian bytes.
+pub fn battleye_guid(uid: u64) -> String {
+    let mut hasher = Md5::new();
+    hasher.update(b"BE");
+    hasher.update(uid.to_le_bytes());
+
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
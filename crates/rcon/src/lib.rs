@@ -0,0 +1,50 @@
+//! A client for BattlEye RCon, the UDP protocol Arma servers use for remote administration.
+//!
+//! After logging in with the password from `beserver_x64.cfg` the client sends commands, each with a
+//! sequence number that the response repeats. Responses too big for one packet are split up and
+//! have to be put back together. The server drops clients it hasn't heard from for 45 seconds, so an
+//! empty command is sent as a keep-alive. Messages from the server, like chat and player connects,
+//! carry their own sequence numbers and are sent again until the client acknowledges them.
+
+use std::fmt;
+
+mod client;
+mod guid;
+mod messages;
+mod packet;
+mod players;
+
+pub use client::*;
+pub use guid::*;
+pub use messages::*;
+pub use players::*;
+
+#[derive(Debug)]
+pub enum RconError {
+    Io(std::io::Error),
+    /// The server rejected the password.
+    LoginFailed,
+    /// The server didn't answer in time, it may not be running or RCon may not be enabled.
+    Timeout,
+    /// The connection was closed while waiting for a response.
+    Disconnected,
+}
+
+impl From<std::io::Error> for RconError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl fmt::Display for RconError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RconError::Io(e) => write!(f, "{}", e),
+            RconError::LoginFailed => write!(f, "RCon login failed, the password was rejected"),
+            RconError::Timeout => write!(f, "RCon server did not respond"),
+            RconError::Disconnected => write!(f, "RCon connection closed"),
+        }
+    }
+}
+
+impl std::error::Error for RconError {}
@@ -0,0 +1,112 @@
+/// What a message pushed by the server is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    Chat,
+    Connect,
+    Disconnect,
+    Kick,
+    /// Kicks by the BattlEye filters, like `Script Restriction #12`.
+    Filter,
+    /// RCon admins logging in and their messages.
+    Admin,
+    Other,
+}
+
+/// A message pushed by the server, with the player it is about if there is one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub kind: MessageKind,
+    /// The chat channel, like `Global` or `Side`.
+    pub channel: Option<String>,
+    pub slot: Option<u32>,
+    pub player: Option<String>,
+    pub guid: Option<String>,
+    pub text: String,
+}
+
+const CHANNELS: [&str; 7] = ["Global", "Side", "Command", "Group", "Vehicle", "Direct", "Unknown"];
+
+/// Sorts a server message by what it is about, anything unknown is kept as [`MessageKind::Other`].
+pub fn parse_message(message: &str) -> Message {
+    let mut parsed = Message {
+        kind: MessageKind::Other,
+        channel: None,
+        slot: None,
+        player: None,
+        guid: None,
+        text: message.to_string(),
+    };
+
+    if let Some((channel, rest)) = chat_channel(message) {
+        parsed.kind = MessageKind::Chat;
+        parsed.channel = Some(channel.to_string());
+
+        if let Some((player, _)) = rest.split_once(": ") {
+            parsed.player = Some(player.to_string());
+        }
+    } else if let Some(rest) = message.strip_prefix("RCon admin #") {
+        parsed.kind = MessageKind::Admin;
+
+        if let Some((_, text)) = rest.split_once(": ") {
+            parsed.channel = chat_channel(text).map(|(channel, _)| channel.to_string());
+        }
+    } else if let Some(rest) = message.strip_prefix("Verified GUID (") {
+        // Verified GUID (<guid>) of player #<slot> <name>
+        if let Some((guid, player)) = rest.split_once(") of player #") {
+            parsed.kind = MessageKind::Connect;
+            parsed.guid = Some(guid.to_string());
+            player_slot(&mut parsed, player);
+        }
+    } else if let Some(rest) = message.strip_prefix("Player #") {
+        player_message(&mut parsed, rest);
+    }
+
+    parsed
+}
+
+fn chat_channel(message: &str) -> Option<(&str, &str)> {
+    let rest = message.strip_prefix('(')?;
+    let (channel, rest) = rest.split_once(") ")?;
+
+    CHANNELS.contains(&channel).then_some((channel, rest))
+}
+
+/// Everything after `Player #`.
+fn player_message(parsed: &mut Message, rest: &str) {
+    if let Some((player, reason)) = rest.split_once(" has been kicked by BattlEye: ") {
+        parsed.kind = if reason.contains("Restriction #") {
+            MessageKind::Filter
+        } else {
+            MessageKind::Kick
+        };
+
+        // <slot> <name> (<guid>)
+        match player.rsplit_once(" (") {
+            Some((player, guid)) => {
+                let guid = guid.trim_end_matches(')');
+                parsed.guid = (guid.len() == 32).then(|| guid.to_string());
+                player_slot(parsed, player);
+            }
+            None => player_slot(parsed, player),
+        }
+    } else if let Some((player, guid)) = rest.split_once(" - BE GUID: ") {
+        parsed.kind = MessageKind::Connect;
+        parsed.guid = Some(guid.trim().to_string());
+        player_slot(parsed, player);
+    } else if let Some(player) = rest.strip_suffix(" connected") {
+        // <slot> <name> (<ip>:<port>)
+        parsed.kind = MessageKind::Connect;
+        player_slot(parsed, player.rsplit_once(" (").map_or(player, |(player, _)| player));
+    } else if let Some(player) = rest.strip_suffix(" disconnected") {
+        parsed.kind = MessageKind::Disconnect;
+        player_slot(parsed, player);
+    }
+}
+
+/// `<slot> <name>`
+fn player_slot(parsed: &mut Message, player: &str) {
+    let (slot, name) = player.split_once(' ').unwrap_or((player, ""));
+
+    parsed.slot = slot.parse().ok();
+    parsed.player = (!name.is_empty()).then(|| name.to_string());
+}
@@ -0,0 +1,92 @@
+pub(crate) const LOGIN: u8 = 0x00;
+pub(crate) const COMMAND: u8 = 0x01;
+pub(crate) const MESSAGE: u8 = 0x02;
+
+/// A packet the server sent to the client.
+#[derive(Debug, PartialEq)]
+pub(crate) enum ServerPacket {
+    Login(bool),
+    Command {
+        sequence: u8,
+        /// The number of packets and the index of this one, for responses too big for one packet.
+        part: Option<(u8, u8)>,
+        data: Vec<u8>,
+    },
+    Message {
+        sequence: u8,
+        message: String,
+    },
+}
+
+/// Every packet starts with `BE`, the CRC32 of the rest and `0xFF`, followed by the type and its payload.
+pub(crate) fn encode(kind: u8, payload: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(payload.len() + 2);
+    body.push(0xFF);
+    body.push(kind);
+    body.extend_from_slice(payload);
+
+    let mut packet = Vec::with_capacity(body.len() + 6);
+    packet.extend_from_slice(b"BE");
+    packet.extend_from_slice(&crc32fast::hash(&body).to_le_bytes());
+    packet.extend_from_slice(&body);
+    packet
+}
+
+pub(crate) fn login(password: &str) -> Vec<u8> {
+    encode(LOGIN, password.as_bytes())
+}
+
+/// An empty command is how the client tells the server it is still there.
+pub(crate) fn command(sequence: u8, command: &str) -> Vec<u8> {
+    let mut payload = vec![sequence];
+    payload.extend_from_slice(command.as_bytes());
+    encode(COMMAND, &payload)
+}
+
+pub(crate) fn acknowledge(sequence: u8) -> Vec<u8> {
+    encode(MESSAGE, &[sequence])
+}
+
+/// Packets with a wrong header or checksum are dropped, UDP gives no guarantees.
+pub(crate) fn decode(data: &[u8]) -> Option<ServerPacket> {
+    if data.len() < 8 || &data[..2] != b"BE" || data[6] != 0xFF {
+        return None;
+    }
+
+    let checksum = u32::from_le_bytes([data[2], data[3], data[4], data[5]]);
+    if crc32fast::hash(&data[6..]) != checksum {
+        return None;
+    }
+
+    let payload = &data[8..];
+
+    match data[7] {
+        LOGIN => Some(ServerPacket::Login(payload.first() == Some(&0x01))),
+        COMMAND => {
+            let (&sequence, rest) = payload.split_first()?;
+
+            // multi-packet responses start with 0x00, the number of packets and the index of this one
+            match rest {
+                [0x00, total, index, data @ ..] => Some(ServerPacket::Command {
+                    sequence,
+                    part: Some((*total, *index)),
+                    data: data.to_vec(),
+                }),
+                _ => Some(ServerPacket::Command {
+                    sequence,
+                    part: None,
+                    data: rest.to_vec(),
+                }),
+            }
+        }
+        MESSAGE => {
+            let (&sequence, message) = payload.split_first()?;
+
+            Some(ServerPacket::Message {
+                sequence,
+                message: String::from_utf8_lossy(message).to_string(),
+            })
+        }
+        _ => None,
+    }
+}
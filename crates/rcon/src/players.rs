@@ -0,0 +1,61 @@
+/// A player from the response to the `players` command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Player {
+    /// The number commands like `kick` and `say` refer to the player by.
+    pub slot: u32,
+    pub ip: String,
+    pub port: u16,
+    pub ping: i64,
+    /// The BattlEye GUID, empty while BattlEye hasn't computed it yet.
+    pub guid: String,
+    /// Whether BattlEye verified the GUID with the master server.
+    pub verified: bool,
+    pub name: String,
+    /// Players in the lobby haven't picked a slot yet.
+    pub lobby: bool,
+}
+
+/// Splits off the next whitespace separated field, the rest keeps its spaces.
+fn next_field(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_start();
+    let end = line.find(char::is_whitespace)?;
+
+    Some((&line[..end], &line[end..]))
+}
+
+fn parse_player(line: &str) -> Option<Player> {
+    // 0   192.168.1.10:2304     31   0123456789abcdef0123456789abcdef(OK) Alice (Lobby)
+    let (slot, rest) = next_field(line)?;
+    let (address, rest) = next_field(rest)?;
+    let (ping, rest) = next_field(rest)?;
+    let (guid, name) = next_field(rest)?;
+
+    let (ip, port) = address.rsplit_once(':')?;
+
+    let (guid, verified) = match guid.split_once('(') {
+        Some((guid, status)) => (guid, status == "OK)"),
+        None => (guid.trim_end_matches('-'), false),
+    };
+
+    let name = name.trim();
+    let (name, lobby) = match name.strip_suffix(" (Lobby)") {
+        Some(name) => (name, true),
+        None => (name, false),
+    };
+
+    Some(Player {
+        slot: slot.parse().ok()?,
+        ip: ip.to_string(),
+        port: port.parse().ok()?,
+        ping: ping.parse().ok()?,
+        guid: guid.to_string(),
+        verified,
+        name: name.to_string(),
+        lobby,
+    })
+}
+
+/// Reads the table the `players` command answers with, the header and the total are skipped.
+pub fn parse_players(response: &str) -> Vec<Player> {
+    response.lines().filter_map(parse_player).collect()
+}
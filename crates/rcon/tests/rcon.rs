@@ -0,0 +1,398 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rcon::{Message, MessageKind, Player, RconBuilder, RconClient, RconError};
+use tokio::net::UdpSocket;
+
+const PASSWORD: &str = "secret";
+
+/// What the stand-in server received, to check what the client sent.
+#[derive(Default)]
+struct Received {
+    logins: usize,
+    commands: Vec<(u8, String)>,
+    acknowledged: Vec<u8>,
+}
+
+/// A BattlEye server that only knows a couple of commands:
+/// `players` answers in three packets sent out of order, `say` answers and then sends a message twice,
+/// `ignore` never answers and anything else is echoed back.
+async fn stand_in() -> (SocketAddr, Arc<Mutex<Received>>) {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+    let received = Arc::new(Mutex::new(Received::default()));
+
+    let state = received.clone();
+    tokio::spawn(async move {
+        let mut buffer = [0u8; 4096];
+
+        loop {
+            let (length, client) = socket.recv_from(&mut buffer).await.unwrap();
+            let data = &buffer[..length];
+
+            assert_eq!(&data[..2], b"BE");
+            assert_eq!(data[6], 0xFF);
+            assert_eq!(
+                u32::from_le_bytes(data[2..6].try_into().unwrap()),
+                crc32fast::hash(&data[6..])
+            );
+
+            let payload = &data[8..];
+
+            match data[7] {
+                0x00 => {
+                    state.lock().unwrap().logins += 1;
+                    let success = payload == PASSWORD.as_bytes();
+                    socket.send_to(&frame(0x00, &[success as u8]), client).await.unwrap();
+                }
+                0x01 => {
+                    let sequence = payload[0];
+                    let command = String::from_utf8(payload[1..].to_vec()).unwrap();
+                    state.lock().unwrap().commands.push((sequence, command.clone()));
+
+                    match command.as_str() {
+                        "ignore" => {}
+                        "players" => {
+                            for (index, part) in [(2u8, "3 players"), (0, "Players on server:\n"), (1, "0 Alice\n")] {
+                                let mut response = vec![sequence, 0x00, 3, index];
+                                response.extend_from_slice(part.as_bytes());
+                                socket.send_to(&frame(0x01, &response), client).await.unwrap();
+                            }
+                        }
+                        "say -1 hello" => {
+                            socket.send_to(&frame(0x01, &[sequence]), client).await.unwrap();
+
+                            let mut message = vec![7];
+                            message.extend_from_slice(b"RCon admin: hello");
+                            socket.send_to(&frame(0x02, &message), client).await.unwrap();
+                            socket.send_to(&frame(0x02, &message), client).await.unwrap();
+                        }
+                        _ => {
+                            let mut response = vec![sequence];
+                            response.extend_from_slice(command.as_bytes());
+                            socket.send_to(&frame(0x01, &response), client).await.unwrap();
+                        }
+                    }
+                }
+                0x02 => state.lock().unwrap().acknowledged.push(payload[0]),
+                kind => panic!("unknown packet type {}", kind),
+            }
+        }
+    });
+
+    (addr, received)
+}
+
+fn frame(kind: u8, payload: &[u8]) -> Vec<u8> {
+    let mut body = vec![0xFF, kind];
+    body.extend_from_slice(payload);
+
+    let mut packet = b"BE".to_vec();
+    packet.extend_from_slice(&crc32fast::hash(&body).to_le_bytes());
+    packet.extend_from_slice(&body);
+    packet
+}
+
+#[tokio::test]
+async fn login() {
+    let (addr, received) = stand_in().await;
+
+    let client = RconClient::connect(addr, PASSWORD).await.unwrap();
+
+    assert!(client.is_connected());
+    assert_eq!(received.lock().unwrap().logins, 1);
+}
+
+#[tokio::test]
+async fn login_with_wrong_password() {
+    let (addr, _) = stand_in().await;
+
+    let result = RconClient::connect(addr, "wrong").await;
+
+    assert!(matches!(result, Err(RconError::LoginFailed)));
+}
+
+#[tokio::test]
+async fn login_without_server() {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    let result = RconBuilder::new()
+        .timeout(Duration::from_millis(200))
+        .connect(addr, PASSWORD)
+        .await;
+
+    assert!(matches!(result, Err(RconError::Timeout)));
+}
+
+#[tokio::test]
+async fn command() {
+    let (addr, received) = stand_in().await;
+    let client = RconClient::connect(addr, PASSWORD).await.unwrap();
+
+    assert_eq!(client.command("version").await.unwrap(), "version");
+    assert_eq!(client.command("missions").await.unwrap(), "missions");
+
+    let commands = received.lock().unwrap().commands.clone();
+    assert_eq!(commands, vec![(0, "version".to_string()), (1, "missions".to_string())]);
+}
+
+#[tokio::test]
+async fn multi_packet_response() {
+    let (addr, _) = stand_in().await;
+    let client = RconClient::connect(addr, PASSWORD).await.unwrap();
+
+    assert_eq!(
+        client.command("players").await.unwrap(),
+        "Players on server:\n0 Alice\n3 players"
+    );
+}
+
+#[tokio::test]
+async fn sequence_wraps_around() {
+    let (addr, received) = stand_in().await;
+    let client = RconClient::connect(addr, PASSWORD).await.unwrap();
+
+    for i in 0..300 {
+        let command = format!("echo {}", i);
+        assert_eq!(client.command(&command).await.unwrap(), command);
+    }
+
+    let commands = received.lock().unwrap().commands.clone();
+    assert_eq!(commands[255].0, 255);
+    assert_eq!(commands[256].0, 0);
+    assert_eq!(commands[299], (43, "echo 299".to_string()));
+}
+
+#[tokio::test]
+async fn concurrent_commands() {
+    let (addr, _) = stand_in().await;
+    let client = Arc::new(RconClient::connect(addr, PASSWORD).await.unwrap());
+
+    let handles = (0..20)
+        .map(|i| {
+            let client = client.clone();
+            tokio::spawn(async move { (i, client.command(&format!("echo {}", i)).await.unwrap()) })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        let (i, response) = handle.await.unwrap();
+        assert_eq!(response, format!("echo {}", i));
+    }
+}
+
+#[tokio::test]
+async fn command_timeout() {
+    let (addr, _) = stand_in().await;
+    let client = RconBuilder::new()
+        .timeout(Duration::from_millis(200))
+        .connect(addr, PASSWORD)
+        .await
+        .unwrap();
+
+    assert!(matches!(client.command("ignore").await, Err(RconError::Timeout)));
+
+    // the connection is still usable afterwards
+    assert_eq!(client.command("version").await.unwrap(), "version");
+}
+
+#[tokio::test]
+async fn keep_alive() {
+    let (addr, received) = stand_in().await;
+    let client = RconBuilder::new()
+        .keep_alive(Duration::from_millis(100))
+        .connect(addr, PASSWORD)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(350)).await;
+
+    let keep_alives = received
+        .lock()
+        .unwrap()
+        .commands
+        .iter()
+        .filter(|(_, command)| command.is_empty())
+        .count();
+    assert!(keep_alives >= 2, "only {} keep-alives sent", keep_alives);
+
+    // the empty responses to the keep-alives don't get mixed up with commands
+    assert_eq!(client.command("version").await.unwrap(), "version");
+}
+
+#[tokio::test]
+async fn server_messages_are_acknowledged() {
+    let (addr, received) = stand_in().await;
+    let client = RconClient::connect(addr, PASSWORD).await.unwrap();
+    let mut messages = client.subscribe();
+
+    assert_eq!(client.command("say -1 hello").await.unwrap(), "");
+
+    let message = tokio::time::timeout(Duration::from_secs(1), messages.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(message, "RCon admin: hello");
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // the message was sent twice, both are acknowledged but it is only delivered once
+    assert_eq!(received.lock().unwrap().acknowledged, vec![7, 7]);
+    assert!(messages.try_recv().is_err());
+}
+
+#[test]
+fn players() {
+    let response = "Players on server:\n\
+        [#] [IP Address]:[Port] [Ping] [GUID] [Name]\n\
+        --------------------------------------------------\n\
+        0   192.168.1.10:2304     31   0123456789abcdef0123456789abcdef(OK) Alice\n\
+        1   10.0.0.2:2316         -1   fedcba9876543210fedcba9876543210(?)  Bob  the Builder (Lobby)\n\
+        12  10.0.0.3:2304         0    -  Carol\n\
+        (3 players in total)";
+
+    let players = rcon::parse_players(response);
+
+    assert_eq!(
+        players,
+        vec![
+            Player {
+                slot: 0,
+                ip: "192.168.1.10".to_string(),
+                port: 2304,
+                ping: 31,
+                guid: "0123456789abcdef0123456789abcdef".to_string(),
+                verified: true,
+                name: "Alice".to_string(),
+                lobby: false,
+            },
+            Player {
+                slot: 1,
+                ip: "10.0.0.2".to_string(),
+                port: 2316,
+                ping: -1,
+                guid: "fedcba9876543210fedcba9876543210".to_string(),
+                verified: false,
+                name: "Bob  the Builder".to_string(),
+                lobby: true,
+            },
+            Player {
+                slot: 12,
+                ip: "10.0.0.3".to_string(),
+                port: 2304,
+                ping: 0,
+                guid: String::new(),
+                verified: false,
+                name: "Carol".to_string(),
+                lobby: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn messages() {
+    let message = |kind, channel: Option<&str>, slot, player: Option<&str>, guid: Option<&str>, text: &str| Message {
+        kind,
+        channel: channel.map(String::from),
+        slot,
+        player: player.map(String::from),
+        guid: guid.map(String::from),
+        text: text.to_string(),
+    };
+
+    let guid = "0123456789abcdef0123456789abcdef";
+
+    let cases = [
+        message(
+            MessageKind::Chat,
+            Some("Side"),
+            None,
+            Some("Alice"),
+            None,
+            "(Side) Alice: hello: there",
+        ),
+        message(
+            MessageKind::Connect,
+            None,
+            Some(3),
+            Some("Bob (2)"),
+            None,
+            "Player #3 Bob (2) (10.0.0.2:2304) connected",
+        ),
+        message(
+            MessageKind::Connect,
+            None,
+            Some(3),
+            Some("Bob (2)"),
+            Some(guid),
+            &format!("Player #3 Bob (2) - BE GUID: {}", guid),
+        ),
+        message(
+            MessageKind::Connect,
+            None,
+            Some(3),
+            Some("Bob (2)"),
+            Some(guid),
+            &format!("Verified GUID ({}) of player #3 Bob (2)", guid),
+        ),
+        message(
+            MessageKind::Disconnect,
+            None,
+            Some(3),
+            Some("Bob (2)"),
+            None,
+            "Player #3 Bob (2) disconnected",
+        ),
+        message(
+            MessageKind::Kick,
+            None,
+            Some(3),
+            Some("Bob"),
+            Some(guid),
+            &format!("Player #3 Bob ({}) has been kicked by BattlEye: Admin Kick (afk)", guid),
+        ),
+        message(
+            MessageKind::Filter,
+            None,
+            Some(3),
+            Some("Bob"),
+            Some(guid),
+            &format!(
+                "Player #3 Bob ({}) has been kicked by BattlEye: Script Restriction #12",
+                guid
+            ),
+        ),
+        message(
+            MessageKind::Admin,
+            Some("Global"),
+            None,
+            None,
+            None,
+            "RCon admin #0: (Global) restart in 5",
+        ),
+        message(
+            MessageKind::Admin,
+            None,
+            None,
+            None,
+            None,
+            "RCon admin #0 (127.0.0.1:50000) logged in",
+        ),
+        message(MessageKind::Other, None, None, None, None, "(Lobby) not a channel: hi"),
+    ];
+
+    for expected in cases {
+        assert_eq!(rcon::parse_message(&expected.text), expected);
+    }
+}
+
+#[test]
+fn battleye_guids() {
+    assert_eq!(rcon::battleye_guid(76561197960287930), "a357f31c8335a5263e0d816e64445b6a");
+    assert_eq!(rcon::battleye_guid(76561198012345678), "2d3fe9abaa51e04aa876a7bcfe84e0d6");
+}
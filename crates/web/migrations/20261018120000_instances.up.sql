@@ -0,0 +1,19 @@
+-- Add up migration script here
+CREATE TABLE "instances" (
+    "id"                INTEGER NOT NULL UNIQUE,
+    "name"              TEXT NOT NULL UNIQUE,
+    "port"              INTEGER NOT NULL DEFAULT 2302,
+    "preset_id"         INTEGER,
+    "config_file"       TEXT NOT NULL,
+    "profile_file"      TEXT NOT NULL,
+    "profile_name"      TEXT NOT NULL,
+    "parameters"        TEXT NOT NULL DEFAULT '',
+    "created_at"        TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    "updated_at"        TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY("id" AUTOINCREMENT)
+    FOREIGN KEY("preset_id") REFERENCES "presets"("id")
+);
+
+-- the existing setup becomes the first instance, keeping its config files in place
+INSERT INTO "instances" (name, port, config_file, profile_file, profile_name) VALUES
+    ('server', 2302, 'server.cfg', 'profile.cfg', 'server');
@@ -0,0 +1,3 @@
+-- Add down migration script here
+
+ALTER TABLE instances DROP COLUMN headless_clients;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- Alter table instances to add a column "headless_clients: integer"
+
+ALTER TABLE instances ADD COLUMN headless_clients INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE "mission_rotation" (
+    "id"          INTEGER NOT NULL UNIQUE,
+    "instance_id" INTEGER NOT NULL,
+    "position"    INTEGER NOT NULL,
+    "template"    TEXT NOT NULL,
+    "difficulty"  TEXT NOT NULL DEFAULT 'Regular',
+    "params"      TEXT NOT NULL DEFAULT '',
+    "created_at"  TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    "updated_at"  TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY("id" AUTOINCREMENT)
+    FOREIGN KEY("instance_id") REFERENCES "instances"("id") ON DELETE CASCADE
+);
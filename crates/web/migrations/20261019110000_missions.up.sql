@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE "missions" (
+    "id"          INTEGER NOT NULL UNIQUE,
+    "name"        TEXT NOT NULL UNIQUE,
+    "world"       TEXT NOT NULL,
+    "title"       TEXT,
+    "author"      TEXT,
+    "min_players" INTEGER,
+    "max_players" INTEGER,
+    "game_type"   TEXT,
+    "addons"      TEXT NOT NULL DEFAULT '',
+    "size"        INTEGER NOT NULL DEFAULT 0,
+    "created_at"  TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    "updated_at"  TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY("id" AUTOINCREMENT)
+);
@@ -0,0 +1,10 @@
+-- Add up migration script here
+CREATE TABLE "keys" (
+    "id"                INTEGER NOT NULL UNIQUE,
+    "name"              TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    "origin"            TEXT NOT NULL,
+    "published_file_id" INTEGER,
+    "dlc"               TEXT,
+    "created_at"        TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY("id" AUTOINCREMENT)
+);
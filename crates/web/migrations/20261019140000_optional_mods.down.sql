@@ -0,0 +1,3 @@
+-- Add down migration script here
+
+ALTER TABLE preset_items DROP COLUMN optional;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- Alter table preset_items to add a column "optional: bool"
+
+ALTER TABLE preset_items ADD COLUMN optional BOOL NOT NULL DEFAULT 0;
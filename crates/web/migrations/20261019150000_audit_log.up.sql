@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE "audit_log" (
+    "id"          INTEGER NOT NULL UNIQUE,
+    "instance_id" INTEGER NOT NULL,
+    "user"        TEXT NOT NULL,
+    "action"      TEXT NOT NULL,
+    "player"      TEXT,
+    "guid"        TEXT,
+    "details"     TEXT,
+    "created_at"  TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY("id" AUTOINCREMENT)
+    FOREIGN KEY("instance_id") REFERENCES "instances"("id") ON DELETE CASCADE
+);
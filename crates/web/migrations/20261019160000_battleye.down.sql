@@ -0,0 +1,6 @@
+-- Add down migration script here
+
+ALTER TABLE instances DROP COLUMN max_ping;
+ALTER TABLE instances DROP COLUMN rcon_ip;
+ALTER TABLE instances DROP COLUMN rcon_port;
+ALTER TABLE instances DROP COLUMN rcon_password;
@@ -0,0 +1,9 @@
+-- Add up migration script here
+-- Alter table instances to add the BattlEye settings, the RCon password is generated for existing instances
+
+ALTER TABLE instances ADD COLUMN rcon_password TEXT NOT NULL DEFAULT '';
+ALTER TABLE instances ADD COLUMN rcon_port INTEGER;
+ALTER TABLE instances ADD COLUMN rcon_ip TEXT;
+ALTER TABLE instances ADD COLUMN max_ping INTEGER;
+
+UPDATE instances SET rcon_password = lower(hex(randomblob(16)));
@@ -0,0 +1,13 @@
+-- Add up migration script here
+-- "kind" is one of uid, guid or ip, "expires_at" is a unix timestamp and NULL for permanent bans
+CREATE TABLE "bans" (
+    "id"          INTEGER NOT NULL UNIQUE,
+    "kind"        TEXT NOT NULL,
+    "value"       TEXT NOT NULL,
+    "expires_at"  INTEGER,
+    "reason"      TEXT NOT NULL DEFAULT '',
+    "author"      TEXT NOT NULL,
+    "created_at"  TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY("id" AUTOINCREMENT)
+    UNIQUE("kind", "value")
+);
@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE "server_messages" (
+    "id"          INTEGER NOT NULL UNIQUE,
+    "instance_id" INTEGER NOT NULL,
+    "kind"        TEXT NOT NULL,
+    "channel"     TEXT,
+    "slot"        INTEGER,
+    "player"      TEXT,
+    "guid"        TEXT,
+    "message"     TEXT NOT NULL,
+    "created_at"  TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY("id" AUTOINCREMENT)
+    FOREIGN KEY("instance_id") REFERENCES "instances"("id") ON DELETE CASCADE
+);
+
+CREATE INDEX "server_messages_instance_id" ON "server_messages" ("instance_id", "id");
@@ -0,0 +1,13 @@
+-- Add up migration script here
+-- either "interval_minutes" is set for recurring announcements or "send_at", a unix timestamp, for one-off ones
+CREATE TABLE "announcements" (
+    "id"               INTEGER NOT NULL UNIQUE,
+    "instance_id"      INTEGER NOT NULL,
+    "message"          TEXT NOT NULL,
+    "interval_minutes" INTEGER,
+    "send_at"          INTEGER,
+    "enabled"          BOOL NOT NULL DEFAULT 1,
+    "created_at"       TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY("id" AUTOINCREMENT)
+    FOREIGN KEY("instance_id") REFERENCES "instances"("id") ON DELETE CASCADE
+);
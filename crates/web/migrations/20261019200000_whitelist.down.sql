@@ -0,0 +1,7 @@
+-- Add down migration script here
+
+ALTER TABLE presets DROP COLUMN whitelist;
+ALTER TABLE instances DROP COLUMN whitelist_message;
+ALTER TABLE instances DROP COLUMN whitelist;
+
+DROP TABLE "whitelist";
@@ -0,0 +1,20 @@
+-- Add up migration script here
+-- "kind" is uid or guid, "guid" is the BattlEye GUID the entry lets in, computed for Steam UIDs
+CREATE TABLE "whitelist" (
+    "id"          INTEGER NOT NULL UNIQUE,
+    "kind"        TEXT NOT NULL,
+    "value"       TEXT NOT NULL,
+    "guid"        TEXT NOT NULL,
+    "name"        TEXT NOT NULL DEFAULT '',
+    "author"      TEXT NOT NULL,
+    "created_at"  TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY("id" AUTOINCREMENT)
+    UNIQUE("kind", "value")
+);
+
+CREATE INDEX "whitelist_guid" ON "whitelist" ("guid");
+
+-- the whitelist is enforced on an instance if either the instance or the preset it runs with turns it on
+ALTER TABLE instances ADD COLUMN whitelist BOOL NOT NULL DEFAULT 0;
+ALTER TABLE instances ADD COLUMN whitelist_message TEXT NOT NULL DEFAULT 'You are not on the whitelist of this server';
+ALTER TABLE presets ADD COLUMN whitelist BOOL NOT NULL DEFAULT 0;
@@ -0,0 +1,15 @@
+-- Add up migration script here
+-- "joined_at" and "left_at" are unix timestamps, "left_at" is moved forward while the player is still seen on the server
+CREATE TABLE "player_sessions" (
+    "id"          INTEGER NOT NULL UNIQUE,
+    "instance_id" INTEGER NOT NULL,
+    "name"        TEXT NOT NULL,
+    "guid"        TEXT,
+    "mission"     TEXT,
+    "joined_at"   INTEGER NOT NULL,
+    "left_at"     INTEGER NOT NULL,
+    PRIMARY KEY("id" AUTOINCREMENT)
+    FOREIGN KEY("instance_id") REFERENCES "instances"("id") ON DELETE CASCADE
+);
+
+CREATE INDEX "player_sessions_instance_id" ON "player_sessions" ("instance_id", "joined_at");
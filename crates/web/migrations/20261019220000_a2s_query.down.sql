@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+ALTER TABLE instances DROP COLUMN query_ip;
+ALTER TABLE instances DROP COLUMN query_port;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+-- Alter table instances to add the A2S query target, it is derived from the game port when not set
+
+ALTER TABLE instances ADD COLUMN query_port INTEGER;
+ALTER TABLE instances ADD COLUMN query_ip TEXT;
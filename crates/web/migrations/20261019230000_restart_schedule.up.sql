@@ -0,0 +1,10 @@
+-- Add up migration script here
+-- the times of day an instance restarts at, in minutes after midnight UTC
+CREATE TABLE "restart_schedule" (
+    "id"          INTEGER NOT NULL UNIQUE,
+    "instance_id" INTEGER NOT NULL,
+    "minute"      INTEGER NOT NULL,
+    "created_at"  TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY("id" AUTOINCREMENT)
+    FOREIGN KEY("instance_id") REFERENCES "instances"("id") ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+
+ALTER TABLE announcements DROP COLUMN before_restart_minutes;
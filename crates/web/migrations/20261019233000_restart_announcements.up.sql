@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- announcements can go out a number of minutes before each scheduled restart instead
+
+ALTER TABLE announcements ADD COLUMN before_restart_minutes INTEGER;
@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
 use axum::{
+    extract::Path,
+    http::StatusCode,
     response::{
         sse::{Event, KeepAlive},
         IntoResponse, Sse,
@@ -10,29 +12,82 @@ use axum::{
 use futures::Stream;
 use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
 
+use super::find_instance;
 use crate::{
-    response::{ApiResponse, ApiResult},
-    A2sService,
+    repository::PresetRepository,
+    response::{ApiResponse, ApiResult, ErrorResponse},
+    InstanceService,
 };
 
-pub async fn api_a2s_info(Extension(a2s): Extension<Arc<A2sService>>) -> ApiResult<impl IntoResponse> {
-    let info = a2s.get_latest_info(); // info or players
+pub async fn api_a2s_info(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let info = find_instance(&instances, instance).await?.a2s.get_latest_info(); // info or players
 
-    Ok(ApiResponse::new(info.unwrap()).with_root_key_name("info"))
+    Ok(ApiResponse::new(info).with_root_key_name("info"))
 }
 
-pub async fn api_a2s_players(Extension(a2s): Extension<Arc<A2sService>>) -> ApiResult<impl IntoResponse> {
-    let players = a2s.get_latest_players(); // info or players
+pub async fn api_a2s_players(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let players = find_instance(&instances, instance).await?.a2s.get_latest_players(); // info or players
 
     Ok(ApiResponse::new(players).with_root_key_name("players"))
 }
 
+pub async fn api_a2s_state(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let state = find_instance(&instances, instance).await?.a2s.get_state();
+
+    Ok(ApiResponse::new(state).with_root_key_name("state"))
+}
+
+pub async fn api_a2s_rules(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let rules = find_instance(&instances, instance).await?.a2s.get_latest_rules();
+
+    Ok(ApiResponse::new(rules).with_root_key_name("rules"))
+}
+
+/// Whether the running server loaded the mods of the preset the instance runs with.
+pub async fn check_loaded_preset(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(preset_repository): Extension<PresetRepository>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let server = find_instance(&instances, instance).await?;
+
+    let Some(rules) = server.a2s.get_latest_rules() else {
+        return Err(ErrorResponse::new("The server hasn't reported its mods yet")
+            .with_status_code(StatusCode::NOT_FOUND)
+            .into());
+    };
+
+    let preset = preset_repository
+        .get_instance_preset(&server.instance)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    let Some(preset) = preset else {
+        return Err(ErrorResponse::new("No preset selected").into());
+    };
+
+    Ok(ApiResponse::new(arma::check_loaded_preset(&preset, &rules)))
+}
+
 pub async fn sse_a2s(
-    Extension(a2s): Extension<Arc<A2sService>>,
-) -> Sse<impl Stream<Item = Result<Event, BroadcastStreamRecvError>>> {
-    let rx = a2s.subscribe();
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Path(instance): Path<i64>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, BroadcastStreamRecvError>>>> {
+    let rx = find_instance(&instances, instance).await?.a2s.subscribe();
 
     let mystream = BroadcastStream::new(rx);
 
-    Sse::new(mystream).keep_alive(KeepAlive::default())
+    Ok(Sse::new(mystream).keep_alive(KeepAlive::default()))
 }
@@ -0,0 +1,134 @@
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use api_schema::{
+    request::AnnouncementSchema,
+    response::{AnnouncementSchedule, SimpleResponse},
+};
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, Extension, Json};
+
+use super::find_instance;
+use crate::{
+    repository::AnnouncementRepository,
+    response::{ApiResponse, ApiResult, ErrorResponse},
+    service::InstanceService,
+};
+
+/// Anything longer than a week is better off as a one-off announcement.
+const MAX_INTERVAL_MINUTES: i64 = 7 * 24 * 60;
+
+/// Restarts are scheduled daily, so a warning can't be further ahead than the restart before.
+const MAX_BEFORE_RESTART_MINUTES: i64 = 24 * 60;
+
+pub async fn get_announcements(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(announcement_repository): Extension<AnnouncementRepository>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    let announcements = announcement_repository
+        .get_by_instance(instance)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(announcements))
+}
+
+pub async fn create_announcement(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(announcement_repository): Extension<AnnouncementRepository>,
+    Path(instance): Path<i64>,
+    Json(input): Json<AnnouncementSchema>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+    let input = validate(input)?;
+
+    let announcement = announcement_repository
+        .add(instance, &input)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(announcement))
+}
+
+pub async fn update_announcement(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(announcement_repository): Extension<AnnouncementRepository>,
+    Path((instance, id)): Path<(i64, i64)>,
+    Json(input): Json<AnnouncementSchema>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+    let input = validate(input)?;
+
+    let announcement = announcement_repository
+        .update(instance, id, &input)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?
+        .ok_or_else(|| ErrorResponse::new("Announcement not found").with_status_code(StatusCode::NOT_FOUND))?;
+
+    Ok(ApiResponse::new(announcement))
+}
+
+pub async fn delete_announcement(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(announcement_repository): Extension<AnnouncementRepository>,
+    Path((instance, id)): Path<(i64, i64)>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    let deleted = announcement_repository
+        .delete(instance, id)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    if !deleted {
+        return Err(ErrorResponse::new("Announcement not found")
+            .with_status_code(StatusCode::NOT_FOUND)
+            .into());
+    }
+
+    Ok(ApiResponse::new(SimpleResponse {
+        response: "OK".to_string(),
+    }))
+}
+
+/// Announcements are sent with `say`, which takes a single line.
+fn validate(mut input: AnnouncementSchema) -> Result<AnnouncementSchema, ErrorResponse> {
+    if input.message.contains(['\r', '\n']) {
+        return Err(ErrorResponse::new("Message must be a single line").with_status_code(StatusCode::BAD_REQUEST));
+    }
+
+    input.message = input.message.trim().to_string();
+
+    if input.message.is_empty() {
+        return Err(ErrorResponse::new("Message is empty").with_status_code(StatusCode::BAD_REQUEST));
+    }
+
+    match input.schedule {
+        AnnouncementSchedule::Interval { minutes } if minutes < 1 => {
+            Err(ErrorResponse::new("The interval must be at least a minute").with_status_code(StatusCode::BAD_REQUEST))
+        }
+        AnnouncementSchedule::Interval { minutes } if minutes > MAX_INTERVAL_MINUTES => {
+            Err(ErrorResponse::new("The interval can be at most a week").with_status_code(StatusCode::BAD_REQUEST))
+        }
+        AnnouncementSchedule::BeforeRestart { minutes } if !(1..=MAX_BEFORE_RESTART_MINUTES).contains(&minutes) => Err(
+            ErrorResponse::new("The announcement must go out between a minute and a day before the restart")
+                .with_status_code(StatusCode::BAD_REQUEST),
+        ),
+        AnnouncementSchedule::At { timestamp } if input.enabled && timestamp <= now() => {
+            Err(ErrorResponse::new("The time to send the announcement at has passed")
+                .with_status_code(StatusCode::BAD_REQUEST))
+        }
+        _ => Ok(input),
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs() as i64)
+        .unwrap_or_default()
+}
@@ -1,120 +1,185 @@
-use std::{sync::Arc, time::Duration};
+use std::sync::Arc;
 
-use api_schema::response::SimpleResponse;
-use axum::{response::IntoResponse, Extension};
+use api_schema::{request::UpdateRestartScheduleSchema, response::SimpleResponse};
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, Extension, Json};
 
+use super::{find_instance, get_installed_keys};
 use crate::{
-    repository::PresetRepository,
+    repository::{KeyRepository, MissionRotationRepository, PresetRepository, RestartScheduleRepository},
     response::{ApiResponse, ApiResult, ErrorResponse},
-    service::{State, StatusService},
+    service::{preflight, prepare_server, InstanceService, ServerService},
 };
 
 pub async fn start_arma(
-    Extension(status): Extension<Arc<StatusService>>,
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(servers): Extension<Arc<ServerService>>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    servers.start(instance).await.map_err(ErrorResponse::new)?;
+
+    Ok(ApiResponse::new(SimpleResponse {
+        response: "OK".to_string(),
+    }))
+}
+
+pub async fn preflight_arma(
+    Extension(instances): Extension<Arc<InstanceService>>,
     Extension(preset_repository): Extension<PresetRepository>,
+    Extension(mission_rotation_repository): Extension<MissionRotationRepository>,
+    Extension(key_repository): Extension<KeyRepository>,
+    Path(instance): Path<i64>,
 ) -> ApiResult<impl IntoResponse> {
-    if status.arma().await != State::Stopped {
-        return Err(ErrorResponse::new("Arma is already running").into());
-    }
+    let server = find_instance(&instances, instance).await?;
 
-    status.set_arma(State::Starting).await;
+    let preset = preset_repository
+        .get_instance_preset(&server.instance)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
 
-    let Ok(Some(preset)) = preset_repository.get_selected_preset().await else {
-        status.set_arma(State::Stopped).await;
+    let Some(preset) = preset else {
         return Err(ErrorResponse::new("No preset selected").into());
     };
 
-    let mod_str = match arma::get_mod_str(&preset).map_err(|e| ErrorResponse::new(format!("{}", e))) {
-        Ok(mod_str) => mod_str,
-        Err(e) => {
-            tokio::spawn(async move {
-                status.set_arma(State::Stopped).await;
-            });
-            return Err(e.into());
-        }
+    let missions = mission_rotation_repository
+        .get(instance)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    let managed_keys = get_installed_keys(&key_repository).await?;
+
+    let report = preflight(&server, &preset, &missions, &managed_keys)
+        .await
+        .map_err(ErrorResponse::new)?;
+
+    Ok(ApiResponse::new(report))
+}
+
+pub async fn preview_launch(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(preset_repository): Extension<PresetRepository>,
+    Extension(key_repository): Extension<KeyRepository>,
+    Extension(mission_rotation_repository): Extension<MissionRotationRepository>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let server = find_instance(&instances, instance).await?;
+
+    let preset = preset_repository
+        .get_instance_preset(&server.instance)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    let Some(preset) = preset else {
+        return Err(ErrorResponse::new("No preset selected").into());
     };
-    let params = arma::get_default_parameters();
-
-    arma::install_keys(&preset).map_err(|e| ErrorResponse::new(format!("{}", e)))?;
-    arma::install_dlc_keys(&preset).map_err(|e| ErrorResponse::new(format!("{}", e)))?;
-
-    let c = match arma::Arma3::new()
-        .mods(mod_str)
-        .parameters(params)
-        .run()
-        .map_err(|e| ErrorResponse::new(format!("{}", e)))
-    {
-        Ok(c) => c,
+
+    let missions = mission_rotation_repository
+        .get(instance)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    let mut warnings = Vec::new();
+
+    let arma = match prepare_server(&server, &preset, &missions) {
+        Ok(arma) => arma,
         Err(e) => {
-            tokio::spawn(async move {
-                status.set_arma(State::Stopped).await;
-            });
-            return Err(e.into());
+            warnings.push(e);
+            server.server().missions(missions)
         }
     };
 
-    let a_status = status.clone();
-    tokio::spawn(async move {
-        a_status.set_arma(State::Running).await;
-
-        loop {
-            if a_status.arma().await == State::Stopping {
-                c.kill();
-            }
-
-            match c.next().await {
-                Ok(Some(_)) => {}
-                Ok(None) => {
-                    break;
-                }
-                Err(_) => {}
-            }
-        }
+    let managed_keys = get_installed_keys(&key_repository).await?;
 
-        a_status.set_arma(State::Stopped).await;
-    });
+    // reading the configs and comparing every key of the preset touches the disk a lot, keep it off the executor
+    let (mut preview, keys) = tokio::task::spawn_blocking(move || {
+        // the same plan the start carries out
+        (arma.preview(), arma::plan_keys(&preset, &managed_keys))
+    })
+    .await
+    .map_err(|e| ErrorResponse::new(format!("Unable to preview the launch: {}", e)))?;
 
-    Ok(ApiResponse::new(SimpleResponse {
-        response: "OK".to_string(),
-    }))
-}
+    preview.keys = keys.install.into_iter().map(|planned| planned.key.name).collect();
 
-pub async fn stop_arma(Extension(status): Extension<Arc<StatusService>>) -> ApiResult<impl IntoResponse> {
-    if status.arma().await == State::Stopped {
-        return Err(ErrorResponse::new("Arma is not running").into());
-    }
+    warnings.append(&mut preview.warnings);
+    warnings.extend(keys.shadowed.into_iter().map(|name| {
+        format!(
+            "The key {} of the preset is not installed, a different key with that name is already in the keys folder",
+            name
+        )
+    }));
+    preview.warnings = warnings;
+
+    Ok(ApiResponse::new(preview))
+}
 
-    status.set_arma(State::Stopping).await;
+pub async fn stop_arma(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(servers): Extension<Arc<ServerService>>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
 
-    tokio::spawn(async move {
-        // if arma status isn't stopped within 5 seconds, find the pid and kill it
-        tokio::time::sleep(Duration::from_secs(5)).await;
-        if status.arma().await != State::Stopped {
-            arma::kill();
-            status.set_arma(State::Stopped).await;
-        }
-    });
+    servers.stop(instance).await.map_err(ErrorResponse::new)?;
 
     Ok(ApiResponse::new(SimpleResponse {
         response: "OK".to_string(),
     }))
 }
 
-pub async fn restart_arma(Extension(status): Extension<Arc<StatusService>>) -> ApiResult<impl IntoResponse> {
-    tokio::spawn(async move {
-        // change state to stopping
-        status.set_arma(State::Stopping).await;
-        // sleep for 3 seconds
-        tokio::time::sleep(Duration::from_secs(3)).await;
-        // change state to starting
-        status.set_arma(State::Starting).await;
-        // sleep for 5 seconds
-        tokio::time::sleep(Duration::from_secs(5)).await;
-        // set state to running
-        status.set_arma(State::Running).await;
-    });
+/// Stops the server and starts it again once it is down.
+pub async fn restart_arma(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(servers): Extension<Arc<ServerService>>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    servers.restart(instance).await.map_err(ErrorResponse::new)?;
 
     Ok(ApiResponse::new(SimpleResponse {
         response: "OK".to_string(),
     }))
 }
+
+/// The times of day the server restarts at, in minutes after midnight UTC.
+pub async fn get_restart_schedule(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(restart_schedule_repository): Extension<RestartScheduleRepository>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    let minutes = restart_schedule_repository
+        .get(instance)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(minutes))
+}
+
+pub async fn update_restart_schedule(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(restart_schedule_repository): Extension<RestartScheduleRepository>,
+    Path(instance): Path<i64>,
+    Json(input): Json<UpdateRestartScheduleSchema>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    if let Some(minute) = input.minutes.iter().find(|minute| !(0..24 * 60).contains(*minute)) {
+        return Err(ErrorResponse::new(format!("{} is not a time of day", minute))
+            .with_status_code(StatusCode::BAD_REQUEST)
+            .into());
+    }
+
+    let mut minutes = input.minutes;
+    minutes.sort();
+    minutes.dedup();
+
+    let minutes = restart_schedule_repository
+        .set(instance, minutes)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(minutes))
+}
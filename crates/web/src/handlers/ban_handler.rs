@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use api_schema::{
+    request::*,
+    response::{BanKind, SimpleResponse},
+};
+use axum::{
+    extract::Path,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use crate::{
+    model::User,
+    repository::{BanRepository, NewBan},
+    response::{ApiResponse, ApiResult, ErrorResponse},
+    service::{ban_expiry, battleye_bans, engine_bans, BanService, InstanceService, StatusService},
+};
+
+pub async fn get_bans(Extension(ban_repository): Extension<BanRepository>) -> ApiResult<impl IntoResponse> {
+    let bans = ban_repository
+        .get_all()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(bans))
+}
+
+pub async fn create_ban(
+    Extension(bans): Extension<Arc<BanService>>,
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(status): Extension<Arc<StatusService>>,
+    Extension(user): Extension<User>,
+    Json(input): Json<CreateBanSchema>,
+) -> ApiResult<impl IntoResponse> {
+    let value = input.value.trim().to_lowercase();
+
+    let (valid, name) = match input.kind {
+        BanKind::Uid => (arma::is_uid(&value), "Steam UID"),
+        BanKind::Guid => (arma::is_guid(&value), "GUID"),
+        BanKind::Ip => (arma::is_ip(&value), "IPv4 address"),
+    };
+
+    if !valid {
+        return Err(ErrorResponse::new(format!("{} is not a valid {}", value, name))
+            .with_status_code(StatusCode::BAD_REQUEST)
+            .into());
+    }
+
+    if input.minutes < 0 {
+        return Err(ErrorResponse::new("The ban duration can't be negative")
+            .with_status_code(StatusCode::BAD_REQUEST)
+            .into());
+    }
+
+    // the engine has no place for a reason or an expiry
+    if input.kind == BanKind::Uid && input.minutes > 0 {
+        return Err(ErrorResponse::new("Steam UID bans are always permanent")
+            .with_status_code(StatusCode::BAD_REQUEST)
+            .into());
+    }
+
+    let ban = NewBan {
+        kind: input.kind,
+        value,
+        expires_at: ban_expiry(input.minutes),
+        reason: input.reason.lines().collect::<Vec<_>>().join(" ").trim().to_string(),
+        author: user.name,
+    };
+
+    let ban = bans
+        .add(ban, &instances, &status)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Unable to ban: {}", e)))?;
+
+    Ok(ApiResponse::new(ban))
+}
+
+pub async fn delete_ban(
+    Extension(ban_repository): Extension<BanRepository>,
+    Extension(bans): Extension<Arc<BanService>>,
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(status): Extension<Arc<StatusService>>,
+    Path(id): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let deleted = ban_repository
+        .delete(id)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    if !deleted {
+        return Err(ErrorResponse::new("Ban not found")
+            .with_status_code(StatusCode::NOT_FOUND)
+            .into());
+    }
+
+    sync(&bans, &instances, &status).await?;
+
+    Ok(ApiResponse::new(SimpleResponse {
+        response: "OK".to_string(),
+    }))
+}
+
+/// Adds the bans of a `ban.txt` or BattlEye `bans.txt`, bans that are already there are kept as they are.
+pub async fn import_bans(
+    Extension(ban_repository): Extension<BanRepository>,
+    Extension(bans): Extension<Arc<BanService>>,
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(status): Extension<Arc<StatusService>>,
+    Extension(user): Extension<User>,
+    Json(input): Json<ImportBansSchema>,
+) -> ApiResult<impl IntoResponse> {
+    let imported = match input.format {
+        BanListFormat::Engine => arma::parse_engine_bans(&input.content)
+            .into_iter()
+            .map(|uid| NewBan {
+                kind: BanKind::Uid,
+                value: uid,
+                expires_at: None,
+                reason: String::new(),
+                author: user.name.clone(),
+            })
+            .collect::<Vec<_>>(),
+        BanListFormat::BattlEye => arma::parse_battleye_bans(&input.content)
+            .into_iter()
+            .map(|ban| NewBan {
+                kind: if arma::is_ip(&ban.target) {
+                    BanKind::Ip
+                } else {
+                    BanKind::Guid
+                },
+                value: ban.target,
+                expires_at: ban.expires_at,
+                reason: ban.reason,
+                author: user.name.clone(),
+            })
+            .collect::<Vec<_>>(),
+    };
+
+    let added = ban_repository
+        .add_missing(imported)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    sync(&bans, &instances, &status).await?;
+
+    Ok(ApiResponse::new(SimpleResponse {
+        response: format!("{} bans imported", added),
+    }))
+}
+
+/// The active bans in the format of the given file.
+pub async fn export_bans(
+    Extension(ban_repository): Extension<BanRepository>,
+    Path(format): Path<BanListFormat>,
+) -> ApiResult<impl IntoResponse> {
+    let bans = ban_repository
+        .get_active()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    let (name, content) = match format {
+        BanListFormat::Engine => ("ban.txt", arma::format_engine_bans(&engine_bans(&bans))),
+        BanListFormat::BattlEye => ("bans.txt", arma::format_battleye_bans(&battleye_bans(&bans))),
+    };
+
+    let headers = [
+        (header::CONTENT_TYPE, "text/plain; charset=utf-8".to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", name),
+        ),
+    ];
+
+    Ok((headers, content))
+}
+
+async fn sync(bans: &BanService, instances: &InstanceService, status: &StatusService) -> Result<(), ErrorResponse> {
+    bans.sync(instances, status)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Unable to write the bans: {}", e)))
+}
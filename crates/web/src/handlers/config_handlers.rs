@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use api_schema::{request::*, response::*};
 use axum::{
     extract::Path,
@@ -12,13 +14,21 @@ use tokio_stream::{
     Stream,
 };
 
+use super::find_instance;
 use crate::{
     response::{ApiResponse, ApiResult, ErrorResponse},
-    ConfigService,
+    InstanceService,
 };
 
-pub async fn get_config(Path(channel): Path<String>) -> ApiResult<impl IntoResponse> {
-    let config_file = paths::get_config_path().join(channel);
+pub async fn get_config(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Path((instance, channel)): Path<(i64, String)>,
+) -> ApiResult<impl IntoResponse> {
+    let server = find_instance(&instances, instance).await?;
+
+    let Some(config_file) = server.config.path(&channel) else {
+        return Err(ErrorResponse::new(format!("Unknown config {}", channel)).into());
+    };
 
     // check if file exists
     if !config_file.exists() {
@@ -35,13 +45,15 @@ pub async fn get_config(Path(channel): Path<String>) -> ApiResult<impl IntoRespo
 }
 
 pub async fn post_config(
-    Extension(arma_config): Extension<ConfigService>,
-    Path(channel): Path<String>,
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Path((instance, channel)): Path<(i64, String)>,
     Json(body): Json<UpdateConfigSchema>,
 ) -> ApiResult<impl IntoResponse> {
+    let server = find_instance(&instances, instance).await?;
     let body = body.config;
 
-    arma_config
+    server
+        .config
         .update_config(channel, body)
         .await
         .map_err(|e| ErrorResponse::new(format!("{}", e)))?;
@@ -52,9 +64,10 @@ pub async fn post_config(
 }
 
 pub async fn sse_config(
-    Extension(arma_config): Extension<ConfigService>,
-) -> Sse<impl Stream<Item = Result<Event, BroadcastStreamRecvError>>> {
-    let rx = arma_config.subscribe();
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Path(instance): Path<i64>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, BroadcastStreamRecvError>>>> {
+    let rx = find_instance(&instances, instance).await?.config.subscribe();
 
-    Sse::new(BroadcastStream::new(rx)).keep_alive(KeepAlive::default())
+    Ok(Sse::new(BroadcastStream::new(rx)).keep_alive(KeepAlive::default()))
 }
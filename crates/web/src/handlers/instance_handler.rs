@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use api_schema::{request::*, response::SimpleResponse};
+use axum::{http::StatusCode, response::IntoResponse, Extension, Json};
+
+use crate::{
+    response::{ApiResponse, ApiResult, ErrorResponse},
+    service::{InstanceService, ServerInstance, State, StatusService},
+};
+
+pub async fn get_instances(Extension(instances): Extension<Arc<InstanceService>>) -> ApiResult<impl IntoResponse> {
+    Ok(ApiResponse::new(instances.get_all().await))
+}
+
+pub async fn create_instance(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Json(input): Json<CreateInstanceSchema>,
+) -> ApiResult<impl IntoResponse> {
+    let instance = instances
+        .create(input)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("{}", e)))?;
+
+    Ok(ApiResponse::new(instance))
+}
+
+pub async fn update_instance(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(status): Extension<Arc<StatusService>>,
+    Json(input): Json<UpdateInstanceSchema>,
+) -> ApiResult<impl IntoResponse> {
+    if status.arma(input.id).await != State::Stopped {
+        return Err(ErrorResponse::new("Stop the server before changing its instance").into());
+    }
+
+    let instance = instances
+        .update(input)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("{}", e)))?;
+
+    Ok(ApiResponse::new(instance))
+}
+
+pub async fn delete_instance(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(status): Extension<Arc<StatusService>>,
+    Json(input): Json<DeleteInstanceSchema>,
+) -> ApiResult<impl IntoResponse> {
+    if status.arma(input.id).await != State::Stopped {
+        return Err(ErrorResponse::new("Stop the server before deleting its instance").into());
+    }
+
+    instances
+        .delete(input.id)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("{}", e)))?;
+
+    status.remove_arma(input.id).await;
+
+    Ok(ApiResponse::new(SimpleResponse {
+        response: "OK".to_string(),
+    }))
+}
+
+/// Looks up the instance from the route, shared by every instance scoped handler.
+pub(crate) async fn find_instance(instances: &InstanceService, id: i64) -> ApiResult<Arc<ServerInstance>> {
+    instances.get(id).await.ok_or_else(|| {
+        ErrorResponse::new(format!("Instance {} does not exist", id))
+            .with_status_code(StatusCode::NOT_FOUND)
+            .into()
+    })
+}
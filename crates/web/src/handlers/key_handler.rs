@@ -0,0 +1,187 @@
+use std::path::PathBuf;
+
+use api_schema::response::{KeyOrigin, ServerKey, SimpleResponse};
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, Extension};
+use axum_extra::extract::Multipart;
+
+use crate::{
+    repository::KeyRepository,
+    response::{ApiResponse, ApiResult, ErrorResponse},
+};
+
+/// Every key in the `keys` folder of the server and where it came from.
+pub async fn get_server_keys(Extension(key_repository): Extension<KeyRepository>) -> ApiResult<impl IntoResponse> {
+    Ok(ApiResponse::new(list_keys(&key_repository).await?))
+}
+
+/// Uploads a key for mods the server doesn't load itself, it is kept until deleted again.
+pub async fn upload_key(
+    Extension(key_repository): Extension<KeyRepository>,
+    mut multipart: Multipart,
+) -> ApiResult<impl IntoResponse> {
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Failed to read upload: {}", e)))?
+        .ok_or_else(|| ErrorResponse::new("No file uploaded").with_status_code(StatusCode::BAD_REQUEST))?;
+
+    let name = field.file_name().unwrap_or_default().to_string();
+
+    // only a plain file name, anything else could end up outside of the keys folder
+    let valid_name = std::path::Path::new(&name)
+        .file_name()
+        .is_some_and(|file_name| file_name.to_string_lossy() == name);
+
+    if !valid_name || !name.to_lowercase().ends_with(".bikey") {
+        return Err(ErrorResponse::new("Only .bikey files can be uploaded")
+            .with_status_code(StatusCode::BAD_REQUEST)
+            .into());
+    }
+
+    let data = field
+        .bytes()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Failed to read upload: {}", e)))?;
+
+    let Some(authority) = arma::parse_authority(&data) else {
+        return Err(ErrorResponse::new(format!("{} is not a valid key", name))
+            .with_status_code(StatusCode::BAD_REQUEST)
+            .into());
+    };
+
+    let keys_path = get_keys_path()?;
+    let key_path = keys_path.join(&name);
+
+    if key_path.exists() {
+        return Err(ErrorResponse::new(format!("A key named {} already exists", name))
+            .with_status_code(StatusCode::CONFLICT)
+            .into());
+    }
+
+    tokio::fs::create_dir_all(&keys_path)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Failed to create the keys folder: {}", e)))?;
+
+    tokio::fs::write(&key_path, &data)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Failed to write file: {}", e)))?;
+
+    key_repository
+        .add_manual(&name)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(ServerKey {
+        name,
+        authority: Some(authority),
+        origin: KeyOrigin::Manual,
+        published_file_id: None,
+        dlc: None,
+    }))
+}
+
+/// Deletes an uploaded key or one that was put into the folder by hand.
+/// Keys of mods and DLCs would only come back on the next start, those have to be disabled in the preset.
+pub async fn delete_key(
+    Extension(key_repository): Extension<KeyRepository>,
+    Path(name): Path<String>,
+) -> ApiResult<impl IntoResponse> {
+    let key = list_keys(&key_repository)
+        .await?
+        .into_iter()
+        .find(|key| key.name.eq_ignore_ascii_case(&name))
+        .ok_or_else(|| ErrorResponse::new(format!("Key {} not found", name)).with_status_code(StatusCode::NOT_FOUND))?;
+
+    match key.origin {
+        KeyOrigin::Manual | KeyOrigin::Unmanaged => {}
+        KeyOrigin::Game => {
+            return Err(ErrorResponse::new("The key of the base game can't be deleted")
+                .with_status_code(StatusCode::CONFLICT)
+                .into());
+        }
+        KeyOrigin::Mod | KeyOrigin::Dlc => {
+            return Err(ErrorResponse::new(format!(
+                "{} is installed from the preset, disable the mod instead",
+                key.name
+            ))
+            .with_status_code(StatusCode::CONFLICT)
+            .into());
+        }
+    }
+
+    tokio::fs::remove_file(get_keys_path()?.join(&key.name))
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Failed to delete {}: {}", key.name, e)))?;
+
+    key_repository
+        .delete(&key.name)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(SimpleResponse {
+        response: "OK".to_string(),
+    }))
+}
+
+/// The names of the keys the next start replaces, needed to tell which keys the server will end up with.
+pub(crate) async fn get_installed_keys(key_repository: &KeyRepository) -> Result<Vec<String>, ErrorResponse> {
+    key_repository
+        .get_installed()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))
+}
+
+fn get_keys_path() -> Result<PathBuf, ErrorResponse> {
+    arma::get_keys_path().ok_or_else(|| ErrorResponse::new("Arma 3 Server is not installed"))
+}
+
+async fn list_keys(key_repository: &KeyRepository) -> Result<Vec<ServerKey>, ErrorResponse> {
+    let keys_path = get_keys_path()?;
+
+    let known = key_repository
+        .get_all()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    let keys = tokio::task::spawn_blocking(move || {
+        let Ok(entries) = std::fs::read_dir(&keys_path) else {
+            return vec![];
+        };
+
+        let mut keys = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .map(|path| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+                let mut key = known
+                    .iter()
+                    .find(|key| key.name.eq_ignore_ascii_case(&name))
+                    .cloned()
+                    .unwrap_or_else(|| ServerKey {
+                        name: name.clone(),
+                        authority: None,
+                        origin: if name.eq_ignore_ascii_case("a3.bikey") {
+                            KeyOrigin::Game
+                        } else {
+                            KeyOrigin::Unmanaged
+                        },
+                        published_file_id: None,
+                        dlc: None,
+                    });
+
+                key.name = name;
+                key.authority = arma::read_authority(&path);
+                key
+            })
+            .collect::<Vec<_>>();
+
+        keys.sort_by_key(|key| key.name.to_lowercase());
+        keys
+    })
+    .await
+    .map_err(|e| ErrorResponse::new(format!("Failed to read keys: {}", e)))?;
+
+    Ok(keys)
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use api_schema::response::LogResponse;
 use axum::{
     extract::Path,
@@ -10,9 +12,10 @@ use axum::{
 use futures::Stream;
 use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
 
+use super::find_instance;
 use crate::{
     response::{ApiResponse, ApiResult},
-    service::LogService,
+    service::{InstanceService, LogService},
 };
 
 pub async fn api_logs(
@@ -33,3 +36,21 @@ pub async fn sse_logs(
 
     Sse::new(mystream).keep_alive(KeepAlive::default())
 }
+
+pub async fn api_instance_logs(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Path((instance, channel)): Path<(i64, String)>,
+) -> ApiResult<impl IntoResponse> {
+    let log = find_instance(&instances, instance).await?.log.get_latest(channel);
+
+    Ok(ApiResponse::new(LogResponse { log }).with_root_key_name("log"))
+}
+
+pub async fn sse_instance_logs(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Path(instance): Path<i64>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, BroadcastStreamRecvError>>>> {
+    let rx = find_instance(&instances, instance).await?.log.subscribe();
+
+    Ok(Sse::new(BroadcastStream::new(rx)).keep_alive(KeepAlive::default()))
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use api_schema::request::MessageQuery;
+use axum::{
+    extract::{Path, Query},
+    response::{
+        sse::{Event, KeepAlive},
+        IntoResponse, Sse,
+    },
+    Extension,
+};
+use futures::Stream;
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
+    StreamExt,
+};
+
+use super::find_instance;
+use crate::{
+    repository::MessageRepository,
+    response::{ApiResponse, ApiResult, ErrorResponse},
+    service::{InstanceService, MessageService},
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+/// A page of the messages of an instance, newest first.
+pub async fn get_messages(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(message_repository): Extension<MessageRepository>,
+    Path(instance): Path<i64>,
+    Query(query): Query<MessageQuery>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let messages = message_repository
+        .search(instance, &query, limit)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(messages))
+}
+
+/// New messages of the instance as they come in.
+pub async fn sse_messages(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(messages): Extension<Arc<MessageService>>,
+    Path(instance): Path<i64>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, BroadcastStreamRecvError>>>> {
+    find_instance(&instances, instance).await?;
+
+    let stream = BroadcastStream::new(messages.subscribe()).filter_map(move |message| match message {
+        Ok(message) if message.instance_id == instance => {
+            let json = serde_json::to_string(&message).expect("serde to work");
+            Some(Ok(Event::default().event("message").data(json)))
+        }
+        Ok(_) => None,
+        Err(e) => Some(Err(e)),
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
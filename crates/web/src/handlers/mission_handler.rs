@@ -1,26 +1,37 @@
 // a single function to upload a mission file
 
-use api_schema::response::{MissionResponse, SimpleResponse};
-use axum::response::IntoResponse;
-use axum_extra::extract::Multipart;
+use std::sync::Arc;
 
-use crate::response::{ApiResponse, ApiResult, ErrorResponse};
+use api_schema::{
+    request::{RenameMissionSchema, UpdateMissionRotationSchema},
+    response::{Mission, MissionResponse, RotationMission, SimpleResponse},
+};
+use axum::{
+    body::StreamBody,
+    extract::Path,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Extension, Json,
+};
+use axum_extra::extract::Multipart;
+use tokio_util::io::ReaderStream;
 
-pub async fn get_missions() -> ApiResult<impl IntoResponse> {
-    let Some(arma_path) = paths::get_arma_path() else {
-        return Err(ErrorResponse::new("Arma not installed").into());
-    };
+use super::find_instance;
+use crate::{
+    repository::{MissionRepository, MissionRotationRepository, PresetRepository},
+    response::{ApiResponse, ApiResult, ErrorResponse},
+    service::{AddonService, InstanceService, State, StatusService},
+};
 
-    let missions_path = arma_path.join("mpmissions");
+pub async fn get_missions(Extension(mission_repository): Extension<MissionRepository>) -> ApiResult<impl IntoResponse> {
+    let missions_path = get_missions_path().await?;
 
-    // if not exists, create
-    if !missions_path.exists() {
-        tokio::fs::create_dir_all(&missions_path)
-            .await
-            .map_err(|e| ErrorResponse::new(format!("Failed to create dir: {}", e)))?;
-    }
+    let known = mission_repository
+        .get_all()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
 
-    let mut missions = Vec::new();
+    let mut found = Vec::new();
 
     let mut dir_contents = tokio::fs::read_dir(missions_path)
         .await
@@ -33,45 +44,479 @@ pub async fn get_missions() -> ApiResult<impl IntoResponse> {
     {
         let path = entry.path();
 
-        if path.is_file() {
-            let filename = path.file_name().unwrap().to_string_lossy().to_string();
+        if !path.is_file() {
+            continue;
+        }
+
+        let filename = path.file_name().unwrap().to_string_lossy().to_string();
 
-            if filename.ends_with(".pbo") {
-                let filename = filename.strip_suffix(".pbo").unwrap().to_string();
-                missions.push(filename);
-            }
+        let Some(name) = filename.strip_suffix(".pbo") else {
+            continue;
+        };
+
+        let size = entry
+            .metadata()
+            .await
+            .map(|metadata| metadata.len() as i64)
+            .unwrap_or_default();
+
+        found.push(name.to_string());
+
+        // only read missions that were copied in by hand or changed since we last looked
+        if known.iter().any(|mission| mission.name == name && mission.size == size) {
+            continue;
         }
+
+        let metadata = tokio::task::spawn_blocking(move || arma::read_mission_file(&path))
+            .await
+            .map_err(|e| ErrorResponse::new(format!("Failed to read mission: {}", e)))?;
+
+        // still list missions we can't make sense of, the server might
+        let metadata = metadata.unwrap_or_else(|e| {
+            tracing::warn!("{}", e);
+
+            let (_, world) = name.rsplit_once('.').unwrap_or_default();
+
+            arma::MissionMetadata {
+                name: name.to_string(),
+                world: world.to_string(),
+                ..Default::default()
+            }
+        });
+
+        mission_repository
+            .upsert(&metadata, size)
+            .await
+            .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
     }
 
+    for mission in known.iter().filter(|mission| !found.contains(&mission.name)) {
+        mission_repository
+            .delete(mission.id)
+            .await
+            .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+    }
+
+    let missions = mission_repository
+        .get_all()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
     Ok(ApiResponse::new(MissionResponse { missions }))
 }
 
-pub async fn upload_mission(mut multipart: Multipart) -> ApiResult<impl IntoResponse> {
-    while let Some(field) = multipart.next_field().await.unwrap() {
-        let name = field.file_name().unwrap().to_string();
-        let data = field.bytes().await.unwrap();
+/// Accepts mission PBOs named `<mission>.<world>.pbo` and zipped mission folders,
+/// anything else is rejected before it is written.
+/// Existing missions are never overwritten, they have to be replaced which keeps the previous version.
+pub async fn upload_mission(
+    Extension(mission_repository): Extension<MissionRepository>,
+    mut multipart: Multipart,
+) -> ApiResult<impl IntoResponse> {
+    let mut missions = Vec::new();
 
-        let Some(arma_path) = paths::get_arma_path() else {
-            return Err(ErrorResponse::new("Arma not installed").into());
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Failed to read upload: {}", e)))?
+    {
+        let Some(name) = field.file_name().map(|name| name.to_string()) else {
+            continue;
         };
 
-        let missions_path = arma_path.join("mpmissions");
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| ErrorResponse::new(format!("Failed to read upload: {}", e)))?
+            .to_vec();
 
-        // if not exists, create
-        if !missions_path.exists() {
-            tokio::fs::create_dir_all(&missions_path)
-                .await
-                .map_err(|e| ErrorResponse::new(format!("Failed to create dir: {}", e)))?;
-        }
+        // packing and reading large missions takes a while, so it is kept off the executor
+        let (data, metadata) = tokio::task::spawn_blocking(move || {
+            // unpacked mission folders are packed here, the PBO is then handled like an uploaded one
+            let (name, data) = if name.to_lowercase().ends_with(".zip") {
+                arma::pack_mission_zip(&name, &data)?
+            } else {
+                (name, data)
+            };
 
-        let file_path = missions_path.join(name);
+            arma::read_mission(&name, &data).map(|metadata| (data, metadata))
+        })
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Failed to read mission: {}", e)))?
+        .map_err(|e| ErrorResponse::new(e).with_status_code(StatusCode::BAD_REQUEST))?;
 
-        tokio::fs::write(file_path, data)
+        let existing = mission_repository
+            .get_by_name(&metadata.name)
+            .await
+            .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+        let file_path = get_missions_path().await?.join(format!("{}.pbo", metadata.name));
+
+        // the file might not be listed yet if it was copied in by hand
+        if existing.is_some() || file_path.exists() {
+            return Err(
+                ErrorResponse::new(format!("Mission {} already exists, replace it instead", metadata.name))
+                    .with_status_code(StatusCode::CONFLICT)
+                    .into(),
+            );
+        }
+
+        tokio::fs::write(file_path, &data)
             .await
             .map_err(|e| ErrorResponse::new(format!("Failed to write file: {}", e)))?;
+
+        let mission = mission_repository
+            .upsert(&metadata, data.len() as i64)
+            .await
+            .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+        missions.push(mission);
+    }
+
+    Ok(ApiResponse::new(MissionResponse { missions }))
+}
+
+/// Uploads a new version of a mission. The name stays the same so rotations keep working,
+/// the previous file is moved to the mission archive.
+pub async fn replace_mission(
+    Extension(mission_repository): Extension<MissionRepository>,
+    Path(id): Path<i64>,
+    mut multipart: Multipart,
+) -> ApiResult<impl IntoResponse> {
+    let mission = find_mission(&mission_repository, id).await?;
+
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Failed to read upload: {}", e)))?
+        .ok_or_else(|| ErrorResponse::new("No file uploaded").with_status_code(StatusCode::BAD_REQUEST))?;
+
+    let data = field
+        .bytes()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Failed to read upload: {}", e)))?;
+
+    let file_name = format!("{}.pbo", mission.name);
+
+    let metadata = {
+        let (file_name, data) = (file_name.clone(), data.clone());
+
+        tokio::task::spawn_blocking(move || arma::read_mission(&file_name, &data))
+            .await
+            .map_err(|e| ErrorResponse::new(format!("Failed to read mission: {}", e)))?
+            .map_err(|e| ErrorResponse::new(e).with_status_code(StatusCode::BAD_REQUEST))?
+    };
+
+    let missions_path = get_missions_path().await?;
+    let file_path = missions_path.join(&file_name);
+
+    // the upload is written next to the mission first, so a failed write leaves the current version in place
+    let upload_path = missions_path.join(format!("{}.upload", file_name));
+
+    if let Err(e) = tokio::fs::write(&upload_path, &data).await {
+        let _ = tokio::fs::remove_file(&upload_path).await;
+        return Err(ErrorResponse::new(format!("Failed to write file: {}", e)).into());
+    }
+
+    let archive_path =
+        paths::get_mission_archive_path(mission.id).join(format!("{}.v{}.pbo", mission.name, mission.version));
+    let archived = file_path.exists();
+
+    if archived {
+        if let Err(e) = tokio::fs::rename(&file_path, &archive_path).await {
+            let _ = tokio::fs::remove_file(&upload_path).await;
+            return Err(ErrorResponse::new(format!("Failed to archive {}: {}", file_name, e)).into());
+        }
+    }
+
+    if let Err(e) = tokio::fs::rename(&upload_path, &file_path).await {
+        if archived {
+            let _ = tokio::fs::rename(&archive_path, &file_path).await;
+        }
+
+        let _ = tokio::fs::remove_file(&upload_path).await;
+        return Err(ErrorResponse::new(format!("Failed to write file: {}", e)).into());
+    }
+
+    let mission = mission_repository
+        .replace(mission.id, &metadata, data.len() as i64)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(mission))
+}
+
+/// Renames the mission file, rotations that use it are updated to the new name.
+pub async fn rename_mission(
+    Extension(mission_repository): Extension<MissionRepository>,
+    Extension(mission_rotation_repository): Extension<MissionRotationRepository>,
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(status): Extension<Arc<StatusService>>,
+    Path(id): Path<i64>,
+    Json(input): Json<RenameMissionSchema>,
+) -> ApiResult<impl IntoResponse> {
+    let mission = find_mission(&mission_repository, id).await?;
+
+    let file_name = format!("{}.pbo", input.name.trim());
+
+    let (_, world) = arma::parse_mission_file_name(&file_name)
+        .map_err(|e| ErrorResponse::new(e).with_status_code(StatusCode::BAD_REQUEST))?;
+
+    let name = input.name.trim().to_string();
+
+    if name == mission.name {
+        return Ok(ApiResponse::new(mission));
+    }
+
+    let existing = mission_repository
+        .get_by_name(&name)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    let missions_path = get_missions_path().await?;
+
+    // only differing in case is fine, that is the same file
+    let taken = existing.is_some_and(|existing| existing.id != mission.id)
+        || (!name.eq_ignore_ascii_case(&mission.name) && missions_path.join(&file_name).exists());
+
+    if taken {
+        return Err(ErrorResponse::new(format!("Mission {} already exists", name))
+            .with_status_code(StatusCode::CONFLICT)
+            .into());
+    }
+
+    ensure_not_in_use(&mission_rotation_repository, &instances, &status, &mission).await?;
+
+    tokio::fs::rename(
+        missions_path.join(format!("{}.pbo", mission.name)),
+        missions_path.join(&file_name),
+    )
+    .await
+    .map_err(|e| ErrorResponse::new(format!("Failed to rename {}: {}", mission.name, e)))?;
+
+    let renamed = mission_repository
+        .rename(mission.id, &name, &world)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    mission_rotation_repository
+        .rename_template(&mission.name, &name)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(renamed))
+}
+
+/// Deletes the mission and its archived versions, unless a running server has it in its rotation.
+pub async fn delete_mission(
+    Extension(mission_repository): Extension<MissionRepository>,
+    Extension(mission_rotation_repository): Extension<MissionRotationRepository>,
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(status): Extension<Arc<StatusService>>,
+    Path(id): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let mission = find_mission(&mission_repository, id).await?;
+
+    ensure_not_in_use(&mission_rotation_repository, &instances, &status, &mission).await?;
+
+    let file_path = get_missions_path().await?.join(format!("{}.pbo", mission.name));
+
+    if file_path.exists() {
+        tokio::fs::remove_file(file_path)
+            .await
+            .map_err(|e| ErrorResponse::new(format!("Failed to delete {}: {}", mission.name, e)))?;
     }
 
+    let _ = tokio::fs::remove_dir_all(paths::get_mission_archive_path(mission.id)).await;
+
+    mission_repository
+        .delete(mission.id)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
     Ok(ApiResponse::new(SimpleResponse {
         response: "OK".to_string(),
     }))
 }
+
+pub async fn download_mission(
+    Extension(mission_repository): Extension<MissionRepository>,
+    Path(id): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let mission = find_mission(&mission_repository, id).await?;
+
+    let file_name = format!("{}.pbo", mission.name);
+
+    let file = tokio::fs::File::open(get_missions_path().await?.join(&file_name))
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Failed to open {}: {}", file_name, e)))?;
+
+    let length = file
+        .metadata()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Failed to open {}: {}", file_name, e)))?
+        .len();
+
+    let headers = [
+        (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+        (header::CONTENT_LENGTH, length.to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", file_name.replace('"', "")),
+        ),
+    ];
+
+    Ok((headers, StreamBody::new(ReaderStream::new(file))))
+}
+
+async fn find_mission(mission_repository: &MissionRepository, id: i64) -> Result<Mission, ErrorResponse> {
+    mission_repository
+        .get_by_id(id)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?
+        .ok_or_else(|| ErrorResponse::new("Mission not found").with_status_code(StatusCode::NOT_FOUND))
+}
+
+/// Refuses changes to a mission that a running server has in its rotation, it would fail on the next mission change.
+async fn ensure_not_in_use(
+    mission_rotation_repository: &MissionRotationRepository,
+    instances: &InstanceService,
+    status: &StatusService,
+    mission: &Mission,
+) -> Result<(), ErrorResponse> {
+    for instance in instances.get_all().await {
+        if status.arma(instance.id).await == State::Stopped {
+            continue;
+        }
+
+        let rotation = mission_rotation_repository
+            .get(instance.id)
+            .await
+            .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+        if rotation
+            .iter()
+            .any(|entry| entry.template.eq_ignore_ascii_case(&mission.name))
+        {
+            return Err(ErrorResponse::new(format!(
+                "{} is in the rotation of {}, stop the server first",
+                mission.name, instance.name
+            ))
+            .with_status_code(StatusCode::CONFLICT));
+        }
+    }
+
+    Ok(())
+}
+
+async fn get_missions_path() -> Result<std::path::PathBuf, ErrorResponse> {
+    let Some(arma_path) = paths::get_arma_path() else {
+        return Err(ErrorResponse::new("Arma not installed"));
+    };
+
+    let missions_path = arma_path.join("mpmissions");
+
+    // if not exists, create
+    if !missions_path.exists() {
+        tokio::fs::create_dir_all(&missions_path)
+            .await
+            .map_err(|e| ErrorResponse::new(format!("Failed to create dir: {}", e)))?;
+    }
+
+    Ok(missions_path)
+}
+
+pub async fn get_mission_rotation(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(mission_rotation_repository): Extension<MissionRotationRepository>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    let missions = mission_rotation_repository
+        .get(instance)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(missions))
+}
+
+pub async fn update_mission_rotation(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(mission_rotation_repository): Extension<MissionRotationRepository>,
+    Path(instance): Path<i64>,
+    Json(input): Json<UpdateMissionRotationSchema>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    for mission in &input.missions {
+        validate_mission(mission).map_err(ErrorResponse::new)?;
+    }
+
+    let missions = mission_rotation_repository
+        .set(instance, input.missions)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(missions))
+}
+
+/// Checks every mission against the preset the instance launches with.
+pub async fn get_mission_addons(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(preset_repository): Extension<PresetRepository>,
+    Extension(mission_repository): Extension<MissionRepository>,
+    Extension(addons): Extension<Arc<AddonService>>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let server = find_instance(&instances, instance).await?;
+
+    let preset = preset_repository
+        .get_instance_preset(&server.instance)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    let Some(preset) = preset else {
+        return Err(ErrorResponse::new("No preset selected").into());
+    };
+
+    let missions = mission_repository
+        .get_all()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(addons.check_missions(&preset, &missions).await))
+}
+
+/// Everything in a mission ends up in the config, so only allow what the server can make sense of.
+fn validate_mission(mission: &RotationMission) -> Result<(), String> {
+    if !arma::mission_exists(&mission.template) {
+        return Err(format!("Mission {} does not exist in mpmissions", mission.template));
+    }
+
+    if !arma::DIFFICULTIES.contains(&mission.difficulty.as_str()) {
+        return Err(format!(
+            "Unknown difficulty {} for {}, expected one of {}",
+            mission.difficulty,
+            mission.template,
+            arma::DIFFICULTIES.join(", ")
+        ));
+    }
+
+    for (i, param) in mission.params.iter().enumerate() {
+        let valid = param.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && param.name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+
+        if !valid {
+            return Err(format!("Invalid param name {} for {}", param.name, mission.template));
+        }
+
+        if mission.params[..i]
+            .iter()
+            .any(|other| other.name.eq_ignore_ascii_case(&param.name))
+        {
+            return Err(format!("Param {} is set twice for {}", param.name, mission.template));
+        }
+    }
+
+    Ok(())
+}
@@ -1,19 +1,35 @@
 mod a2s_handler;
+mod announcement_handler;
 mod arma_handler;
+mod ban_handler;
 mod config_handlers;
+mod instance_handler;
+mod key_handler;
 mod logs_handler;
+mod message_handler;
 mod mission_handler;
+mod player_handler;
 mod preset_handler;
+mod session_handler;
 mod status_handler;
 mod steam_handler;
 mod user_handler;
+mod whitelist_handler;
 
 pub use a2s_handler::*;
+pub use announcement_handler::*;
 pub use arma_handler::*;
+pub use ban_handler::*;
 pub use config_handlers::*;
+pub use instance_handler::*;
+pub use key_handler::*;
 pub use logs_handler::*;
+pub use message_handler::*;
 pub use mission_handler::*;
+pub use player_handler::*;
 pub use preset_handler::*;
+pub use session_handler::*;
 pub use status_handler::*;
 pub use steam_handler::*;
 pub use user_handler::*;
+pub use whitelist_handler::*;
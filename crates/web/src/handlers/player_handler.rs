@@ -0,0 +1,296 @@
+use std::sync::Arc;
+
+use api_schema::{
+    request::*,
+    response::{BanKind, RconPlayer, SimpleResponse},
+};
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, Extension, Json};
+
+use super::find_instance;
+use crate::{
+    model::User,
+    repository::{AuditRepository, NewAuditEntry, NewBan},
+    response::{ApiResponse, ApiResult, ErrorResponse},
+    service::{ban_expiry, BanService, InstanceService, ServerInstance, State, StatusService},
+};
+
+/// How many audit entries the players page shows.
+const AUDIT_LIMIT: i64 = 100;
+
+/// The players on the server as RCon lists them, including the ones in the lobby.
+pub async fn get_players(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(status): Extension<Arc<StatusService>>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let server = find_running(&instances, &status, instance).await?;
+
+    let players = server
+        .rcon
+        .players()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("RCon Error: {}", e)))?
+        .into_iter()
+        .map(|player| RconPlayer {
+            slot: player.slot as i64,
+            ip: player.ip,
+            port: player.port,
+            ping: player.ping,
+            guid: player.guid,
+            verified: player.verified,
+            name: player.name,
+            lobby: player.lobby,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(ApiResponse::new(players))
+}
+
+pub async fn kick_player(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(status): Extension<Arc<StatusService>>,
+    Extension(audit_repository): Extension<AuditRepository>,
+    Extension(user): Extension<User>,
+    Path(instance): Path<i64>,
+    Json(input): Json<KickPlayerSchema>,
+) -> ApiResult<impl IntoResponse> {
+    let server = find_running(&instances, &status, instance).await?;
+    let reason = single_line(&input.reason, "Reason")?;
+    let player = find_player(&server, input.slot, &input.guid).await?;
+
+    rcon_command(&server, &format!("kick {} {}", player.slot, reason)).await?;
+
+    audit(
+        &audit_repository,
+        NewAuditEntry {
+            instance_id: instance,
+            user: &user.name,
+            action: "kick",
+            player: Some(&player.name),
+            guid: Some(&player.guid),
+            details: (!reason.is_empty()).then(|| reason.clone()),
+        },
+    )
+    .await?;
+
+    Ok(ApiResponse::new(SimpleResponse {
+        response: "OK".to_string(),
+    }))
+}
+
+/// Bans through BattlEye, which kicks the player right away, and keeps the ban with the others.
+pub async fn ban_player(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(status): Extension<Arc<StatusService>>,
+    Extension(audit_repository): Extension<AuditRepository>,
+    Extension(bans): Extension<Arc<BanService>>,
+    Extension(user): Extension<User>,
+    Path(instance): Path<i64>,
+    Json(input): Json<BanPlayerSchema>,
+) -> ApiResult<impl IntoResponse> {
+    if input.minutes < 0 {
+        return Err(ErrorResponse::new("The ban duration can't be negative")
+            .with_status_code(StatusCode::BAD_REQUEST)
+            .into());
+    }
+
+    let server = find_running(&instances, &status, instance).await?;
+    let reason = single_line(&input.reason, "Reason")?;
+    let player = find_player(&server, input.slot, &input.guid).await?;
+
+    rcon_command(&server, &format!("ban {} {} {}", player.slot, input.minutes, reason)).await?;
+
+    // the other instances get the ban with the next sync
+    if !player.guid.is_empty() {
+        let ban = NewBan {
+            kind: BanKind::Guid,
+            value: player.guid.to_lowercase(),
+            expires_at: ban_expiry(input.minutes),
+            reason: reason.clone(),
+            author: user.name.clone(),
+        };
+
+        bans.add(ban, &instances, &status)
+            .await
+            .map_err(|e| ErrorResponse::new(format!("Unable to keep the ban: {}", e)))?;
+    }
+
+    let duration = match input.minutes {
+        0 => "permanent".to_string(),
+        minutes => format!("{} minutes", minutes),
+    };
+
+    let details = if reason.is_empty() {
+        duration
+    } else {
+        format!("{}: {}", duration, reason)
+    };
+
+    audit(
+        &audit_repository,
+        NewAuditEntry {
+            instance_id: instance,
+            user: &user.name,
+            action: "ban",
+            player: Some(&player.name),
+            guid: Some(&player.guid),
+            details: Some(details),
+        },
+    )
+    .await?;
+
+    Ok(ApiResponse::new(SimpleResponse {
+        response: "OK".to_string(),
+    }))
+}
+
+/// Sends a message to everyone or, with a slot, only to that player.
+pub async fn send_message(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(status): Extension<Arc<StatusService>>,
+    Extension(audit_repository): Extension<AuditRepository>,
+    Extension(user): Extension<User>,
+    Path(instance): Path<i64>,
+    Json(input): Json<SendMessageSchema>,
+) -> ApiResult<impl IntoResponse> {
+    let server = find_running(&instances, &status, instance).await?;
+    let message = single_line(&input.message, "Message")?;
+
+    if message.is_empty() {
+        return Err(ErrorResponse::new("Message is empty")
+            .with_status_code(StatusCode::BAD_REQUEST)
+            .into());
+    }
+
+    let player = match input.slot {
+        Some(slot) => Some(find_player(&server, slot, input.guid.as_deref().unwrap_or_default()).await?),
+        None => None,
+    };
+
+    let target = player.as_ref().map(|player| player.slot as i64).unwrap_or(-1);
+    rcon_command(&server, &format!("say {} {}", target, message)).await?;
+
+    audit(
+        &audit_repository,
+        NewAuditEntry {
+            instance_id: instance,
+            user: &user.name,
+            action: "message",
+            player: player.as_ref().map(|player| player.name.as_str()),
+            guid: player.as_ref().map(|player| player.guid.as_str()),
+            details: Some(message),
+        },
+    )
+    .await?;
+
+    Ok(ApiResponse::new(SimpleResponse {
+        response: "OK".to_string(),
+    }))
+}
+
+/// A locked server lets no new players join, the ones on it stay.
+pub async fn lock_server(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(status): Extension<Arc<StatusService>>,
+    Extension(audit_repository): Extension<AuditRepository>,
+    Extension(user): Extension<User>,
+    Path(instance): Path<i64>,
+    Json(input): Json<LockServerSchema>,
+) -> ApiResult<impl IntoResponse> {
+    let server = find_running(&instances, &status, instance).await?;
+
+    let action = if input.locked { "lock" } else { "unlock" };
+    rcon_command(&server, &format!("#{}", action)).await?;
+
+    audit(
+        &audit_repository,
+        NewAuditEntry {
+            instance_id: instance,
+            user: &user.name,
+            action,
+            player: None,
+            guid: None,
+            details: None,
+        },
+    )
+    .await?;
+
+    Ok(ApiResponse::new(SimpleResponse {
+        response: "OK".to_string(),
+    }))
+}
+
+/// The latest actions taken on the server through the manager, newest first.
+pub async fn get_audit_log(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(audit_repository): Extension<AuditRepository>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    let entries = audit_repository
+        .get_by_instance(instance, AUDIT_LIMIT)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(entries))
+}
+
+/// RCon is only there while the server is running.
+async fn find_running(
+    instances: &InstanceService,
+    status: &StatusService,
+    instance: i64,
+) -> ApiResult<Arc<ServerInstance>> {
+    let server = find_instance(instances, instance).await?;
+
+    if status.arma(instance).await != State::Running {
+        return Err(ErrorResponse::new("The server is not running")
+            .with_status_code(StatusCode::CONFLICT)
+            .into());
+    }
+
+    Ok(server)
+}
+
+/// Slots are reused as soon as a player leaves, the GUID makes sure the action hits the player that was picked.
+async fn find_player(server: &ServerInstance, slot: i64, guid: &str) -> Result<rcon::Player, ErrorResponse> {
+    let players = server
+        .rcon
+        .players()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("RCon Error: {}", e)))?;
+
+    players
+        .into_iter()
+        .find(|player| player.slot as i64 == slot && player.guid.eq_ignore_ascii_case(guid))
+        .ok_or_else(|| {
+            ErrorResponse::new("The player is no longer on the server").with_status_code(StatusCode::CONFLICT)
+        })
+}
+
+async fn rcon_command(server: &ServerInstance, command: &str) -> Result<String, ErrorResponse> {
+    server
+        .rcon
+        .command(command)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("RCon Error: {}", e)))
+}
+
+/// RCon commands are a single line, a line break would cut the command short.
+fn single_line(text: &str, name: &str) -> Result<String, ErrorResponse> {
+    if text.contains(['\r', '\n']) {
+        return Err(
+            ErrorResponse::new(format!("{} must be a single line", name)).with_status_code(StatusCode::BAD_REQUEST)
+        );
+    }
+
+    Ok(text.trim().to_string())
+}
+
+async fn audit(audit_repository: &AuditRepository, entry: NewAuditEntry<'_>) -> Result<(), ErrorResponse> {
+    audit_repository
+        .add(entry)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))
+}
@@ -2,7 +2,9 @@ use std::convert::Infallible;
 use std::sync::Arc;
 
 use api_schema::request::*;
-use api_schema::response::SimpleResponse;
+use api_schema::response::{Preset, SimpleResponse};
+use axum::extract::Path;
+use axum::http::{header, StatusCode};
 use axum::response::sse::{Event, KeepAlive};
 use axum::response::Sse;
 use axum::Json;
@@ -10,8 +12,10 @@ use axum::{response::IntoResponse, Extension};
 use futures::Stream;
 use tokio_stream::wrappers::WatchStream;
 
+use super::get_installed_keys;
+use crate::repository::{KeyRepository, PresetRepository};
 use crate::response::{ApiResponse, ApiResult, ErrorResponse};
-use crate::service::PresetService;
+use crate::service::{AddonService, PresetService};
 
 pub async fn get_presets(Extension(preset_service): Extension<Arc<PresetService>>) -> ApiResult<impl IntoResponse> {
     let presets = preset_service
@@ -60,6 +64,100 @@ pub async fn update_preset_item(
     Ok(ApiResponse::new(item))
 }
 
+/// Proposes a load order for the enabled mods of the preset without changing anything.
+pub async fn get_load_order(
+    Extension(preset_repository): Extension<PresetRepository>,
+    Extension(addons): Extension<Arc<AddonService>>,
+    Path(id): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let preset = find_preset(&preset_repository, id).await?;
+
+    Ok(ApiResponse::new(addons.sort_load_order(&preset).await))
+}
+
+/// Moves every mod of the preset to the position of the proposed load order.
+pub async fn apply_load_order(
+    Extension(preset_service): Extension<Arc<PresetService>>,
+    Extension(preset_repository): Extension<PresetRepository>,
+    Extension(addons): Extension<Arc<AddonService>>,
+    Path(id): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let preset = find_preset(&preset_repository, id).await?;
+    let report = addons.sort_load_order(&preset).await;
+
+    for item in report
+        .order
+        .iter()
+        .filter(|item| item.position != item.previous_position)
+    {
+        preset_service
+            .update_item(UpdatePresetItemSchema {
+                id: item.id,
+                enabled: None,
+                position: Some(item.position),
+                server_mod: None,
+                optional: None,
+            })
+            .await
+            .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+    }
+
+    Ok(ApiResponse::new(report))
+}
+
+/// Lists the mods of the preset whose keys or signatures would get players kicked.
+pub async fn get_key_report(
+    Extension(preset_repository): Extension<PresetRepository>,
+    Extension(key_repository): Extension<KeyRepository>,
+    Path(id): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let preset = find_preset(&preset_repository, id).await?;
+    let managed_keys = get_installed_keys(&key_repository).await?;
+
+    let report = tokio::task::spawn_blocking(move || arma::key_report(&preset, &managed_keys))
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Unable to check keys: {}", e)))?;
+
+    Ok(ApiResponse::new(report))
+}
+
+/// Lists the PBOs, `CfgPatches` and prefixes that more than one enabled mod of the preset brings.
+pub async fn get_conflicts(
+    Extension(preset_repository): Extension<PresetRepository>,
+    Extension(addons): Extension<Arc<AddonService>>,
+    Path(id): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let preset = find_preset(&preset_repository, id).await?;
+
+    Ok(ApiResponse::new(addons.find_conflicts(&preset).await))
+}
+
+/// The preset as an Arma 3 Launcher preset for the players to import.
+pub async fn export_preset(
+    Extension(preset_repository): Extension<PresetRepository>,
+    Path(id): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let preset = find_preset(&preset_repository, id).await?;
+
+    let headers = [
+        (header::CONTENT_TYPE, "text/html; charset=utf-8".to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.html\"", preset.name.replace('"', "")),
+        ),
+    ];
+
+    Ok((headers, arma::launcher_preset(&preset)))
+}
+
+async fn find_preset(preset_repository: &PresetRepository, id: i64) -> Result<Preset, ErrorResponse> {
+    preset_repository
+        .get_preset(id)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?
+        .ok_or_else(|| ErrorResponse::new("Preset not found").with_status_code(StatusCode::NOT_FOUND))
+}
+
 pub async fn update_preset_dlc(
     Extension(preset_service): Extension<Arc<PresetService>>,
     Json(input): Json<UpdatePresetDlcSchema>,
@@ -102,8 +200,22 @@ pub async fn unblacklist_item(
 
 pub async fn delete_preset(
     Extension(preset_service): Extension<Arc<PresetService>>,
+    Extension(preset_repository): Extension<PresetRepository>,
     Json(input): Json<DeletePresetSchema>,
 ) -> ApiResult<impl IntoResponse> {
+    let instances = preset_repository
+        .get_instances_using(input.id)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    if !instances.is_empty() {
+        return Err(
+            ErrorResponse::new(format!("The preset is used by {}", instances.join(", ")))
+                .with_status_code(StatusCode::CONFLICT)
+                .into(),
+        );
+    }
+
     preset_service
         .delete_preset(input)
         .await
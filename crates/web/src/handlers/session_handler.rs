@@ -0,0 +1,90 @@
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use api_schema::request::{SessionQuery, StatsQuery};
+use axum::{
+    extract::{Path, Query},
+    response::IntoResponse,
+    Extension,
+};
+
+use super::find_instance;
+use crate::{
+    repository::SessionRepository,
+    response::{ApiResponse, ApiResult, ErrorResponse},
+    service::InstanceService,
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+const DEFAULT_DAYS: i64 = 30;
+const MAX_DAYS: i64 = 365;
+const DEFAULT_PLAYTIME_LIMIT: i64 = 10;
+
+/// The sessions of an instance, optionally of a single player, newest first.
+pub async fn get_sessions(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(session_repository): Extension<SessionRepository>,
+    Path(instance): Path<i64>,
+    Query(query): Query<SessionQuery>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let sessions = session_repository
+        .search(instance, &query, limit)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(sessions))
+}
+
+/// Unique players and the peak player count per day.
+pub async fn get_daily_stats(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(session_repository): Extension<SessionRepository>,
+    Path(instance): Path<i64>,
+    Query(query): Query<StatsQuery>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    let days = session_repository
+        .daily(instance, since(&query))
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(days))
+}
+
+/// The players with the most playtime.
+pub async fn get_playtime(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(session_repository): Extension<SessionRepository>,
+    Path(instance): Path<i64>,
+    Query(query): Query<StatsQuery>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_PLAYTIME_LIMIT).clamp(1, MAX_LIMIT);
+
+    let players = session_repository
+        .playtime(instance, since(&query), limit)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(players))
+}
+
+fn since(query: &StatsQuery) -> i64 {
+    let days = query.days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS);
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs() as i64)
+        .unwrap_or_default();
+
+    now - days * 24 * 60 * 60
+}
@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use api_schema::{
+    request::*,
+    response::{SimpleResponse, WhitelistKind},
+};
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, Extension, Json};
+
+use super::find_instance;
+use crate::{
+    model::User,
+    repository::{NewWhitelistEntry, WhitelistRepository},
+    response::{ApiResponse, ApiResult, ErrorResponse},
+    service::{parse_whitelist, InstanceService},
+};
+
+pub async fn get_whitelist(
+    Extension(whitelist_repository): Extension<WhitelistRepository>,
+) -> ApiResult<impl IntoResponse> {
+    let entries = whitelist_repository
+        .get_all()
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(entries))
+}
+
+pub async fn create_whitelist_entry(
+    Extension(whitelist_repository): Extension<WhitelistRepository>,
+    Extension(user): Extension<User>,
+    Json(input): Json<CreateWhitelistEntrySchema>,
+) -> ApiResult<impl IntoResponse> {
+    let value = input.value.trim().to_lowercase();
+
+    let (valid, name) = match input.kind {
+        WhitelistKind::Uid => (arma::is_uid(&value), "Steam UID"),
+        WhitelistKind::Guid => (arma::is_guid(&value), "GUID"),
+    };
+
+    if !valid {
+        return Err(ErrorResponse::new(format!("{} is not a valid {}", value, name))
+            .with_status_code(StatusCode::BAD_REQUEST)
+            .into());
+    }
+
+    let entry = NewWhitelistEntry {
+        kind: input.kind,
+        value,
+        name: input.name.trim().to_string(),
+        author: user.name,
+    };
+
+    let entry = whitelist_repository
+        .add(entry)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(entry))
+}
+
+pub async fn delete_whitelist_entry(
+    Extension(whitelist_repository): Extension<WhitelistRepository>,
+    Path(id): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    let deleted = whitelist_repository
+        .delete(id)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    if !deleted {
+        return Err(ErrorResponse::new("Whitelist entry not found")
+            .with_status_code(StatusCode::NOT_FOUND)
+            .into());
+    }
+
+    Ok(ApiResponse::new(SimpleResponse {
+        response: "OK".to_string(),
+    }))
+}
+
+/// Adds the players of a CSV, players that are already on the whitelist keep their name.
+pub async fn import_whitelist(
+    Extension(whitelist_repository): Extension<WhitelistRepository>,
+    Extension(user): Extension<User>,
+    Json(input): Json<ImportWhitelistSchema>,
+) -> ApiResult<impl IntoResponse> {
+    let (entries, skipped) = parse_whitelist(&input.content);
+
+    if entries.is_empty() {
+        return Err(ErrorResponse::new("No Steam UIDs or GUIDs found")
+            .with_status_code(StatusCode::BAD_REQUEST)
+            .into());
+    }
+
+    let found = entries.len();
+
+    let entries = entries
+        .into_iter()
+        .map(|(kind, value, name)| NewWhitelistEntry {
+            kind,
+            value,
+            name,
+            author: user.name.clone(),
+        })
+        .collect();
+
+    let added = whitelist_repository
+        .add_missing(entries)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    let mut response = format!("Imported {} of {} players", added, found);
+
+    if skipped > 0 {
+        response.push_str(&format!(", skipped {} lines without a Steam UID or GUID", skipped));
+    }
+
+    Ok(ApiResponse::new(SimpleResponse { response }))
+}
+
+pub async fn get_whitelist_settings(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(whitelist_repository): Extension<WhitelistRepository>,
+    Path(instance): Path<i64>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    let settings = whitelist_repository
+        .get_settings(instance)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(settings))
+}
+
+/// Applies to players joining from now on, the ones on the server stay.
+pub async fn update_whitelist_settings(
+    Extension(instances): Extension<Arc<InstanceService>>,
+    Extension(whitelist_repository): Extension<WhitelistRepository>,
+    Path(instance): Path<i64>,
+    Json(input): Json<UpdateWhitelistSettingsSchema>,
+) -> ApiResult<impl IntoResponse> {
+    find_instance(&instances, instance).await?;
+
+    // the message is sent with `kick`, which takes a single line
+    if input.message.contains(['\r', '\n']) {
+        return Err(ErrorResponse::new("Message must be a single line")
+            .with_status_code(StatusCode::BAD_REQUEST)
+            .into());
+    }
+
+    let message = input.message.trim();
+
+    if message.is_empty() {
+        return Err(ErrorResponse::new("Message is empty")
+            .with_status_code(StatusCode::BAD_REQUEST)
+            .into());
+    }
+
+    whitelist_repository
+        .set_settings(instance, input.enabled, message)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    Ok(ApiResponse::new(SimpleResponse {
+        response: "OK".to_string(),
+    }))
+}
+
+pub async fn update_preset_whitelist(
+    Extension(whitelist_repository): Extension<WhitelistRepository>,
+    Path(id): Path<i64>,
+    Json(input): Json<PresetWhitelistSchema>,
+) -> ApiResult<impl IntoResponse> {
+    let updated = whitelist_repository
+        .set_preset(id, input.enabled)
+        .await
+        .map_err(|e| ErrorResponse::new(format!("Database Error: {}", e)))?;
+
+    if !updated {
+        return Err(ErrorResponse::new("Preset not found")
+            .with_status_code(StatusCode::NOT_FOUND)
+            .into());
+    }
+
+    Ok(ApiResponse::new(SimpleResponse {
+        response: "OK".to_string(),
+    }))
+}
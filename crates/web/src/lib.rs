@@ -5,7 +5,11 @@ use axum::{
     Extension, Router,
 };
 pub use config::*;
-use repository::{PresetRepository, UserRepository, UserTokenRepository};
+use repository::{
+    AnnouncementRepository, AuditRepository, BanRepository, InstanceRepository, KeyRepository, MessageRepository,
+    MissionRepository, MissionRotationRepository, PresetRepository, RestartScheduleRepository, SessionRepository,
+    UserRepository, UserTokenRepository, WhitelistRepository,
+};
 use route::create_router;
 pub use service::*;
 use sqlx::sqlite::SqlitePoolOptions;
@@ -25,17 +29,57 @@ pub async fn start() {
     let user_repository = UserRepository::new(pool.clone());
     let user_token_repository = UserTokenRepository::new(pool.clone());
     let preset_repository = PresetRepository::new(pool.clone());
-
-    let config_service = ConfigService::new();
-    let status = StatusService::new();
+    let instance_repository = InstanceRepository::new(pool.clone());
+    let key_repository = KeyRepository::new(pool.clone());
+    let mission_repository = MissionRepository::new(pool.clone());
+    let mission_rotation_repository = MissionRotationRepository::new(pool.clone());
+    let audit_repository = AuditRepository::new(pool.clone());
+    let ban_repository = BanRepository::new(pool.clone());
+    let message_repository = MessageRepository::new(pool.clone());
+    let announcement_repository = AnnouncementRepository::new(pool.clone());
+    let whitelist_repository = WhitelistRepository::new(pool.clone());
+    let session_repository = SessionRepository::new(pool.clone());
+    let restart_schedule_repository = RestartScheduleRepository::new(pool.clone());
+
+    let status = StatusService::new(&instance_repository.get_all().await.expect("Failed to load instances."));
+    let instances = InstanceService::new(instance_repository, status.clone())
+        .await
+        .expect("Failed to load instances.");
     let preset = PresetService::new(preset_repository.clone());
     let log = LogService::new();
-    let a2s = A2sService::new();
+    let addons = AddonService::new();
+    let bans = BanService::new(ban_repository.clone());
+
+    // bans added on the servers while the manager was down are taken over before anything is written
+    if let Err(e) = bans.sync(&instances, &status).await {
+        tracing::error!("Failed to sync the bans: {}", e);
+    }
+
+    let servers = ServerService::new(
+        status.clone(),
+        instances.clone(),
+        preset_repository.clone(),
+        mission_rotation_repository.clone(),
+        key_repository.clone(),
+        bans.clone(),
+    );
+
+    let restarts = RestartService::new(restart_schedule_repository.clone(), servers.clone());
+    restarts.start(status.clone());
+
+    let messages = MessageService::new(message_repository.clone());
+    messages.start(instances.clone(), status.clone());
+
+    let whitelist = WhitelistService::new(whitelist_repository.clone(), audit_repository.clone());
+    whitelist.start(instances.clone(), &messages);
+
+    let announcements = AnnouncementService::new(announcement_repository.clone(), restart_schedule_repository.clone());
+    announcements.start(instances.clone(), status.clone());
 
-    a2s.start();
+    let sessions = SessionService::new(session_repository.clone());
+    sessions.start(instances.clone(), status.clone());
 
     log.register("steamcmd", paths::get_log_path().join("steamcmd.log"));
-    log.register("arma", paths::get_arma_log_path().join("*.rpt"));
 
     let app_state = AppState {
         db: pool,
@@ -52,11 +96,24 @@ pub async fn start() {
         .layer(Extension(user_repository))
         .layer(Extension(user_token_repository))
         .layer(Extension(preset_repository))
-        .layer(Extension(config_service))
+        .layer(Extension(key_repository))
+        .layer(Extension(mission_repository))
+        .layer(Extension(mission_rotation_repository))
+        .layer(Extension(audit_repository))
+        .layer(Extension(ban_repository))
+        .layer(Extension(message_repository))
+        .layer(Extension(announcement_repository))
+        .layer(Extension(whitelist_repository))
+        .layer(Extension(session_repository))
+        .layer(Extension(restart_schedule_repository))
+        .layer(Extension(instances))
         .layer(Extension(status))
         .layer(Extension(preset))
         .layer(Extension(log))
-        .layer(Extension(a2s))
+        .layer(Extension(addons))
+        .layer(Extension(bans))
+        .layer(Extension(servers))
+        .layer(Extension(messages))
         .layer(cors);
 
     let dashboard = dashboard::get_router();
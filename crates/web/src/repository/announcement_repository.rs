@@ -0,0 +1,161 @@
+use api_schema::{
+    request::AnnouncementSchema,
+    response::{Announcement, AnnouncementSchedule},
+};
+use sqlx::SqlitePool;
+
+use super::RepositoryResult;
+
+#[derive(Clone)]
+pub struct AnnouncementRepository {
+    pool: SqlitePool,
+}
+
+impl AnnouncementRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl AnnouncementRepository {
+    pub async fn get_by_instance(&self, instance_id: i64) -> RepositoryResult<Vec<Announcement>> {
+        let announcements: Vec<SqlAnnouncement> = sqlx::query_as(
+            r#"
+            SELECT id, instance_id, message, interval_minutes, send_at, before_restart_minutes, enabled,
+                CAST(strftime('%s', created_at) AS INTEGER) AS created_at
+            FROM announcements
+            WHERE instance_id = ?
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(instance_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(announcements.into_iter().map(Into::into).collect())
+    }
+
+    /// The announcements of all instances that may still go out.
+    pub async fn get_enabled(&self) -> RepositoryResult<Vec<Announcement>> {
+        let announcements: Vec<SqlAnnouncement> = sqlx::query_as(
+            r#"
+            SELECT id, instance_id, message, interval_minutes, send_at, before_restart_minutes, enabled,
+                CAST(strftime('%s', created_at) AS INTEGER) AS created_at
+            FROM announcements
+            WHERE enabled = 1
+            ORDER BY id ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(announcements.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn add(&self, instance_id: i64, announcement: &AnnouncementSchema) -> RepositoryResult<Announcement> {
+        let (interval_minutes, send_at, before_restart_minutes) = schedule_columns(announcement.schedule);
+
+        let announcement: SqlAnnouncement = sqlx::query_as(
+            r#"
+            INSERT INTO announcements (instance_id, message, interval_minutes, send_at, before_restart_minutes, enabled)
+            VALUES (?, ?, ?, ?, ?, ?)
+            RETURNING id, instance_id, message, interval_minutes, send_at, before_restart_minutes, enabled,
+                CAST(strftime('%s', created_at) AS INTEGER) AS created_at
+            "#,
+        )
+        .bind(instance_id)
+        .bind(&announcement.message)
+        .bind(interval_minutes)
+        .bind(send_at)
+        .bind(before_restart_minutes)
+        .bind(announcement.enabled)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(announcement.into())
+    }
+
+    /// Returns `None` if the instance has no such announcement.
+    pub async fn update(
+        &self,
+        instance_id: i64,
+        id: i64,
+        announcement: &AnnouncementSchema,
+    ) -> RepositoryResult<Option<Announcement>> {
+        let (interval_minutes, send_at, before_restart_minutes) = schedule_columns(announcement.schedule);
+
+        let announcement: Option<SqlAnnouncement> = sqlx::query_as(
+            r#"
+            UPDATE announcements
+            SET message = ?, interval_minutes = ?, send_at = ?, before_restart_minutes = ?, enabled = ?
+            WHERE id = ? AND instance_id = ?
+            RETURNING id, instance_id, message, interval_minutes, send_at, before_restart_minutes, enabled,
+                CAST(strftime('%s', created_at) AS INTEGER) AS created_at
+            "#,
+        )
+        .bind(&announcement.message)
+        .bind(interval_minutes)
+        .bind(send_at)
+        .bind(before_restart_minutes)
+        .bind(announcement.enabled)
+        .bind(id)
+        .bind(instance_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(announcement.map(Into::into))
+    }
+
+    /// Returns whether there was an announcement to delete.
+    pub async fn delete(&self, instance_id: i64, id: i64) -> RepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM announcements WHERE id = ? AND instance_id = ?")
+            .bind(id)
+            .bind(instance_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Only the column of the kind of schedule is set.
+fn schedule_columns(schedule: AnnouncementSchedule) -> (Option<i64>, Option<i64>, Option<i64>) {
+    match schedule {
+        AnnouncementSchedule::Interval { minutes } => (Some(minutes), None, None),
+        AnnouncementSchedule::At { timestamp } => (None, Some(timestamp), None),
+        AnnouncementSchedule::BeforeRestart { minutes } => (None, None, Some(minutes)),
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SqlAnnouncement {
+    id: i64,
+    instance_id: i64,
+    message: String,
+    interval_minutes: Option<i64>,
+    send_at: Option<i64>,
+    before_restart_minutes: Option<i64>,
+    enabled: bool,
+    created_at: Option<i64>,
+}
+
+impl From<SqlAnnouncement> for Announcement {
+    fn from(announcement: SqlAnnouncement) -> Self {
+        let schedule = match (announcement.interval_minutes, announcement.before_restart_minutes) {
+            (Some(minutes), _) => AnnouncementSchedule::Interval { minutes },
+            (None, Some(minutes)) => AnnouncementSchedule::BeforeRestart { minutes },
+            (None, None) => AnnouncementSchedule::At {
+                timestamp: announcement.send_at.unwrap_or_default(),
+            },
+        };
+
+        Self {
+            id: announcement.id,
+            instance_id: announcement.instance_id,
+            message: announcement.message,
+            schedule,
+            enabled: announcement.enabled,
+            created_at: announcement.created_at.unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use api_schema::response::AuditEntry;
+use sqlx::SqlitePool;
+
+use super::RepositoryResult;
+
+/// Who did what to which player, kept even after the user is deleted.
+#[derive(Clone)]
+pub struct AuditRepository {
+    pool: SqlitePool,
+}
+
+impl AuditRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+/// An entry that is about to be written, the player is left out for actions on the whole server.
+pub struct NewAuditEntry<'a> {
+    pub instance_id: i64,
+    pub user: &'a str,
+    pub action: &'a str,
+    pub player: Option<&'a str>,
+    pub guid: Option<&'a str>,
+    pub details: Option<String>,
+}
+
+impl AuditRepository {
+    /// The latest entries of an instance, newest first.
+    pub async fn get_by_instance(&self, instance_id: i64, limit: i64) -> RepositoryResult<Vec<AuditEntry>> {
+        let entries: Vec<SqlAuditEntry> = sqlx::query_as(
+            r#"
+            SELECT id, instance_id, user, action, player, guid, details,
+                CAST(strftime('%s', created_at) AS INTEGER) AS created_at
+            FROM audit_log
+            WHERE instance_id = ?
+            ORDER BY id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(instance_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn add(&self, entry: NewAuditEntry<'_>) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log (instance_id, user, action, player, guid, details)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(entry.instance_id)
+        .bind(entry.user)
+        .bind(entry.action)
+        .bind(entry.player)
+        .bind(entry.guid)
+        .bind(entry.details)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SqlAuditEntry {
+    id: i64,
+    instance_id: i64,
+    user: String,
+    action: String,
+    player: Option<String>,
+    guid: Option<String>,
+    details: Option<String>,
+    created_at: Option<i64>,
+}
+
+impl From<SqlAuditEntry> for AuditEntry {
+    fn from(entry: SqlAuditEntry) -> Self {
+        Self {
+            id: entry.id,
+            instance_id: entry.instance_id,
+            user: entry.user,
+            action: entry.action,
+            player: entry.player,
+            guid: entry.guid,
+            details: entry.details,
+            created_at: entry.created_at.unwrap_or_default(),
+        }
+    }
+}
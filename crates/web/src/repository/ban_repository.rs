@@ -0,0 +1,157 @@
+use api_schema::response::{Ban, BanKind};
+use sqlx::SqlitePool;
+
+use super::RepositoryResult;
+
+/// The bans of all instances, the ban files on disk are written from this.
+#[derive(Clone)]
+pub struct BanRepository {
+    pool: SqlitePool,
+}
+
+impl BanRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+pub struct NewBan {
+    pub kind: BanKind,
+    pub value: String,
+    pub expires_at: Option<i64>,
+    pub reason: String,
+    pub author: String,
+}
+
+impl BanRepository {
+    pub async fn get_all(&self) -> RepositoryResult<Vec<Ban>> {
+        let bans: Vec<SqlBan> = sqlx::query_as(
+            r#"
+            SELECT id, kind, value, expires_at, reason, author,
+                CAST(strftime('%s', created_at) AS INTEGER) AS created_at
+            FROM bans
+            ORDER BY id DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(bans.into_iter().map(Into::into).collect())
+    }
+
+    /// The bans that didn't expire yet, oldest first so the files keep their order.
+    pub async fn get_active(&self) -> RepositoryResult<Vec<Ban>> {
+        let bans: Vec<SqlBan> = sqlx::query_as(
+            r#"
+            SELECT id, kind, value, expires_at, reason, author,
+                CAST(strftime('%s', created_at) AS INTEGER) AS created_at
+            FROM bans
+            WHERE expires_at IS NULL OR expires_at > CAST(strftime('%s', 'now') AS INTEGER)
+            ORDER BY id ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(bans.into_iter().map(Into::into).collect())
+    }
+
+    /// Banning someone who is already banned replaces the old ban.
+    pub async fn add(&self, ban: NewBan) -> RepositoryResult<Ban> {
+        let ban: SqlBan = sqlx::query_as(
+            r#"
+            INSERT INTO bans (kind, value, expires_at, reason, author)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(kind, value) DO UPDATE SET
+                expires_at = excluded.expires_at,
+                reason = excluded.reason,
+                author = excluded.author,
+                created_at = CURRENT_TIMESTAMP
+            RETURNING id, kind, value, expires_at, reason, author,
+                CAST(strftime('%s', created_at) AS INTEGER) AS created_at
+            "#,
+        )
+        .bind(kind_to_str(ban.kind))
+        .bind(ban.value)
+        .bind(ban.expires_at)
+        .bind(ban.reason)
+        .bind(ban.author)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ban.into())
+    }
+
+    /// Adds the bans that aren't there yet and leaves existing ones alone, returns how many were added.
+    pub async fn add_missing(&self, bans: Vec<NewBan>) -> RepositoryResult<u64> {
+        let mut added = 0;
+
+        for ban in bans {
+            added += sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO bans (kind, value, expires_at, reason, author)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(kind_to_str(ban.kind))
+            .bind(ban.value)
+            .bind(ban.expires_at)
+            .bind(ban.reason)
+            .bind(ban.author)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        }
+
+        Ok(added)
+    }
+
+    /// Returns whether there was a ban to delete.
+    pub async fn delete(&self, id: i64) -> RepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM bans WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn kind_to_str(kind: BanKind) -> &'static str {
+    match kind {
+        BanKind::Uid => "uid",
+        BanKind::Guid => "guid",
+        BanKind::Ip => "ip",
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SqlBan {
+    id: i64,
+    kind: String,
+    value: String,
+    expires_at: Option<i64>,
+    reason: String,
+    author: String,
+    created_at: Option<i64>,
+}
+
+impl From<SqlBan> for Ban {
+    fn from(ban: SqlBan) -> Self {
+        let kind = match ban.kind.as_str() {
+            "uid" => BanKind::Uid,
+            "ip" => BanKind::Ip,
+            _ => BanKind::Guid,
+        };
+
+        Self {
+            id: ban.id,
+            kind,
+            value: ban.value,
+            expires_at: ban.expires_at,
+            reason: ban.reason,
+            author: ban.author,
+            created_at: ban.created_at.unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use api_schema::{request::*, response::Instance};
+use rand_core::{OsRng, RngCore};
+use sqlx::SqlitePool;
+
+use super::RepositoryResult;
+
+#[derive(Clone)]
+pub struct InstanceRepository {
+    pool: SqlitePool,
+}
+
+impl InstanceRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl InstanceRepository {
+    pub async fn get_all(&self) -> RepositoryResult<Vec<Instance>> {
+        let instances: Vec<SqlInstance> = sqlx::query_as(
+            r#"
+            SELECT id, name, port, preset_id, config_file, profile_file, profile_name, parameters, headless_clients,
+                rcon_port, rcon_ip, max_ping, rcon_password, query_port, query_ip
+            FROM instances
+            ORDER BY id ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(instances.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn get(&self, id: i64) -> RepositoryResult<Option<Instance>> {
+        let instance: Option<SqlInstance> = sqlx::query_as(
+            r#"
+            SELECT id, name, port, preset_id, config_file, profile_file, profile_name, parameters, headless_clients,
+                rcon_port, rcon_ip, max_ping, rcon_password, query_port, query_ip
+            FROM instances
+            WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(instance.map(Into::into))
+    }
+
+    pub async fn create(&self, input: CreateInstanceSchema) -> RepositoryResult<Instance> {
+        let profile_name = input.profile_name.unwrap_or_else(|| input.name.clone());
+
+        let mut tx = self.pool.begin().await?;
+
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO instances (name, port, preset_id, config_file, profile_file, profile_name, parameters, headless_clients,
+                rcon_port, rcon_ip, max_ping, rcon_password, query_port, query_ip)
+            VALUES (?, ?, ?, '', '', ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING id
+            "#,
+        )
+        .bind(input.name)
+        .bind(input.port)
+        .bind(input.preset_id)
+        .bind(profile_name)
+        .bind(input.parameters.join("\n"))
+        .bind(input.headless_clients)
+        .bind(input.rcon_port)
+        .bind(input.rcon_ip)
+        .bind(input.max_ping)
+        .bind(generate_rcon_password())
+        .bind(input.query_port)
+        .bind(input.query_ip)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // every new instance gets its own folder for config files, named by id so renaming can't mix them up
+        let instance: SqlInstance = sqlx::query_as(
+            r#"
+            UPDATE instances
+            SET config_file = ?, profile_file = ?
+            WHERE id = ?
+            RETURNING id, name, port, preset_id, config_file, profile_file, profile_name, parameters, headless_clients,
+                rcon_port, rcon_ip, max_ping, rcon_password, query_port, query_ip
+            "#,
+        )
+        .bind(format!("{}/server.cfg", id))
+        .bind(format!("{}/profile.cfg", id))
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(instance.into())
+    }
+
+    pub async fn update(&self, input: UpdateInstanceSchema) -> RepositoryResult<Instance> {
+        let instance: SqlInstance = sqlx::query_as(
+            r#"
+            UPDATE instances
+            SET name = ?, port = ?, preset_id = ?, profile_name = ?, parameters = ?, headless_clients = ?,
+                rcon_port = ?, rcon_ip = ?, max_ping = ?, query_port = ?, query_ip = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            RETURNING id, name, port, preset_id, config_file, profile_file, profile_name, parameters, headless_clients,
+                rcon_port, rcon_ip, max_ping, rcon_password, query_port, query_ip
+            "#,
+        )
+        .bind(input.name)
+        .bind(input.port)
+        .bind(input.preset_id)
+        .bind(input.profile_name)
+        .bind(input.parameters.join("\n"))
+        .bind(input.headless_clients)
+        .bind(input.rcon_port)
+        .bind(input.rcon_ip)
+        .bind(input.max_ping)
+        .bind(input.query_port)
+        .bind(input.query_ip)
+        .bind(input.id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(instance.into())
+    }
+
+    pub async fn delete(&self, id: i64) -> RepositoryResult<()> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM instances")
+            .fetch_one(&self.pool)
+            .await?;
+
+        if count <= 1 {
+            return Err(anyhow::anyhow!("Cannot delete the last instance").into());
+        }
+
+        sqlx::query("DELETE FROM instances WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Only the manager and BattlEye ever see it, so it doesn't have to be memorable.
+fn generate_rcon_password() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[derive(sqlx::FromRow)]
+struct SqlInstance {
+    id: i64,
+    name: String,
+    port: i64,
+    preset_id: Option<i64>,
+    config_file: String,
+    profile_file: String,
+    profile_name: String,
+    parameters: String,
+    headless_clients: i64,
+    rcon_port: Option<i64>,
+    rcon_ip: Option<String>,
+    max_ping: Option<i64>,
+    rcon_password: String,
+    query_port: Option<i64>,
+    query_ip: Option<String>,
+}
+
+impl From<SqlInstance> for Instance {
+    fn from(instance: SqlInstance) -> Self {
+        Self {
+            id: instance.id,
+            name: instance.name,
+            port: instance.port,
+            preset_id: instance.preset_id,
+            config_file: instance.config_file,
+            profile_file: instance.profile_file,
+            profile_name: instance.profile_name,
+            parameters: instance
+                .parameters
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.to_string())
+                .collect(),
+            headless_clients: instance.headless_clients,
+            rcon_port: instance.rcon_port,
+            rcon_ip: instance.rcon_ip,
+            max_ping: instance.max_ping,
+            rcon_password: instance.rcon_password,
+            query_port: instance.query_port,
+            query_ip: instance.query_ip,
+        }
+    }
+}
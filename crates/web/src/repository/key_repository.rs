@@ -0,0 +1,126 @@
+use api_schema::response::{KeyOrigin, ServerKey};
+use arma::InstalledKey;
+use sqlx::SqlitePool;
+
+use super::RepositoryResult;
+
+/// Keeps track of the keys the manager put into the `keys` folder of the server,
+/// everything not in here is left alone when the keys are installed.
+#[derive(Clone)]
+pub struct KeyRepository {
+    pool: SqlitePool,
+}
+
+impl KeyRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl KeyRepository {
+    pub async fn get_all(&self) -> RepositoryResult<Vec<ServerKey>> {
+        let keys: Vec<SqlKey> = sqlx::query_as(
+            r#"
+            SELECT name, origin, published_file_id, dlc
+            FROM keys
+            ORDER BY name COLLATE NOCASE ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(keys.into_iter().map(Into::into).collect())
+    }
+
+    /// The names of the keys copied from mods and DLCs, these are replaced every time the keys are installed.
+    pub async fn get_installed(&self) -> RepositoryResult<Vec<String>> {
+        let names: Vec<(String,)> = sqlx::query_as("SELECT name FROM keys WHERE origin IN ('mod', 'dlc')")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(names.into_iter().map(|(name,)| name).collect())
+    }
+
+    /// Replaces the keys copied from mods and DLCs with the ones `arma::install_keys` just copied.
+    /// Uploaded keys are kept even if a mod brings an identical one.
+    pub async fn set_installed(&self, keys: &[InstalledKey]) -> RepositoryResult<()> {
+        sqlx::query("DELETE FROM keys WHERE origin IN ('mod', 'dlc')")
+            .execute(&self.pool)
+            .await?;
+
+        for key in keys {
+            let origin = match key.dlc {
+                Some(_) => "dlc",
+                None => "mod",
+            };
+
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO keys (name, origin, published_file_id, dlc)
+                VALUES (?, ?, ?, ?)
+                "#,
+            )
+            .bind(&key.name)
+            .bind(origin)
+            .bind(key.published_file_id)
+            .bind(&key.dlc)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn add_manual(&self, name: &str) -> RepositoryResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO keys (name, origin)
+            VALUES (?, 'manual')
+            ON CONFLICT(name) DO UPDATE SET
+                origin = 'manual',
+                published_file_id = NULL,
+                dlc = NULL
+            "#,
+        )
+        .bind(name)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, name: &str) -> RepositoryResult<()> {
+        sqlx::query("DELETE FROM keys WHERE name = ?")
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SqlKey {
+    name: String,
+    origin: String,
+    published_file_id: Option<i64>,
+    dlc: Option<String>,
+}
+
+impl From<SqlKey> for ServerKey {
+    fn from(key: SqlKey) -> Self {
+        let origin = match key.origin.as_str() {
+            "mod" => KeyOrigin::Mod,
+            "dlc" => KeyOrigin::Dlc,
+            _ => KeyOrigin::Manual,
+        };
+
+        Self {
+            name: key.name,
+            authority: None,
+            origin,
+            published_file_id: key.published_file_id,
+            dlc: key.dlc,
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use api_schema::{
+    request::MessageQuery,
+    response::{MessageKind, ServerMessage},
+};
+use sqlx::SqlitePool;
+
+use super::RepositoryResult;
+
+/// Everything the servers pushed over RCon, kept until the instance is deleted.
+#[derive(Clone)]
+pub struct MessageRepository {
+    pool: SqlitePool,
+}
+
+impl MessageRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl MessageRepository {
+    /// Newest first, the search matches anywhere in the message, which includes the player names.
+    pub async fn search(
+        &self,
+        instance_id: i64,
+        query: &MessageQuery,
+        limit: i64,
+    ) -> RepositoryResult<Vec<ServerMessage>> {
+        let search = query
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|search| !search.is_empty());
+        let kind = query.kind.map(kind_to_str);
+
+        let messages: Vec<SqlMessage> = sqlx::query_as(
+            r#"
+            SELECT id, instance_id, kind, channel, slot, player, guid, message,
+                CAST(strftime('%s', created_at) AS INTEGER) AS created_at
+            FROM server_messages
+            WHERE instance_id = ?
+                AND (? IS NULL OR instr(lower(message), lower(?)) > 0)
+                AND (? IS NULL OR kind = ?)
+                AND (? IS NULL OR id < ?)
+            ORDER BY id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(instance_id)
+        .bind(search)
+        .bind(search)
+        .bind(kind)
+        .bind(kind)
+        .bind(query.before)
+        .bind(query.before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(messages.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn add(&self, instance_id: i64, message: &rcon::Message) -> RepositoryResult<ServerMessage> {
+        let kind = match message.kind {
+            rcon::MessageKind::Chat => MessageKind::Chat,
+            rcon::MessageKind::Connect => MessageKind::Connect,
+            rcon::MessageKind::Disconnect => MessageKind::Disconnect,
+            rcon::MessageKind::Kick => MessageKind::Kick,
+            rcon::MessageKind::Filter => MessageKind::Filter,
+            rcon::MessageKind::Admin => MessageKind::Admin,
+            rcon::MessageKind::Other => MessageKind::Other,
+        };
+
+        let message: SqlMessage = sqlx::query_as(
+            r#"
+            INSERT INTO server_messages (instance_id, kind, channel, slot, player, guid, message)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            RETURNING id, instance_id, kind, channel, slot, player, guid, message,
+                CAST(strftime('%s', created_at) AS INTEGER) AS created_at
+            "#,
+        )
+        .bind(instance_id)
+        .bind(kind_to_str(kind))
+        .bind(&message.channel)
+        .bind(message.slot.map(|slot| slot as i64))
+        .bind(&message.player)
+        .bind(&message.guid)
+        .bind(&message.text)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(message.into())
+    }
+}
+
+fn kind_to_str(kind: MessageKind) -> &'static str {
+    match kind {
+        MessageKind::Chat => "chat",
+        MessageKind::Connect => "connect",
+        MessageKind::Disconnect => "disconnect",
+        MessageKind::Kick => "kick",
+        MessageKind::Filter => "filter",
+        MessageKind::Admin => "admin",
+        MessageKind::Other => "other",
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SqlMessage {
+    id: i64,
+    instance_id: i64,
+    kind: String,
+    channel: Option<String>,
+    slot: Option<i64>,
+    player: Option<String>,
+    guid: Option<String>,
+    message: String,
+    created_at: Option<i64>,
+}
+
+impl From<SqlMessage> for ServerMessage {
+    fn from(message: SqlMessage) -> Self {
+        let kind = match message.kind.as_str() {
+            "chat" => MessageKind::Chat,
+            "connect" => MessageKind::Connect,
+            "disconnect" => MessageKind::Disconnect,
+            "kick" => MessageKind::Kick,
+            "filter" => MessageKind::Filter,
+            "admin" => MessageKind::Admin,
+            _ => MessageKind::Other,
+        };
+
+        Self {
+            id: message.id,
+            instance_id: message.instance_id,
+            kind,
+            channel: message.channel,
+            slot: message.slot,
+            player: message.player,
+            guid: message.guid,
+            message: message.message,
+            created_at: message.created_at.unwrap_or_default(),
+        }
+    }
+}
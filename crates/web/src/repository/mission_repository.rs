@@ -0,0 +1,191 @@
+use api_schema::response::Mission;
+use arma::MissionMetadata;
+use sqlx::SqlitePool;
+
+use super::RepositoryResult;
+
+#[derive(Clone)]
+pub struct MissionRepository {
+    pool: SqlitePool,
+}
+
+impl MissionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl MissionRepository {
+    pub async fn get_all(&self) -> RepositoryResult<Vec<Mission>> {
+        let missions: Vec<SqlMission> = sqlx::query_as(
+            r#"
+            SELECT id, name, world, title, author, min_players, max_players, game_type, addons, size, version
+            FROM missions
+            ORDER BY name COLLATE NOCASE ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(missions.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn get_by_id(&self, id: i64) -> RepositoryResult<Option<Mission>> {
+        let mission: Option<SqlMission> = sqlx::query_as(
+            r#"
+            SELECT id, name, world, title, author, min_players, max_players, game_type, addons, size, version
+            FROM missions
+            WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(mission.map(Into::into))
+    }
+
+    /// Names are matched case insensitive, the server doesn't tell missions apart by case either.
+    pub async fn get_by_name(&self, name: &str) -> RepositoryResult<Option<Mission>> {
+        let mission: Option<SqlMission> = sqlx::query_as(
+            r#"
+            SELECT id, name, world, title, author, min_players, max_players, game_type, addons, size, version
+            FROM missions
+            WHERE name = ? COLLATE NOCASE
+            "#,
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(mission.map(Into::into))
+    }
+
+    /// Stores the metadata of a mission, replacing what was known about a mission with the same name.
+    pub async fn upsert(&self, metadata: &MissionMetadata, size: i64) -> RepositoryResult<Mission> {
+        sqlx::query(
+            r#"
+            INSERT INTO missions (name, world, title, author, min_players, max_players, game_type, addons, size)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(name) DO UPDATE SET
+                world = excluded.world,
+                title = excluded.title,
+                author = excluded.author,
+                min_players = excluded.min_players,
+                max_players = excluded.max_players,
+                game_type = excluded.game_type,
+                addons = excluded.addons,
+                size = excluded.size,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(&metadata.name)
+        .bind(&metadata.world)
+        .bind(&metadata.title)
+        .bind(&metadata.author)
+        .bind(metadata.min_players)
+        .bind(metadata.max_players)
+        .bind(&metadata.game_type)
+        .bind(metadata.addons.join("\n"))
+        .bind(size)
+        .execute(&self.pool)
+        .await?;
+
+        let mission = self
+            .get_by_name(&metadata.name)
+            .await?
+            .ok_or("Mission was not stored")?;
+
+        Ok(mission)
+    }
+
+    /// Stores the metadata of a new version of a mission, the name stays the same.
+    pub async fn replace(&self, id: i64, metadata: &MissionMetadata, size: i64) -> RepositoryResult<Mission> {
+        sqlx::query(
+            r#"
+            UPDATE missions SET
+                world = ?,
+                title = ?,
+                author = ?,
+                min_players = ?,
+                max_players = ?,
+                game_type = ?,
+                addons = ?,
+                size = ?,
+                version = version + 1,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+        )
+        .bind(&metadata.world)
+        .bind(&metadata.title)
+        .bind(&metadata.author)
+        .bind(metadata.min_players)
+        .bind(metadata.max_players)
+        .bind(&metadata.game_type)
+        .bind(metadata.addons.join("\n"))
+        .bind(size)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        let mission = self.get_by_id(id).await?.ok_or("Mission was not stored")?;
+
+        Ok(mission)
+    }
+
+    pub async fn rename(&self, id: i64, name: &str, world: &str) -> RepositoryResult<Mission> {
+        sqlx::query("UPDATE missions SET name = ?, world = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(name)
+            .bind(world)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        let mission = self.get_by_id(id).await?.ok_or("Mission was not stored")?;
+
+        Ok(mission)
+    }
+
+    pub async fn delete(&self, id: i64) -> RepositoryResult<()> {
+        sqlx::query("DELETE FROM missions WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SqlMission {
+    id: i64,
+    name: String,
+    world: String,
+    title: Option<String>,
+    author: Option<String>,
+    min_players: Option<i64>,
+    max_players: Option<i64>,
+    game_type: Option<String>,
+    addons: String,
+    size: i64,
+    version: i64,
+}
+
+impl From<SqlMission> for Mission {
+    fn from(mission: SqlMission) -> Self {
+        Self {
+            id: mission.id,
+            name: mission.name,
+            world: mission.world,
+            title: mission.title,
+            author: mission.author,
+            min_players: mission.min_players,
+            max_players: mission.max_players,
+            game_type: mission.game_type,
+            addons: mission.addons.lines().map(|addon| addon.to_string()).collect(),
+            size: mission.size,
+            version: mission.version,
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use api_schema::response::{MissionParam, RotationMission};
+use sqlx::SqlitePool;
+
+use super::RepositoryResult;
+
+#[derive(Clone)]
+pub struct MissionRotationRepository {
+    pool: SqlitePool,
+}
+
+impl MissionRotationRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl MissionRotationRepository {
+    pub async fn get(&self, instance_id: i64) -> RepositoryResult<Vec<RotationMission>> {
+        let missions: Vec<SqlRotationMission> = sqlx::query_as(
+            r#"
+            SELECT template, difficulty, params
+            FROM mission_rotation
+            WHERE instance_id = ?
+            ORDER BY position ASC
+            "#,
+        )
+        .bind(instance_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(missions.into_iter().map(Into::into).collect())
+    }
+
+    /// Replaces the whole rotation, the missions are stored in the given order.
+    pub async fn set(
+        &self,
+        instance_id: i64,
+        missions: Vec<RotationMission>,
+    ) -> RepositoryResult<Vec<RotationMission>> {
+        sqlx::query("DELETE FROM mission_rotation WHERE instance_id = ?")
+            .bind(instance_id)
+            .execute(&self.pool)
+            .await?;
+
+        for (position, mission) in missions.iter().enumerate() {
+            let params = mission
+                .params
+                .iter()
+                .map(|param| format!("{}={}", param.name, param.value))
+                .collect::<Vec<_>>();
+
+            sqlx::query(
+                r#"
+                INSERT INTO mission_rotation (instance_id, position, template, difficulty, params)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(instance_id)
+            .bind(position as i64)
+            .bind(&mission.template)
+            .bind(&mission.difficulty)
+            .bind(params.join("\n"))
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(missions)
+    }
+
+    /// Points every rotation that uses a mission to its new name.
+    pub async fn rename_template(&self, old: &str, new: &str) -> RepositoryResult<()> {
+        sqlx::query("UPDATE mission_rotation SET template = ? WHERE template = ? COLLATE NOCASE")
+            .bind(new)
+            .bind(old)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SqlRotationMission {
+    template: String,
+    difficulty: String,
+    params: String,
+}
+
+impl From<SqlRotationMission> for RotationMission {
+    fn from(mission: SqlRotationMission) -> Self {
+        Self {
+            template: mission.template,
+            difficulty: mission.difficulty,
+            params: mission
+                .params
+                .lines()
+                .filter_map(|line| {
+                    let (name, value) = line.split_once('=')?;
+
+                    Some(MissionParam {
+                        name: name.to_string(),
+                        value: value.parse().ok()?,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
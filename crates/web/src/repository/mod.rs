@@ -1,9 +1,31 @@
 type RepositoryResult<T> = Result<T, Box<dyn std::error::Error>>;
 
+mod announcement_repository;
+mod audit_repository;
+mod ban_repository;
+mod instance_repository;
+mod key_repository;
+mod message_repository;
+mod mission_repository;
+mod mission_rotation_repository;
 mod preset_repository;
+mod restart_schedule_repository;
+mod session_repository;
 mod user_repository;
 mod user_token_repository;
+mod whitelist_repository;
 
+pub use announcement_repository::*;
+pub use audit_repository::*;
+pub use ban_repository::*;
+pub use instance_repository::*;
+pub use key_repository::*;
+pub use message_repository::*;
+pub use mission_repository::*;
+pub use mission_rotation_repository::*;
 pub use preset_repository::*;
+pub use restart_schedule_repository::*;
+pub use session_repository::*;
 pub use user_repository::*;
 pub use user_token_repository::*;
+pub use whitelist_repository::*;
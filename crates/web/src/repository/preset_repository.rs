@@ -1,11 +1,18 @@
 use api_schema::{
     request::*,
-    response::{DlcItem, Preset, PresetItem},
+    response::{DlcItem, Instance, Preset, PresetItem},
 };
 use sqlx::{QueryBuilder, SqlitePool};
 
 use super::RepositoryResult;
 
+/// Instances without a preset of their own run the selected one.
+const INSTANCES_USING_PRESET: &str = r#"
+    SELECT name FROM instances
+    WHERE preset_id = ?1 OR (preset_id IS NULL AND EXISTS(SELECT 1 FROM presets WHERE id = ?1 AND selected = 1))
+    ORDER BY id ASC
+"#;
+
 #[derive(Clone)]
 pub struct PresetRepository {
     pool: SqlitePool,
@@ -22,7 +29,7 @@ impl PresetRepository {
         let presets = sqlx::query_as!(
             SqlPreset,
             r#"
-            SELECT id, name, selected
+            SELECT id, name, selected, whitelist
             FROM presets
             ORDER BY name ASC
             "#
@@ -38,6 +45,7 @@ impl PresetRepository {
                 id: preset.id,
                 name: preset.name,
                 selected: preset.selected.is_some(),
+                whitelist: preset.whitelist,
                 items,
                 dlcs,
             });
@@ -46,11 +54,19 @@ impl PresetRepository {
         Ok(result)
     }
 
+    /// The preset the instance launches with, falling back to the selected one.
+    pub async fn get_instance_preset(&self, instance: &Instance) -> RepositoryResult<Option<Preset>> {
+        match instance.preset_id {
+            Some(id) => self.get_preset(id).await,
+            None => self.get_selected_preset().await,
+        }
+    }
+
     pub async fn get_selected_preset(&self) -> RepositoryResult<Option<Preset>> {
         let preset = sqlx::query_as!(
             SqlPreset,
             r#"
-            SELECT id, name, selected
+            SELECT id, name, selected, whitelist
             FROM presets
             WHERE selected = ?
             "#,
@@ -66,6 +82,7 @@ impl PresetRepository {
                 id: preset.id,
                 name: preset.name,
                 selected: true,
+                whitelist: preset.whitelist,
                 items,
                 dlcs,
             }))
@@ -74,6 +91,35 @@ impl PresetRepository {
         }
     }
 
+    pub async fn get_preset(&self, id: i64) -> RepositoryResult<Option<Preset>> {
+        let preset: Option<(i64, String, Option<bool>, bool)> = sqlx::query_as(
+            r#"
+            SELECT id, name, selected, whitelist
+            FROM presets
+            WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some((id, name, selected, whitelist)) = preset else {
+            return Ok(None);
+        };
+
+        let items = self.get_items(id).await?;
+        let dlcs = self.get_dlcs(id).await?;
+
+        Ok(Some(Preset {
+            id,
+            name,
+            selected: selected.is_some(),
+            whitelist,
+            items,
+            dlcs,
+        }))
+    }
+
     /*
     SELECT id, name, published_file_id, position, enabled
     FROM preset_items
@@ -85,7 +131,7 @@ impl PresetRepository {
     pub async fn get_items(&self, preset_id: i64) -> RepositoryResult<Vec<PresetItem>> {
         let mut items: Vec<PresetItem> = sqlx::query_as(
             r#"
-            SELECT i.id, i.name, i.published_file_id, i.position, i.enabled, i.server_mod,
+            SELECT i.id, i.name, i.published_file_id, i.position, i.enabled, i.server_mod, i.optional,
                 CASE WHEN b.published_file_id IS NULL THEN 0 ELSE 1 END AS blacklisted
             FROM preset_items i
             LEFT JOIN blacklist b ON i.published_file_id = b.published_file_id
@@ -130,7 +176,7 @@ impl PresetRepository {
             INSERT INTO presets (name, selected)
             VALUES (?, ?)
             ON CONFLICT (name) DO UPDATE SET updated_at = CURRENT_TIMESTAMP
-            RETURNING id, name, selected
+            RETURNING id, name, selected, whitelist
             "#,
             input.name,
             false
@@ -165,7 +211,7 @@ impl PresetRepository {
                 r#"
                 INSERT INTO preset_items (preset_id, name, published_file_id, position, enabled)
                 VALUES (?, ?, ?, ?, ?)
-                RETURNING id, name, published_file_id, position, enabled, server_mod,
+                RETURNING id, name, published_file_id, position, enabled, server_mod, optional,
                     (SELECT EXISTS(SELECT 1 FROM blacklist b
                     WHERE b.published_file_id = preset_items.published_file_id)) AS blacklisted
                 "#,
@@ -209,6 +255,7 @@ impl PresetRepository {
             id: preset.id,
             name: preset.name,
             selected: preset.selected.is_some(),
+            whitelist: preset.whitelist,
             items,
             dlcs,
         };
@@ -231,7 +278,7 @@ impl PresetRepository {
             SqlPreset,
             r#"
             UPDATE presets SET selected = 1 WHERE id = ?
-            RETURNING id as "id!", name, selected
+            RETURNING id as "id!", name, selected, whitelist
             "#,
             id
         )
@@ -245,6 +292,7 @@ impl PresetRepository {
             id: preset.id,
             name: preset.name,
             selected: preset.selected.is_some(),
+            whitelist: preset.whitelist,
             items,
             dlcs,
         })
@@ -279,14 +327,22 @@ impl PresetRepository {
             if !first {
                 query.push(",");
             }
+            first = false;
             query.push(" server_mod = ").push_bind(server_mod);
         }
 
+        if let Some(optional) = schema.optional {
+            if !first {
+                query.push(",");
+            }
+            query.push(" optional = ").push_bind(optional);
+        }
+
         query.push(" WHERE id = ").push_bind(schema.id);
 
         // returning
         query.push(
-            "RETURNING id, name, published_file_id, position, enabled, server_mod,
+            "RETURNING id, name, published_file_id, position, enabled, server_mod, optional,
             (SELECT EXISTS(SELECT 1 FROM blacklist b
              WHERE b.published_file_id = preset_items.published_file_id)) AS blacklisted",
         );
@@ -322,7 +378,19 @@ impl PresetRepository {
         Ok(dlc)
     }
 
+    /// The instances running the preset, either picked for them or as the selected preset they fall back to.
+    pub async fn get_instances_using(&self, id: i64) -> RepositoryResult<Vec<String>> {
+        let names = sqlx::query_scalar(INSTANCES_USING_PRESET)
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(names)
+    }
+
     pub async fn delete_preset(&self, id: i64) -> RepositoryResult<()> {
+        let mut tx = self.pool.begin().await?;
+
         // abort if the preset is the currently selected one
         let selected = sqlx::query!(
             r#"
@@ -330,28 +398,39 @@ impl PresetRepository {
             "#,
             id
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
         if selected.selected.is_some() {
             return Err(anyhow::anyhow!("Cannot delete the currently selected preset").into());
         }
 
+        let instances: Vec<String> = sqlx::query_scalar(INSTANCES_USING_PRESET)
+            .bind(id)
+            .fetch_all(&mut *tx)
+            .await?;
+
+        if !instances.is_empty() {
+            return Err(anyhow::anyhow!("The preset is used by {}", instances.join(", ")).into());
+        }
+
         sqlx::query("DELETE FROM preset_items WHERE preset_id = ?")
             .bind(id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
 
         sqlx::query("DELETE FROM preset_dlc WHERE preset_id = ?")
             .bind(id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
 
         sqlx::query("DELETE FROM presets WHERE id = ?")
             .bind(id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
 
+        tx.commit().await?;
+
         Ok(())
     }
 }
@@ -406,4 +485,5 @@ struct SqlPreset {
     id: i64,
     name: String,
     selected: Option<bool>,
+    whitelist: bool,
 }
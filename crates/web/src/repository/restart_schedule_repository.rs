@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use sqlx::SqlitePool;
+
+use super::RepositoryResult;
+
+#[derive(Clone)]
+pub struct RestartScheduleRepository {
+    pool: SqlitePool,
+}
+
+impl RestartScheduleRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+impl RestartScheduleRepository {
+    /// The times of day the instance restarts at, in minutes after midnight UTC.
+    pub async fn get(&self, instance_id: i64) -> RepositoryResult<Vec<i64>> {
+        let minutes = sqlx::query_scalar(
+            r#"
+            SELECT minute
+            FROM restart_schedule
+            WHERE instance_id = ?
+            ORDER BY minute ASC
+            "#,
+        )
+        .bind(instance_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(minutes)
+    }
+
+    /// The schedules of every instance that has one.
+    pub async fn get_all(&self) -> RepositoryResult<HashMap<i64, Vec<i64>>> {
+        let rows: Vec<(i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT instance_id, minute
+            FROM restart_schedule
+            ORDER BY instance_id ASC, minute ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut schedules: HashMap<i64, Vec<i64>> = HashMap::new();
+
+        for (instance_id, minute) in rows {
+            schedules.entry(instance_id).or_default().push(minute);
+        }
+
+        Ok(schedules)
+    }
+
+    /// Replaces the whole schedule of the instance.
+    pub async fn set(&self, instance_id: i64, minutes: Vec<i64>) -> RepositoryResult<Vec<i64>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM restart_schedule WHERE instance_id = ?")
+            .bind(instance_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for minute in &minutes {
+            sqlx::query("INSERT INTO restart_schedule (instance_id, minute) VALUES (?, ?)")
+                .bind(instance_id)
+                .bind(minute)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(minutes)
+    }
+}
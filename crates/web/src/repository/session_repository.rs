@@ -0,0 +1,179 @@
+use api_schema::{
+    request::SessionQuery,
+    response::{DailyPlayerStats, PlayerPlaytime, PlayerSession},
+};
+use sqlx::SqlitePool;
+
+use super::RepositoryResult;
+
+/// Who played when, players are told apart by GUID and by name where there is none.
+#[derive(Clone)]
+pub struct SessionRepository {
+    pool: SqlitePool,
+}
+
+impl SessionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+pub struct NewSession<'a> {
+    pub instance_id: i64,
+    pub name: &'a str,
+    pub guid: Option<&'a str>,
+    pub mission: Option<&'a str>,
+    pub joined_at: i64,
+}
+
+impl SessionRepository {
+    /// Returns the id of the new session.
+    pub async fn open(&self, session: NewSession<'_>) -> RepositoryResult<i64> {
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO player_sessions (instance_id, name, guid, mission, joined_at, left_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            RETURNING id
+            "#,
+        )
+        .bind(session.instance_id)
+        .bind(session.name)
+        .bind(session.guid)
+        .bind(session.mission)
+        .bind(session.joined_at)
+        .bind(session.joined_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Moves the end of the session to now, the GUID is only set if it wasn't known yet.
+    pub async fn touch(&self, id: i64, guid: Option<&str>, left_at: i64) -> RepositoryResult<()> {
+        sqlx::query("UPDATE player_sessions SET left_at = ?, guid = COALESCE(guid, ?) WHERE id = ?")
+            .bind(left_at)
+            .bind(guid)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn search(
+        &self,
+        instance_id: i64,
+        query: &SessionQuery,
+        limit: i64,
+    ) -> RepositoryResult<Vec<PlayerSession>> {
+        let player = query.player.as_deref().filter(|player| !player.is_empty());
+
+        let sessions: Vec<SqlSession> = sqlx::query_as(
+            r#"
+            SELECT id, instance_id, name, guid, mission, joined_at, left_at
+            FROM player_sessions
+            WHERE instance_id = ?
+                AND (? IS NULL OR guid = ? OR (guid IS NULL AND name = ?))
+            ORDER BY joined_at DESC, id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(instance_id)
+        .bind(player)
+        .bind(player)
+        .bind(player)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sessions.into_iter().map(Into::into).collect())
+    }
+
+    /// Days without players are left out. The peak is taken at the moments players joined,
+    /// which is the only time the number of players goes up.
+    pub async fn daily(&self, instance_id: i64, since: i64) -> RepositoryResult<Vec<DailyPlayerStats>> {
+        let days: Vec<(String, i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT date(a.joined_at, 'unixepoch', 'localtime') AS day,
+                COUNT(DISTINCT COALESCE(a.guid, a.name)) AS unique_players,
+                MAX((
+                    SELECT COUNT(*) FROM player_sessions b
+                    WHERE b.instance_id = a.instance_id AND b.joined_at <= a.joined_at AND b.left_at >= a.joined_at
+                )) AS peak_players
+            FROM player_sessions a
+            WHERE a.instance_id = ? AND a.joined_at >= ?
+            GROUP BY day
+            ORDER BY day ASC
+            "#,
+        )
+        .bind(instance_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(days
+            .into_iter()
+            .map(|(day, unique_players, peak_players)| DailyPlayerStats {
+                day,
+                unique_players,
+                peak_players,
+            })
+            .collect())
+    }
+
+    /// The players that played the longest, the name and GUID are the ones of their last session.
+    pub async fn playtime(&self, instance_id: i64, since: i64, limit: i64) -> RepositoryResult<Vec<PlayerPlaytime>> {
+        let players: Vec<(String, Option<String>, i64, i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT name, guid, SUM(left_at - joined_at) AS playtime, COUNT(*) AS sessions, MAX(left_at) AS last_seen
+            FROM player_sessions
+            WHERE instance_id = ? AND joined_at >= ?
+            GROUP BY COALESCE(guid, name)
+            ORDER BY playtime DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(instance_id)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(players
+            .into_iter()
+            .map(|(name, guid, playtime, sessions, last_seen)| PlayerPlaytime {
+                name,
+                guid,
+                playtime,
+                sessions,
+                last_seen,
+            })
+            .collect())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SqlSession {
+    id: i64,
+    instance_id: i64,
+    name: String,
+    guid: Option<String>,
+    mission: Option<String>,
+    joined_at: i64,
+    left_at: i64,
+}
+
+impl From<SqlSession> for PlayerSession {
+    fn from(session: SqlSession) -> Self {
+        Self {
+            id: session.id,
+            instance_id: session.instance_id,
+            name: session.name,
+            guid: session.guid,
+            mission: session.mission,
+            joined_at: session.joined_at,
+            left_at: session.left_at,
+            duration: session.left_at - session.joined_at,
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use api_schema::response::{WhitelistEntry, WhitelistKind, WhitelistSettings};
+use sqlx::SqlitePool;
+
+use super::RepositoryResult;
+
+/// The whitelist shared by all instances and where it is turned on.
+#[derive(Clone)]
+pub struct WhitelistRepository {
+    pool: SqlitePool,
+}
+
+impl WhitelistRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+pub struct NewWhitelistEntry {
+    pub kind: WhitelistKind,
+    pub value: String,
+    pub name: String,
+    pub author: String,
+}
+
+impl WhitelistRepository {
+    pub async fn get_all(&self) -> RepositoryResult<Vec<WhitelistEntry>> {
+        let entries: Vec<SqlWhitelistEntry> = sqlx::query_as(
+            r#"
+            SELECT id, kind, value, name, author,
+                CAST(strftime('%s', created_at) AS INTEGER) AS created_at
+            FROM whitelist
+            ORDER BY id DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries.into_iter().map(Into::into).collect())
+    }
+
+    /// Whether an entry lets the player with the BattlEye GUID in.
+    pub async fn contains(&self, guid: &str) -> RepositoryResult<bool> {
+        let contains: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM whitelist WHERE guid = ?)")
+            .bind(guid.to_lowercase())
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(contains)
+    }
+
+    /// Adding a player that is already on the whitelist only updates the name.
+    pub async fn add(&self, entry: NewWhitelistEntry) -> RepositoryResult<WhitelistEntry> {
+        let entry: SqlWhitelistEntry = sqlx::query_as(
+            r#"
+            INSERT INTO whitelist (kind, value, guid, name, author)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(kind, value) DO UPDATE SET name = excluded.name
+            RETURNING id, kind, value, name, author,
+                CAST(strftime('%s', created_at) AS INTEGER) AS created_at
+            "#,
+        )
+        .bind(kind_to_str(entry.kind))
+        .bind(&entry.value)
+        .bind(guid(&entry))
+        .bind(entry.name)
+        .bind(entry.author)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(entry.into())
+    }
+
+    /// Adds the entries that aren't there yet and leaves existing ones alone, returns how many were added.
+    pub async fn add_missing(&self, entries: Vec<NewWhitelistEntry>) -> RepositoryResult<u64> {
+        let mut added = 0;
+
+        for entry in entries {
+            added += sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO whitelist (kind, value, guid, name, author)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(kind_to_str(entry.kind))
+            .bind(&entry.value)
+            .bind(guid(&entry))
+            .bind(entry.name)
+            .bind(entry.author)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+        }
+
+        Ok(added)
+    }
+
+    /// Returns whether there was an entry to delete.
+    pub async fn delete(&self, id: i64) -> RepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM whitelist WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Without an instance preset the selected preset decides, like it does on start.
+    pub async fn get_settings(&self, instance_id: i64) -> RepositoryResult<Option<WhitelistSettings>> {
+        let settings: Option<(bool, String, bool)> = sqlx::query_as(
+            r#"
+            SELECT i.whitelist, i.whitelist_message,
+                COALESCE((
+                    SELECT p.whitelist FROM presets p
+                    WHERE p.id = COALESCE(i.preset_id, (SELECT id FROM presets WHERE selected = 1))
+                ), 0)
+            FROM instances i
+            WHERE i.id = ?
+            "#,
+        )
+        .bind(instance_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(settings.map(|(enabled, message, preset_enabled)| WhitelistSettings {
+            enabled,
+            message,
+            preset_enabled,
+        }))
+    }
+
+    pub async fn set_settings(&self, instance_id: i64, enabled: bool, message: &str) -> RepositoryResult<()> {
+        sqlx::query("UPDATE instances SET whitelist = ?, whitelist_message = ? WHERE id = ?")
+            .bind(enabled)
+            .bind(message)
+            .bind(instance_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns whether there was a preset to update.
+    pub async fn set_preset(&self, preset_id: i64, enabled: bool) -> RepositoryResult<bool> {
+        let result = sqlx::query("UPDATE presets SET whitelist = ? WHERE id = ?")
+            .bind(enabled)
+            .bind(preset_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// RCon only tells the GUID of a player, so Steam UIDs are matched by the GUID they map to.
+fn guid(entry: &NewWhitelistEntry) -> String {
+    match entry.kind {
+        WhitelistKind::Uid => rcon::battleye_guid(entry.value.parse().unwrap_or_default()),
+        WhitelistKind::Guid => entry.value.to_lowercase(),
+    }
+}
+
+fn kind_to_str(kind: WhitelistKind) -> &'static str {
+    match kind {
+        WhitelistKind::Uid => "uid",
+        WhitelistKind::Guid => "guid",
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SqlWhitelistEntry {
+    id: i64,
+    kind: String,
+    value: String,
+    name: String,
+    author: String,
+    created_at: Option<i64>,
+}
+
+impl From<SqlWhitelistEntry> for WhitelistEntry {
+    fn from(entry: SqlWhitelistEntry) -> Self {
+        let kind = match entry.kind.as_str() {
+            "uid" => WhitelistKind::Uid,
+            _ => WhitelistKind::Guid,
+        };
+
+        Self {
+            id: entry.id,
+            kind,
+            value: entry.value,
+            name: entry.name,
+            author: entry.author,
+            created_at: entry.created_at.unwrap_or_default(),
+        }
+    }
+}
@@ -7,8 +7,12 @@ pub fn create_router(app_state: AppState) -> Router {
 
     Router::new()
         .route("/api/v1/arma/mission", post(upload_mission))
+        .route("/api/v1/arma/mission/:id", post(replace_mission))
         .layer(DefaultBodyLimit::max(1024 * 1024 * 1024)) // 1GB
         .route("/api/v1/arma/mission", get(get_missions))
+        .route("/api/v1/arma/mission/:id", patch(rename_mission))
+        .route("/api/v1/arma/mission/:id", delete(delete_mission))
+        .route("/api/v1/arma/mission/:id/download", get(download_mission))
         // Protected routes
         .route("/api/v1/users", get(get_users_without_tokens))
         .route("/api/v1/users/me", get(get_me_handler))
@@ -19,30 +23,87 @@ pub fn create_router(app_state: AppState) -> Router {
         .route("/api/v1/status", get(api_status_handler))
         .route("/api/v1/arma/update", get(update_arma))
         .route("/api/v1/arma/cancel_update", get(cancel_update_arma))
-        .route("/api/v1/arma/start", get(start_arma))
-        .route("/api/v1/arma/stop", get(stop_arma))
-        .route("/api/v1/arma/restart", get(restart_arma))
         .route("/api/v1/arma/mods/download", get(download_missing_mods))
         .route("/api/v1/arma/mods/check", get(force_check))
-        .route("/api/v1/arma/config/:channel", get(get_config))
-        .route("/api/v1/arma/config/:channel", post(post_config))
         .route("/api/v1/logs/:channel", get(api_logs))
+        .route("/api/v1/instances", get(get_instances))
+        .route("/api/v1/instances", post(create_instance))
+        .route("/api/v1/instances", patch(update_instance))
+        .route("/api/v1/instances", delete(delete_instance))
+        .route("/api/v1/instances/:instance/start", get(start_arma))
+        .route("/api/v1/instances/:instance/stop", get(stop_arma))
+        .route("/api/v1/instances/:instance/restart", get(restart_arma))
+        .route("/api/v1/instances/:instance/launch/preview", get(preview_launch))
+        .route("/api/v1/instances/:instance/preflight", get(preflight_arma))
+        .route("/api/v1/instances/:instance/restarts", get(get_restart_schedule))
+        .route("/api/v1/instances/:instance/restarts", post(update_restart_schedule))
+        .route("/api/v1/instances/:instance/config/:channel", get(get_config))
+        .route("/api/v1/instances/:instance/config/:channel", post(post_config))
+        .route("/api/v1/instances/:instance/missions", get(get_mission_rotation))
+        .route("/api/v1/instances/:instance/missions", post(update_mission_rotation))
+        .route("/api/v1/instances/:instance/missions/addons", get(get_mission_addons))
+        .route("/api/v1/instances/:instance/logs/:channel", get(api_instance_logs))
+        .route("/api/v1/instances/:instance/a2s/info", get(api_a2s_info))
+        .route("/api/v1/instances/:instance/a2s/players", get(api_a2s_players))
+        .route("/api/v1/instances/:instance/a2s/state", get(api_a2s_state))
+        .route("/api/v1/instances/:instance/a2s/rules", get(api_a2s_rules))
+        .route("/api/v1/instances/:instance/a2s/rules/preset", get(check_loaded_preset))
+        .route("/api/v1/instances/:instance/players", get(get_players))
+        .route("/api/v1/instances/:instance/players/kick", post(kick_player))
+        .route("/api/v1/instances/:instance/players/ban", post(ban_player))
+        .route("/api/v1/instances/:instance/players/message", post(send_message))
+        .route("/api/v1/instances/:instance/lock", post(lock_server))
+        .route("/api/v1/instances/:instance/audit", get(get_audit_log))
+        .route("/api/v1/instances/:instance/messages", get(get_messages))
+        .route("/api/v1/instances/:instance/announcements", get(get_announcements))
+        .route("/api/v1/instances/:instance/announcements", post(create_announcement))
+        .route(
+            "/api/v1/instances/:instance/announcements/:id",
+            patch(update_announcement),
+        )
+        .route(
+            "/api/v1/instances/:instance/announcements/:id",
+            delete(delete_announcement),
+        )
+        .route("/api/v1/instances/:instance/whitelist", get(get_whitelist_settings))
+        .route("/api/v1/instances/:instance/whitelist", post(update_whitelist_settings))
+        .route("/api/v1/instances/:instance/sessions", get(get_sessions))
+        .route("/api/v1/instances/:instance/stats/daily", get(get_daily_stats))
+        .route("/api/v1/instances/:instance/stats/playtime", get(get_playtime))
         .route("/api/v1/presets", get(get_presets))
         .route("/api/v1/presets", post(create_preset))
         .route("/api/v1/presets", patch(select_preset))
         .route("/api/v1/presets", delete(delete_preset))
         .route("/api/v1/presets/item", patch(update_preset_item))
         .route("/api/v1/presets/dlc", patch(update_preset_dlc))
+        .route("/api/v1/presets/:id/load_order", get(get_load_order))
+        .route("/api/v1/presets/:id/load_order", post(apply_load_order))
+        .route("/api/v1/presets/:id/keys", get(get_key_report))
+        .route("/api/v1/presets/:id/conflicts", get(get_conflicts))
+        .route("/api/v1/presets/:id/export", get(export_preset))
+        .route("/api/v1/presets/:id/whitelist", patch(update_preset_whitelist))
+        .route("/api/v1/keys", get(get_server_keys))
+        .route("/api/v1/keys", post(upload_key))
+        .route("/api/v1/keys/:name", delete(delete_key))
         .route("/api/v1/presets/item/blacklist", post(blacklist_item))
         .route("/api/v1/presets/item/blacklist", delete(unblacklist_item))
-        .route("/api/v1/a2s/info", get(api_a2s_info))
-        .route("/api/v1/a2s/players", get(api_a2s_players))
+        .route("/api/v1/bans", get(get_bans))
+        .route("/api/v1/bans", post(create_ban))
+        .route("/api/v1/bans/import", post(import_bans))
+        .route("/api/v1/bans/export/:format", get(export_bans))
+        .route("/api/v1/bans/:id", delete(delete_ban))
+        .route("/api/v1/whitelist", get(get_whitelist))
+        .route("/api/v1/whitelist", post(create_whitelist_entry))
+        .route("/api/v1/whitelist/import", post(import_whitelist))
+        .route("/api/v1/whitelist/:id", delete(delete_whitelist_entry))
         // SSE routes
         .route("/sse/v1/status", get(sse_status_handler))
         .route("/sse/v1/logs", get(sse_logs))
         .route("/sse/v1/presets", get(sse_preset_handler))
-        .route("/sse/v1/arma/config", get(sse_config))
-        .route("/sse/v1/a2s", get(sse_a2s))
+        .route("/sse/v1/instances/:instance/logs", get(sse_instance_logs))
+        .route("/sse/v1/instances/:instance/config", get(sse_config))
+        .route("/sse/v1/instances/:instance/a2s", get(sse_a2s))
+        .route("/sse/v1/instances/:instance/messages", get(sse_messages))
         .layer(auth_layer)
         // Public routes
         .route("/api/v1/auth/register", post(register_user_handler))
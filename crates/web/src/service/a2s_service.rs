@@ -1,36 +1,79 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    future::Future,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
 
-use a2s::{info::Info, players::Player};
+use a2s::{info::Info, players::Player, A2SClient};
+use api_schema::a2s::{A2sState, ArmaRules};
 use axum::response::sse::Event;
 use serde::Serialize;
-use tokio::sync::broadcast;
+use tokio::{net::UdpSocket, sync::broadcast, task::JoinHandle};
+
+use crate::service::{State, StatusService};
+
+/// How often a reachable server is queried.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// How often the status is checked while the server isn't running.
+const IDLE_INTERVAL: Duration = Duration::from_secs(2);
+/// Failed queries wait twice as long as the one before, up to this.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+/// The rules only change with the mission, so they are queried less often.
+const RULES_INTERVAL: Duration = Duration::from_secs(60);
+/// Failed queries in a row before a server that is still loading counts as unreachable.
+const UNREACHABLE_AFTER: u32 = 3;
 
 #[derive(Debug, Clone, Serialize)]
 pub enum PlayerOrInfo {
     Players(Vec<Player>),
     Info(Box<Info>),
+    State(A2sState),
 }
 
 #[derive(Clone)]
 pub struct A2sService {
+    target: SocketAddr,
+    state: Arc<RwLock<A2sState>>,
     info: Arc<RwLock<Option<Info>>>,
     players: Arc<RwLock<Vec<Player>>>,
+    rules: Arc<RwLock<Option<ArmaRules>>>,
     tx: broadcast::Sender<Event>,
+    handle: Arc<Mutex<Option<JoinHandle<()>>>>,
 }
 
 impl A2sService {
-    pub fn new() -> Arc<Self> {
+    /// Polls the Steam query port at `target`.
+    pub fn new(target: SocketAddr) -> Arc<Self> {
         Arc::new(Self {
+            target,
+            state: Arc::new(RwLock::new(A2sState::default())),
             info: Arc::new(RwLock::new(None)),
             players: Arc::new(RwLock::new(vec![])),
+            rules: Arc::new(RwLock::new(None)),
             tx: broadcast::channel(100).0,
+            handle: Arc::new(Mutex::new(None)),
         })
     }
 
+    /// The query port is the game port + 1 unless set, the address the first network interface that isn't loopback.
+    pub fn target(game_port: u16, query_ip: Option<IpAddr>, query_port: Option<u16>) -> SocketAddr {
+        let ip = query_ip
+            .or_else(get_ip_address)
+            .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        SocketAddr::new(ip, query_port.unwrap_or(game_port + 1))
+    }
+
     pub fn subscribe(&self) -> broadcast::Receiver<Event> {
         self.tx.subscribe()
     }
 
+    pub fn get_state(&self) -> A2sState {
+        *self.state.read().unwrap()
+    }
+
     pub fn get_latest_info(&self) -> Option<Info> {
         let info = self.info.read().unwrap();
         info.clone()
@@ -41,68 +84,178 @@ impl A2sService {
         players.clone()
     }
 
-    pub fn start(&self) {
-        // spawn a thread to pull changes from a2s every 5 to 10 seconds
-        let tx = self.tx.clone();
+    /// The mods, DLC and difficulty the server reports, `None` until it answered.
+    pub fn get_latest_rules(&self) -> Option<ArmaRules> {
+        let rules = self.rules.read().unwrap();
+        rules.clone()
+    }
+
+    /// Polls while the instance is running, backing off while the server doesn't answer.
+    pub fn start(&self, instance: i64, status: Arc<StatusService>) {
+        let service = self.clone();
 
-        // get the ip address of the first network interface that is not localhost
+        let handle = tokio::spawn(async move {
+            let mut client = None;
+            let mut rules_socket = None;
+            let mut rules_at: Option<Instant> = None;
+            let mut failures = 0;
 
-        let last_info = self.info.clone();
-        let last_players = self.players.clone();
-        tokio::spawn(async move {
-            let ip = get_ip_address().expect("ip address");
-            let ip_port = format!("{}:2303", ip);
             loop {
-                {
-                    let client = a2s::A2SClient::new().await.expect("socket stuff");
-                    if let Ok(info) = client.info(&ip_port).await {
-                        let mut last_info = last_info.write().unwrap();
-                        *last_info = Some(info.clone());
-                        drop(last_info);
-                        let info = PlayerOrInfo::Info(Box::new(info));
-                        let info_json = serde_json::to_string(&info).expect("serde to work");
-                        let info_event = Event::default().event("info").data(info_json);
-                        let _ = tx.send(info_event);
-                    };
+                if status.arma(instance).await != State::Running {
+                    service.set_state(A2sState::Stopped);
+                    failures = 0;
+                    rules_at = None;
+                    tokio::time::sleep(IDLE_INTERVAL).await;
+                    continue;
                 }
 
-                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-
-                {
-                    let client = a2s::A2SClient::new().await.expect("socket stuff");
-                    match client.players(&ip_port).await {
-                        Ok(players) => {
-                            let mut last_players = last_players.write().unwrap();
-                            *last_players = players.clone();
-                            drop(last_players);
-                            let players = PlayerOrInfo::Players(players);
-                            let players_json = serde_json::to_string(&players).expect("serde to work");
-                            let players_event = Event::default().event("players").data(players_json);
-                            let _ = tx.send(players_event);
-                        }
+                if service.get_state() == A2sState::Stopped {
+                    service.set_state(A2sState::Waiting);
+                }
+
+                // the socket is kept for as long as the instance exists
+                let Some(a2s) = client.as_ref() else {
+                    match A2SClient::new().await {
+                        Ok(created) => client = Some(created),
                         Err(e) => {
-                            tracing::error!("{:?}", e);
+                            tracing::error!("Failed to create the A2S client: {:?}", e);
+                            tokio::time::sleep(MAX_BACKOFF).await;
                         }
                     }
+                    continue;
+                };
+
+                if service.poll(a2s).await {
+                    failures = 0;
+                    service.set_state(A2sState::Reachable);
+
+                    if rules_at.is_none_or(|at| at.elapsed() >= RULES_INTERVAL) {
+                        if rules_socket.is_none() {
+                            rules_socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await.ok();
+                        }
+
+                        if let Some(socket) = &rules_socket {
+                            service.poll_rules(socket).await;
+                            rules_at = Some(Instant::now());
+                        }
+                    }
+
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                    continue;
                 }
 
-                // sleep
-                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                failures += 1;
+                rules_at = None;
+
+                // a reachable server that stops answering is reported right away, a loading one gets some time
+                if service.get_state() == A2sState::Reachable || failures >= UNREACHABLE_AFTER {
+                    service.set_state(A2sState::Unreachable);
+                }
+
+                let backoff = POLL_INTERVAL * 2u32.pow(failures.min(8));
+                tokio::time::sleep(backoff.min(MAX_BACKOFF)).await;
             }
         });
+
+        *self.handle.lock().unwrap() = Some(handle);
+    }
+
+    pub fn stop(&self) {
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+
+    /// Queries info and players, returns whether the server answered both.
+    async fn poll(&self, client: &A2SClient) -> bool {
+        let info = match query(client.info(self.target)).await {
+            Ok(info) => info,
+            Err(e) => {
+                tracing::debug!("A2S info of {} failed: {}", self.target, e);
+                return false;
+            }
+        };
+
+        *self.info.write().unwrap() = Some(info.clone());
+        self.send("info", &PlayerOrInfo::Info(Box::new(info)));
+
+        let players = match query(client.players(self.target)).await {
+            Ok(players) => players,
+            Err(e) => {
+                tracing::debug!("A2S players of {} failed: {}", self.target, e);
+                return false;
+            }
+        };
+
+        *self.players.write().unwrap() = players.clone();
+        self.send("players", &PlayerOrInfo::Players(players));
+
+        true
+    }
+
+    /// Arma's rules are binary, so they are queried without the A2S client. Failing rules don't make the server unreachable.
+    async fn poll_rules(&self, socket: &UdpSocket) {
+        let rules = arma::query_rules(socket, self.target, QUERY_TIMEOUT)
+            .await
+            .and_then(|rules| arma::decode_rules(&rules));
+
+        match rules {
+            Ok(mut rules) => {
+                let keywords = self
+                    .get_latest_info()
+                    .and_then(|info| info.extended_server_info.keywords);
+
+                if let Some(keywords) = keywords {
+                    arma::apply_keywords(&mut rules, &keywords);
+                }
+
+                *self.rules.write().unwrap() = Some(rules);
+            }
+            Err(e) => tracing::debug!("A2S rules of {} failed: {}", self.target, e),
+        }
+    }
+
+    /// Info, players and rules are discarded once the server isn't reachable, so nothing stale is shown.
+    fn set_state(&self, state: A2sState) {
+        let previous = std::mem::replace(&mut *self.state.write().unwrap(), state);
+
+        if previous == state {
+            return;
+        }
+
+        if state != A2sState::Reachable {
+            *self.info.write().unwrap() = None;
+            self.players.write().unwrap().clear();
+            *self.rules.write().unwrap() = None;
+        }
+
+        self.send("state", &PlayerOrInfo::State(state));
+    }
+
+    fn send(&self, event: &str, data: &PlayerOrInfo) {
+        let json = serde_json::to_string(data).expect("serde to work");
+        let _ = self.tx.send(Event::default().event(event).data(json));
+    }
+}
+
+async fn query<T, E: std::fmt::Debug>(request: impl Future<Output = Result<T, E>>) -> Result<T, String> {
+    match tokio::time::timeout(QUERY_TIMEOUT, request).await {
+        Ok(Ok(response)) => Ok(response),
+        Ok(Err(e)) => Err(format!("{:?}", e)),
+        Err(_) => Err("timed out".to_string()),
     }
 }
 
 /// getting the players gives me a InvalidResponse when using 127.0.0.1
 /// So an automated way to get the first network adapter that is not localhost
 /// is needed, weird hack, i know, but it allows me to continue developing.
-fn get_ip_address() -> Option<String> {
+fn get_ip_address() -> Option<IpAddr> {
     use local_ip_address::list_afinet_netifas;
     let network_interfaces = list_afinet_netifas();
     if let Ok(network_interfaces) = network_interfaces {
         for (_, ip) in network_interfaces.iter() {
             if ip.is_ipv4() && !ip.is_loopback() {
-                return Some(ip.to_string());
+                return Some(*ip);
             }
         }
     }
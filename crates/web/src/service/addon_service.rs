@@ -0,0 +1,204 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use api_schema::response::{
+    AddonProvider, ConflictReport, LoadOrderReport, MissingAddon, Mission, MissionAddonCheck, MissionAddonReport,
+    Preset,
+};
+use arma::CfgPatch;
+use tokio::sync::Mutex;
+
+/// The `CfgPatches` of a folder along with the fingerprint of its PBOs when they were read.
+type CachedPatches = (u64, Arc<Vec<CfgPatch>>);
+
+/// Keeps the `CfgPatches` of every mod and DLC folder that was looked at,
+/// reading them means opening every PBO so they are only read again when the PBOs change.
+pub struct AddonService {
+    cache: Mutex<HashMap<PathBuf, CachedPatches>>,
+}
+
+impl AddonService {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub async fn cfg_patches(&self, folder: PathBuf) -> Arc<Vec<CfgPatch>> {
+        let fingerprint = {
+            let folder = folder.clone();
+            tokio::task::spawn_blocking(move || arma::addons_fingerprint(&folder))
+                .await
+                .unwrap_or_default()
+        };
+
+        if let Some((cached, patches)) = self.cache.lock().await.get(&folder) {
+            if *cached == fingerprint {
+                return patches.clone();
+            }
+        }
+
+        let patches = {
+            let folder = folder.clone();
+            tokio::task::spawn_blocking(move || arma::read_cfg_patches(&folder))
+                .await
+                .unwrap_or_default()
+        };
+
+        let patches = Arc::new(patches);
+        self.cache.lock().await.insert(folder, (fingerprint, patches.clone()));
+
+        patches
+    }
+
+    /// Compares the `addons[]` of every mission with what the game and the enabled mods of the preset provide.
+    /// Missing addons come with the downloaded workshop items that would provide them.
+    pub async fn check_missions(&self, preset: &Preset, missions: &[Mission]) -> MissionAddonReport {
+        let mut provided = self.game_addons(preset).await;
+
+        for item in preset
+            .items
+            .iter()
+            .filter(|item| item.enabled && !item.blacklisted && !item.optional)
+        {
+            let patches = self.cfg_patches(arma::get_mod_path(item.published_file_id)).await;
+            provided.extend(patches.iter().map(|patch| patch.name.to_lowercase()));
+        }
+
+        let mut providers: Option<HashMap<String, Vec<AddonProvider>>> = None;
+        let mut report = MissionAddonReport::default();
+
+        for mission in missions {
+            let missing = mission
+                .addons
+                .iter()
+                .filter(|addon| !provided.contains(&addon.to_lowercase()))
+                .collect::<Vec<_>>();
+
+            // only look through every downloaded mod once something is actually missing
+            if !missing.is_empty() && providers.is_none() {
+                providers = Some(self.providers(preset).await);
+            }
+
+            let missing = missing
+                .into_iter()
+                .map(|addon| MissingAddon {
+                    addon: addon.clone(),
+                    providers: providers
+                        .as_ref()
+                        .and_then(|providers| providers.get(&addon.to_lowercase()))
+                        .cloned()
+                        .unwrap_or_default(),
+                })
+                .collect::<Vec<_>>();
+
+            report.missions.push(MissionAddonCheck {
+                mission: mission.name.clone(),
+                playable: missing.is_empty(),
+                missing,
+            });
+        }
+
+        report
+    }
+
+    /// Proposes a load order for the enabled mods of the preset based on their `requiredAddons`.
+    pub async fn sort_load_order(&self, preset: &Preset) -> LoadOrderReport {
+        let mut patches = HashMap::new();
+
+        for item in preset
+            .items
+            .iter()
+            .filter(|item| item.enabled && !item.blacklisted && !item.optional)
+        {
+            let found = self.cfg_patches(arma::get_mod_path(item.published_file_id)).await;
+            patches.insert(item.published_file_id, found.as_ref().clone());
+        }
+
+        let game_addons = self.game_addons(preset).await;
+
+        arma::sort_load_order(&preset.items, &patches, &game_addons)
+    }
+
+    /// Looks for PBOs, `CfgPatches` and prefixes that several enabled mods of the preset share.
+    pub async fn find_conflicts(&self, preset: &Preset) -> ConflictReport {
+        let mut patches = HashMap::new();
+        let mut pbos = HashMap::new();
+
+        for item in preset
+            .items
+            .iter()
+            .filter(|item| item.enabled && !item.blacklisted && !item.optional)
+        {
+            let path = arma::get_mod_path(item.published_file_id);
+
+            let found = self.cfg_patches(path.clone()).await;
+            patches.insert(item.published_file_id, found.as_ref().clone());
+
+            let found = tokio::task::spawn_blocking(move || arma::read_pbo_infos(&path))
+                .await
+                .unwrap_or_default();
+            pbos.insert(item.published_file_id, found);
+        }
+
+        arma::find_conflicts(&preset.items, &patches, &pbos)
+    }
+
+    /// The lowercase names of every addon the base game and the enabled DLCs of the preset provide.
+    async fn game_addons(&self, preset: &Preset) -> HashSet<String> {
+        let optional_dlcs = preset.dlcs.iter().map(|dlc| dlc.key.clone()).collect::<Vec<_>>();
+
+        let mut folders = arma::get_game_addon_folders(&optional_dlcs);
+
+        if let Some(arma_path) = paths::get_arma_path() {
+            for dlc in preset.dlcs.iter().filter(|dlc| dlc.enabled) {
+                folders.push(arma_path.join(&dlc.key));
+            }
+        }
+
+        let mut provided = HashSet::new();
+
+        for folder in folders {
+            provided.extend(
+                self.cfg_patches(folder)
+                    .await
+                    .iter()
+                    .map(|patch| patch.name.to_lowercase()),
+            );
+        }
+
+        provided
+    }
+
+    /// Every addon provided by a downloaded workshop item, keyed by the lowercase addon name.
+    async fn providers(&self, preset: &Preset) -> HashMap<String, Vec<AddonProvider>> {
+        let mut providers: HashMap<String, Vec<AddonProvider>> = HashMap::new();
+
+        for published_file_id in arma::get_installed_mods() {
+            let path = arma::get_mod_path(published_file_id);
+
+            let name = preset
+                .items
+                .iter()
+                .find(|item| item.published_file_id == published_file_id)
+                .map(|item| item.name.clone())
+                .or_else(|| arma::read_mod_name(&path))
+                .unwrap_or_else(|| published_file_id.to_string());
+
+            for patch in self.cfg_patches(path).await.iter() {
+                providers
+                    .entry(patch.name.to_lowercase())
+                    .or_default()
+                    .push(AddonProvider {
+                        published_file_id,
+                        name: name.clone(),
+                    });
+            }
+        }
+
+        providers
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use api_schema::response::{Announcement, AnnouncementSchedule};
+
+use crate::{
+    repository::{AnnouncementRepository, RestartScheduleRepository},
+    service::{next_restart, InstanceService, State, StatusService},
+};
+
+/// How often the announcements are checked, one-off announcements go out at most that late.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Sends the announcements of the running servers to everyone on them.
+pub struct AnnouncementService {
+    repository: AnnouncementRepository,
+    restart_schedule_repository: RestartScheduleRepository,
+}
+
+impl AnnouncementService {
+    pub fn new(
+        repository: AnnouncementRepository,
+        restart_schedule_repository: RestartScheduleRepository,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            repository,
+            restart_schedule_repository,
+        })
+    }
+
+    /// Recurring announcements start counting when the server is seen running and start over after it stopped.
+    /// One-off announcements and restart warnings that came due while the server was stopped are skipped.
+    pub fn start(self: &Arc<Self>, instances: Arc<InstanceService>, status: Arc<StatusService>) {
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            // the next time each recurring announcement is due, with the interval it was planned with
+            let mut due: HashMap<i64, (i64, Instant)> = HashMap::new();
+            let mut last_check = now();
+
+            loop {
+                tokio::time::sleep(CHECK_INTERVAL).await;
+
+                let announcements = match service.repository.get_enabled().await {
+                    Ok(announcements) => announcements,
+                    Err(e) => {
+                        tracing::error!("Failed to load the announcements: {}", e);
+                        continue;
+                    }
+                };
+
+                let restarts = match service.restart_schedule_repository.get_all().await {
+                    Ok(restarts) => restarts,
+                    Err(e) => {
+                        tracing::error!("Failed to load the restart schedules: {}", e);
+                        continue;
+                    }
+                };
+
+                let check = now();
+                let instant = Instant::now();
+                let mut running = HashMap::new();
+
+                for instance_id in announcements.iter().map(|announcement| announcement.instance_id) {
+                    if let Entry::Vacant(entry) = running.entry(instance_id) {
+                        entry.insert(status.arma(instance_id).await == State::Running);
+                    }
+                }
+
+                due.retain(|id, _| {
+                    announcements
+                        .iter()
+                        .any(|announcement| announcement.id == *id && running[&announcement.instance_id])
+                });
+
+                for announcement in announcements {
+                    if !running[&announcement.instance_id] {
+                        continue;
+                    }
+
+                    let send = match announcement.schedule {
+                        AnnouncementSchedule::Interval { minutes } => {
+                            let planned = due.get(&announcement.id).filter(|(planned, _)| *planned == minutes);
+                            let send = planned.is_some_and(|(_, at)| *at <= instant);
+
+                            if planned.is_none() || send {
+                                let interval = Duration::from_secs((minutes.max(1) as u64).saturating_mul(60));
+
+                                // an interval this long never comes up, announcements stored before the limit included
+                                match instant.checked_add(interval) {
+                                    Some(at) => due.insert(announcement.id, (minutes, at)),
+                                    None => due.remove(&announcement.id),
+                                };
+                            }
+
+                            send
+                        }
+                        AnnouncementSchedule::At { timestamp } => last_check < timestamp && timestamp <= check,
+                        AnnouncementSchedule::BeforeRestart { minutes } => {
+                            let ahead = minutes.saturating_mul(60);
+
+                            restarts
+                                .get(&announcement.instance_id)
+                                .and_then(|restarts| next_restart(restarts, last_check.saturating_add(ahead)))
+                                .is_some_and(|restart| restart <= check.saturating_add(ahead))
+                        }
+                    };
+
+                    if send {
+                        service.send(&instances, &announcement).await;
+                    }
+                }
+
+                last_check = check;
+            }
+        });
+    }
+
+    async fn send(&self, instances: &InstanceService, announcement: &Announcement) {
+        let Some(server) = instances.get(announcement.instance_id).await else {
+            return;
+        };
+
+        if let Err(e) = server.rcon.command(&format!("say -1 {}", announcement.message)).await {
+            tracing::warn!(
+                "Failed to send announcement {} of instance {}: {}",
+                announcement.id,
+                announcement.instance_id,
+                e
+            );
+        }
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs() as i64)
+        .unwrap_or_default()
+}
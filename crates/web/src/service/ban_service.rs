@@ -0,0 +1,165 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use api_schema::response::{Ban, BanKind};
+use tokio::sync::Mutex;
+
+use crate::{
+    repository::{BanRepository, NewBan},
+    service::{InstanceService, State, StatusService},
+};
+
+/// Keeps `ban.txt` and the BattlEye `bans.txt` of every instance in line with the database.
+pub struct BanService {
+    repository: BanRepository,
+    /// What was last written to each file, anything else in there was banned on the server itself.
+    written: Mutex<HashMap<PathBuf, HashSet<String>>>,
+}
+
+impl BanService {
+    pub fn new(repository: BanRepository) -> Arc<Self> {
+        Arc::new(Self {
+            repository,
+            written: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Bans someone on every instance, replacing an earlier ban of them.
+    pub async fn add(
+        &self,
+        ban: NewBan,
+        instances: &InstanceService,
+        status: &StatusService,
+    ) -> Result<Ban, Box<dyn std::error::Error + Send + Sync>> {
+        let ban = self.repository.add(ban).await.map_err(|e| e.to_string())?;
+        self.sync(instances, status).await?;
+
+        Ok(ban)
+    }
+
+    /// Takes over the bans added on the servers since the last sync, then writes the files
+    /// and lets BattlEye reload them on the servers that are running.
+    /// The engine only reads `ban.txt` when the server starts.
+    pub async fn sync(
+        &self,
+        instances: &InstanceService,
+        status: &StatusService,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut written = self.written.lock().await;
+
+        let mut servers = Vec::new();
+        for instance in instances.get_all().await {
+            servers.extend(instances.get(instance.id).await);
+        }
+
+        let engine_path = arma::get_engine_bans_path().filter(|path| path.parent().is_some_and(Path::exists));
+        let mut found = Vec::new();
+
+        if let Some(path) = &engine_path {
+            let known = written.get(path);
+
+            found.extend(
+                arma::parse_engine_bans(&std::fs::read_to_string(path).unwrap_or_default())
+                    .into_iter()
+                    .filter(|uid| !known.is_some_and(|known| known.contains(uid)))
+                    .map(|uid| NewBan {
+                        kind: BanKind::Uid,
+                        value: uid,
+                        expires_at: None,
+                        reason: String::new(),
+                        author: "Server".to_string(),
+                    }),
+            );
+        }
+
+        for server in &servers {
+            let path = arma::get_battleye_bans_path(&server.profiles_path());
+            let known = written.get(&path);
+
+            found.extend(
+                arma::parse_battleye_bans(&std::fs::read_to_string(&path).unwrap_or_default())
+                    .into_iter()
+                    .filter(|ban| !known.is_some_and(|known| known.contains(&ban.target)))
+                    .filter(|ban| ban.expires_at.is_none_or(|expires_at| expires_at > now()))
+                    .map(|ban| NewBan {
+                        kind: if arma::is_ip(&ban.target) {
+                            BanKind::Ip
+                        } else {
+                            BanKind::Guid
+                        },
+                        value: ban.target,
+                        expires_at: ban.expires_at,
+                        reason: ban.reason,
+                        author: "BattlEye".to_string(),
+                    }),
+            );
+        }
+
+        if !found.is_empty() {
+            self.repository.add_missing(found).await.map_err(|e| e.to_string())?;
+        }
+
+        let bans = self.repository.get_active().await.map_err(|e| e.to_string())?;
+
+        if let Some(path) = engine_path {
+            let uids = engine_bans(&bans);
+            std::fs::write(&path, arma::format_engine_bans(&uids))?;
+            written.insert(path, uids.into_iter().collect());
+        }
+
+        let battleye = battleye_bans(&bans);
+
+        for server in &servers {
+            let path = arma::get_battleye_bans_path(&server.profiles_path());
+
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            std::fs::write(&path, arma::format_battleye_bans(&battleye))?;
+            written.insert(path, battleye.iter().map(|ban| ban.target.clone()).collect());
+
+            if status.arma(server.id()).await == State::Running {
+                if let Err(e) = server.rcon.command("loadBans").await {
+                    tracing::warn!("Failed to reload the bans of {}: {}", server.instance.name, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn engine_bans(bans: &[Ban]) -> Vec<String> {
+    bans.iter()
+        .filter(|ban| ban.kind == BanKind::Uid)
+        .map(|ban| ban.value.clone())
+        .collect()
+}
+
+pub fn battleye_bans(bans: &[Ban]) -> Vec<arma::BattlEyeBan> {
+    bans.iter()
+        .filter(|ban| ban.kind != BanKind::Uid)
+        .map(|ban| arma::BattlEyeBan {
+            target: ban.value.clone(),
+            expires_at: ban.expires_at,
+            reason: ban.reason.clone(),
+        })
+        .collect()
+}
+
+/// When a ban for the given minutes runs out, 0 bans permanently like BattlEye does.
+pub fn ban_expiry(minutes: i64) -> Option<i64> {
+    (minutes > 0).then(|| now() + minutes * 60)
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default()
+}
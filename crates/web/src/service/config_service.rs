@@ -1,30 +1,44 @@
+use std::path::PathBuf;
+
 use axum::response::sse::Event;
 use tokio::sync::broadcast;
 
 #[derive(Clone)]
 pub struct ConfigService {
     tx: broadcast::Sender<Event>,
+    config_file: PathBuf,
+    profile_file: PathBuf,
 }
 
-impl Default for ConfigService {
-    fn default() -> Self {
+impl ConfigService {
+    pub fn new(config_file: PathBuf, profile_file: PathBuf) -> Self {
         Self {
             tx: broadcast::channel(100).0,
+            config_file,
+            profile_file,
         }
     }
-}
-
-impl ConfigService {
-    pub fn new() -> Self {
-        Self::default()
-    }
 
     pub fn subscribe(&self) -> broadcast::Receiver<Event> {
         self.tx.subscribe()
     }
 
+    /// Resolves a channel to the file of this instance, channels are named after the default files.
+    pub fn path(&self, channel: &str) -> Option<PathBuf> {
+        match channel {
+            "server.cfg" => Some(self.config_file.clone()),
+            "profile.cfg" => Some(self.profile_file.clone()),
+            _ => None,
+        }
+    }
+
     pub async fn update_config(&self, channel: String, body: String) -> Result<(), std::io::Error> {
-        let config_file = paths::get_config_path().join(channel.clone());
+        let Some(config_file) = self.path(&channel) else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Unknown config {}", channel),
+            ));
+        };
 
         if !config_file.exists() {
             return Err(std::io::Error::new(
@@ -0,0 +1,389 @@
+use std::{collections::HashMap, net::IpAddr, path::PathBuf, sync::Arc};
+
+use api_schema::{request::*, response::Instance};
+use tokio::sync::RwLock;
+
+use crate::{
+    repository::InstanceRepository,
+    service::{A2sService, ConfigService, LogService, RconService, StatusService},
+};
+
+/// The ports a server occupies, starting at the game port.
+const PORT_RANGE: i64 = 5;
+
+/// Each headless client is a full game process, so don't let it get out of hand.
+const MAX_HEADLESS_CLIENTS: i64 = 10;
+
+/// An instance together with the services that only make sense for that instance.
+pub struct ServerInstance {
+    pub instance: Instance,
+    pub log: LogService,
+    pub a2s: Arc<A2sService>,
+    pub rcon: Arc<RconService>,
+    pub config: ConfigService,
+}
+
+impl ServerInstance {
+    fn new(instance: Instance, status: &Arc<StatusService>) -> Self {
+        let config_path = paths::get_config_path();
+        let config_file = config_path.join(&instance.config_file);
+        let profile_file = config_path.join(&instance.profile_file);
+
+        // create the files up front so they can be edited before the first start
+        if let Err(e) = arma::prepare_config(&config_file).and_then(|_| arma::prepare_profile(&profile_file)) {
+            tracing::error!("Failed to prepare config for {}: {}", instance.name, e);
+        }
+
+        let log = LogService::new();
+        let profiles = paths::get_instance_path(&instance.name);
+        log.register("arma", profiles.join("*.rpt"));
+
+        for index in 1..=instance.headless_clients as usize {
+            log.register(
+                arma::headless_client_name(index),
+                arma::headless_client_profiles(&profiles, index).join("*.rpt"),
+            );
+        }
+
+        let a2s = A2sService::new(A2sService::target(
+            instance.port as u16,
+            instance.query_ip.as_deref().and_then(|ip| ip.parse().ok()),
+            instance.query_port.map(|port| port as u16),
+        ));
+        a2s.start(instance.id, status.clone());
+
+        let rcon = RconService::new(profiles, instance.port as u16);
+
+        let config = ConfigService::new(config_file, profile_file);
+
+        Self {
+            instance,
+            log,
+            a2s,
+            rcon,
+            config,
+        }
+    }
+
+    pub fn id(&self) -> i64 {
+        self.instance.id
+    }
+
+    /// The `-profiles` directory, which also identifies the running server.
+    pub fn profiles_path(&self) -> PathBuf {
+        paths::get_instance_path(&self.instance.name)
+    }
+
+    /// The server for this instance, without any mods.
+    pub fn server(&self) -> arma::Arma3 {
+        let config_path = paths::get_config_path();
+
+        let parameters = if self.instance.parameters.is_empty() {
+            arma::get_default_parameters()
+        } else {
+            self.instance.parameters.clone()
+        };
+
+        arma::Arma3::new()
+            .name(self.instance.profile_name.clone())
+            .port(self.instance.port as u16)
+            .config_file(config_path.join(&self.instance.config_file))
+            .profile_file(config_path.join(&self.instance.profile_file))
+            .profiles(self.profiles_path())
+            .parameters(parameters)
+            .headless_clients(self.instance.headless_clients as usize)
+            .battleye(self.battleye())
+    }
+
+    /// The BattlEye config written on every start, RCon is always enabled so the manager can use it.
+    fn battleye(&self) -> arma::BattlEyeConfig {
+        arma::BattlEyeConfig {
+            rcon: arma::RconSettings {
+                password: self.instance.rcon_password.clone(),
+                port: self.instance.rcon_port.map(|port| port as u16),
+                ip: self.instance.rcon_ip.as_deref().and_then(|ip| ip.parse().ok()),
+            },
+            max_ping: self.instance.max_ping.map(|max_ping| max_ping as u32),
+        }
+    }
+
+    fn stop(&self) {
+        self.log.stop();
+        self.a2s.stop();
+    }
+}
+
+pub struct InstanceService {
+    repository: InstanceRepository,
+    status: Arc<StatusService>,
+    instances: RwLock<HashMap<i64, Arc<ServerInstance>>>,
+}
+
+impl InstanceService {
+    pub async fn new(
+        repository: InstanceRepository,
+        status: Arc<StatusService>,
+    ) -> Result<Arc<Self>, Box<dyn std::error::Error>> {
+        let instances = repository
+            .get_all()
+            .await?
+            .into_iter()
+            .map(|instance| (instance.id, Arc::new(ServerInstance::new(instance, &status))))
+            .collect();
+
+        Ok(Arc::new(Self {
+            repository,
+            status,
+            instances: RwLock::new(instances),
+        }))
+    }
+
+    pub async fn get(&self, id: i64) -> Option<Arc<ServerInstance>> {
+        self.instances.read().await.get(&id).cloned()
+    }
+
+    pub async fn get_all(&self) -> Vec<Instance> {
+        let mut instances = self
+            .instances
+            .read()
+            .await
+            .values()
+            .map(|server| server.instance.clone())
+            .collect::<Vec<_>>();
+
+        instances.sort_by_key(|instance| instance.id);
+
+        instances
+    }
+
+    pub async fn create(&self, schema: CreateInstanceSchema) -> Result<Instance, Box<dyn std::error::Error>> {
+        self.validate(
+            None,
+            &schema.name,
+            schema.profile_name.as_deref(),
+            schema.port,
+            schema.headless_clients,
+        )
+        .await?;
+        self.validate_battleye(None, schema.rcon_port, schema.rcon_ip.as_deref(), schema.max_ping)
+            .await?;
+        validate_query(schema.query_port, schema.query_ip.as_deref())?;
+
+        let instance = self.repository.create(schema).await?;
+
+        self.instances.write().await.insert(
+            instance.id,
+            Arc::new(ServerInstance::new(instance.clone(), &self.status)),
+        );
+
+        Ok(instance)
+    }
+
+    /// Replaces the instance and restarts its services, the server itself must not be running.
+    /// Renaming the instance moves its folder along, so logs, bans and the BattlEye config stay with it.
+    pub async fn update(&self, schema: UpdateInstanceSchema) -> Result<Instance, Box<dyn std::error::Error>> {
+        self.validate(
+            Some(schema.id),
+            &schema.name,
+            Some(&schema.profile_name),
+            schema.port,
+            schema.headless_clients,
+        )
+        .await?;
+        self.validate_battleye(
+            Some(schema.id),
+            schema.rcon_port,
+            schema.rcon_ip.as_deref(),
+            schema.max_ping,
+        )
+        .await?;
+        validate_query(schema.query_port, schema.query_ip.as_deref())?;
+
+        let mut instances = self.instances.write().await;
+        let id = schema.id;
+
+        // the log watchers hold on to the folder, so they have to let go before it can be moved
+        let old = instances.remove(&id);
+
+        if let Some(old) = &old {
+            old.stop();
+        }
+
+        match self.rename_and_update(old.as_ref(), schema).await {
+            Ok(instance) => {
+                instances.insert(id, Arc::new(ServerInstance::new(instance.clone(), &self.status)));
+                Ok(instance)
+            }
+            Err(e) => {
+                if let Some(old) = old {
+                    instances.insert(id, Arc::new(ServerInstance::new(old.instance.clone(), &self.status)));
+                }
+
+                Err(e)
+            }
+        }
+    }
+
+    async fn rename_and_update(
+        &self,
+        old: Option<&Arc<ServerInstance>>,
+        schema: UpdateInstanceSchema,
+    ) -> Result<Instance, Box<dyn std::error::Error>> {
+        let moved = match old {
+            Some(old) if old.instance.name != schema.name => {
+                Some(move_instance_path(&old.instance.name, &schema.name)?)
+            }
+            _ => None,
+        };
+
+        let result = self.repository.update(schema).await;
+
+        if let (Err(_), Some((from, to))) = (&result, moved) {
+            if let Err(e) = std::fs::rename(&to, &from) {
+                tracing::error!("Failed to move {} back to {}: {}", to.display(), from.display(), e);
+            }
+        }
+
+        result
+    }
+
+    pub async fn delete(&self, id: i64) -> Result<(), Box<dyn std::error::Error>> {
+        self.repository.delete(id).await?;
+
+        if let Some(old) = self.instances.write().await.remove(&id) {
+            old.stop();
+        }
+
+        Ok(())
+    }
+
+    async fn validate(
+        &self,
+        id: Option<i64>,
+        name: &str,
+        profile_name: Option<&str>,
+        port: i64,
+        headless_clients: i64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !is_valid_name(name) {
+            return Err("Instance names may only contain letters, numbers, - and _".into());
+        }
+
+        // the profile name is part of the profile path and the command line
+        if profile_name.is_some_and(|profile_name| !is_valid_name(profile_name)) {
+            return Err("Profile names may only contain letters, numbers, - and _".into());
+        }
+
+        if !(1..=(u16::MAX as i64 - PORT_RANGE)).contains(&port) {
+            return Err(format!("Invalid port {}", port).into());
+        }
+
+        if !(0..=MAX_HEADLESS_CLIENTS).contains(&headless_clients) {
+            return Err(format!("An instance can have at most {} headless clients", MAX_HEADLESS_CLIENTS).into());
+        }
+
+        let instances = self.instances.read().await;
+
+        for other in instances.values().filter(|other| Some(other.id()) != id) {
+            if other.instance.name.eq_ignore_ascii_case(name) {
+                return Err(format!("An instance named {} already exists", name).into());
+            }
+
+            if (other.instance.port - port).abs() < PORT_RANGE {
+                return Err(format!(
+                    "Ports {}-{} overlap with instance {}",
+                    port,
+                    port + PORT_RANGE - 1,
+                    other.instance.name
+                )
+                .into());
+            }
+
+            if other
+                .instance
+                .rcon_port
+                .is_some_and(|rcon_port| (port..port + PORT_RANGE).contains(&rcon_port))
+            {
+                return Err(format!("Port {} is used by the RCon of instance {}", port, other.instance.name).into());
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn validate_battleye(
+        &self,
+        id: Option<i64>,
+        rcon_port: Option<i64>,
+        rcon_ip: Option<&str>,
+        max_ping: Option<i64>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(ip) = rcon_ip {
+            if ip.parse::<IpAddr>().is_err() {
+                return Err(format!("Invalid RCon IP {}", ip).into());
+            }
+        }
+
+        if let Some(max_ping) = max_ping {
+            if !(1..=u32::MAX as i64).contains(&max_ping) {
+                return Err(format!("Invalid max ping {}", max_ping).into());
+            }
+        }
+
+        let Some(rcon_port) = rcon_port else {
+            return Ok(());
+        };
+
+        if !(1..=u16::MAX as i64).contains(&rcon_port) {
+            return Err(format!("Invalid RCon port {}", rcon_port).into());
+        }
+
+        let instances = self.instances.read().await;
+
+        for other in instances.values().filter(|other| Some(other.id()) != id) {
+            let other_ports = other.instance.port..other.instance.port + PORT_RANGE;
+
+            if other_ports.contains(&rcon_port) || other.instance.rcon_port == Some(rcon_port) {
+                return Err(format!("RCon port {} is used by instance {}", rcon_port, other.instance.name).into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Names end up in paths and on the command line, so keep them boring.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Moves the folder of an instance to its new name, returning where it came from and went.
+fn move_instance_path(old_name: &str, new_name: &str) -> Result<(PathBuf, PathBuf), Box<dyn std::error::Error>> {
+    let from = paths::get_instance_path(old_name);
+    let to = from.with_file_name(new_name);
+
+    // a left over empty folder can be replaced, changing only the case is the same folder on Windows
+    if to.exists() && !old_name.eq_ignore_ascii_case(new_name) && std::fs::remove_dir(&to).is_err() {
+        return Err(format!("The folder {} is already in use", to.display()).into());
+    }
+
+    std::fs::rename(&from, &to).map_err(|e| format!("Failed to move {} to {}: {}", from.display(), to.display(), e))?;
+
+    Ok((from, to))
+}
+
+fn validate_query(query_port: Option<i64>, query_ip: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(ip) = query_ip {
+        if ip.parse::<IpAddr>().is_err() {
+            return Err(format!("Invalid query IP {}", ip).into());
+        }
+    }
+
+    if let Some(query_port) = query_port {
+        if !(1..=u16::MAX as i64).contains(&query_port) {
+            return Err(format!("Invalid query port {}", query_port).into());
+        }
+    }
+
+    Ok(())
+}
@@ -2,7 +2,7 @@ use std::{
     collections::HashMap,
     io::{Read, SeekFrom},
     path::{Path, PathBuf},
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
 };
 
 use axum::response::sse::Event;
@@ -11,6 +11,7 @@ use notify::{RecursiveMode, Result as NResult, Watcher};
 use tokio::{
     io::{AsyncReadExt, AsyncSeekExt},
     sync::broadcast,
+    task::JoinHandle,
 };
 
 use futures::{channel::mpsc::unbounded, SinkExt, StreamExt};
@@ -19,6 +20,7 @@ use futures::{channel::mpsc::unbounded, SinkExt, StreamExt};
 pub struct LogService {
     map: Arc<RwLock<HashMap<String, WatchOptions>>>,
     tx: broadcast::Sender<Event>,
+    handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
 }
 
 impl Default for LogService {
@@ -26,6 +28,7 @@ impl Default for LogService {
         Self {
             map: Arc::new(RwLock::new(HashMap::new())),
             tx: broadcast::channel(100).0,
+            handles: Arc::new(Mutex::new(Vec::new())),
         }
     }
 }
@@ -43,6 +46,13 @@ impl LogService {
         self.tx.subscribe()
     }
 
+    /// Stops watching all registered channels.
+    pub fn stop(&self) {
+        for handle in self.handles.lock().unwrap().drain(..) {
+            handle.abort();
+        }
+    }
+
     pub fn get_latest(&self, channel_name: String) -> Vec<String> {
         let map = self.map.read().unwrap();
         let Some(watch_options) = map.get(&channel_name) else {
@@ -123,7 +133,7 @@ impl LogService {
         .expect("watcher");
 
         let tx = self.tx.clone();
-        tokio::spawn(async move {
+        let handle = tokio::spawn(async move {
             if let Err(e) = watcher.watch(watch_options.path.as_path(), RecursiveMode::Recursive) {
                 println!("watch error: {:?}", e);
                 return;
@@ -152,6 +162,8 @@ impl LogService {
                 }
             }
         });
+
+        self.handles.lock().unwrap().push(handle);
     }
 }
 
@@ -0,0 +1,117 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use api_schema::response::ServerMessage;
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    task::JoinHandle,
+};
+
+use crate::{
+    repository::MessageRepository,
+    service::{InstanceService, RconService, State, StatusService},
+};
+
+/// How often the running servers are checked for one without a listener,
+/// and how long a listener waits for a message before checking the connection.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Listens to the messages of every running server over RCon and keeps them.
+pub struct MessageService {
+    repository: MessageRepository,
+    tx: broadcast::Sender<ServerMessage>,
+}
+
+impl MessageService {
+    pub fn new(repository: MessageRepository) -> Arc<Self> {
+        Arc::new(Self {
+            repository,
+            tx: broadcast::channel(100).0,
+        })
+    }
+
+    /// The messages of all instances as they are stored.
+    pub fn subscribe(&self) -> broadcast::Receiver<ServerMessage> {
+        self.tx.subscribe()
+    }
+
+    /// Starts a listener for every server once it is running and stops it again with the server.
+    /// An instance that is updated gets a new RCon connection, the listener follows it.
+    pub fn start(self: &Arc<Self>, instances: Arc<InstanceService>, status: Arc<StatusService>) {
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            let mut listeners: HashMap<i64, (Arc<RconService>, JoinHandle<()>)> = HashMap::new();
+
+            loop {
+                let mut running = HashMap::new();
+
+                for instance in instances.get_all().await {
+                    if status.arma(instance.id).await != State::Running {
+                        continue;
+                    }
+
+                    if let Some(server) = instances.get(instance.id).await {
+                        running.insert(instance.id, server.rcon.clone());
+                    }
+                }
+
+                listeners.retain(|id, (rcon, handle)| {
+                    let keep = !handle.is_finished() && running.get(id).is_some_and(|other| Arc::ptr_eq(rcon, other));
+
+                    if !keep {
+                        handle.abort();
+                    }
+
+                    keep
+                });
+
+                for (id, rcon) in running {
+                    if listeners.contains_key(&id) {
+                        continue;
+                    }
+
+                    let handle = tokio::spawn(service.clone().listen(id, rcon.clone()));
+                    listeners.insert(id, (rcon, handle));
+                }
+
+                tokio::time::sleep(CHECK_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Returns once the connection is gone, the next check starts a new listener.
+    async fn listen(self: Arc<Self>, instance_id: i64, rcon: Arc<RconService>) {
+        let client = match rcon.client().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::debug!("RCon of instance {} is not reachable yet: {}", instance_id, e);
+                return;
+            }
+        };
+
+        let mut messages = client.subscribe();
+
+        loop {
+            match tokio::time::timeout(CHECK_INTERVAL, messages.recv()).await {
+                Ok(Ok(message)) => self.store(instance_id, &message).await,
+                Ok(Err(RecvError::Lagged(skipped))) => {
+                    tracing::warn!("Missed {} messages of instance {}", skipped, instance_id);
+                }
+                Ok(Err(RecvError::Closed)) => return,
+                Err(_) if !client.is_connected() => return,
+                Err(_) => {}
+            }
+        }
+    }
+
+    async fn store(&self, instance_id: i64, message: &str) {
+        let message = rcon::parse_message(message);
+
+        match self.repository.add(instance_id, &message).await {
+            Ok(message) => {
+                let _ = self.tx.send(message);
+            }
+            Err(e) => tracing::error!("Failed to store a message of instance {}: {}", instance_id, e),
+        }
+    }
+}
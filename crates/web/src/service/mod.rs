@@ -1,11 +1,31 @@
 mod a2s_service;
+mod addon_service;
+mod announcement_service;
+mod ban_service;
 mod config_service;
+mod instance_service;
 mod log_service;
+mod message_service;
 mod preset_service;
+mod rcon_service;
+mod restart_service;
+mod server_service;
+mod session_service;
 mod status_service;
+mod whitelist_service;
 
 pub use a2s_service::*;
+pub use addon_service::*;
+pub use announcement_service::*;
+pub use ban_service::*;
 pub use config_service::*;
+pub use instance_service::*;
 pub use log_service::*;
+pub use message_service::*;
 pub use preset_service::*;
+pub use rcon_service::*;
+pub use restart_service::*;
+pub use server_service::*;
+pub use session_service::*;
 pub use status_service::*;
+pub use whitelist_service::*;
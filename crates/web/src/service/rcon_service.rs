@@ -0,0 +1,70 @@
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use rcon::{Player, RconClient, RconError};
+use tokio::sync::Mutex;
+
+/// The RCon connection of one instance, connected on first use and again whenever the server dropped it.
+pub struct RconService {
+    profiles: PathBuf,
+    port: u16,
+    client: Mutex<Option<Arc<RconClient>>>,
+}
+
+impl RconService {
+    pub fn new(profiles: PathBuf, port: u16) -> Arc<Self> {
+        Arc::new(Self {
+            profiles,
+            port,
+            client: Mutex::new(None),
+        })
+    }
+
+    /// The current connection, connecting first if there is none or it stopped answering.
+    pub async fn client(&self) -> Result<Arc<RconClient>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut client = self.client.lock().await;
+
+        if let Some(client) = client.as_ref().filter(|client| client.is_connected()) {
+            return Ok(client.clone());
+        }
+
+        // the config is written on start, the running server may still use the one from an earlier start
+        let settings = arma::read_rcon_settings(&self.profiles)
+            .ok_or("RCon is not enabled, restart the server to generate its BattlEye config")?;
+
+        // BattlEye listens on every interface unless RConIP says otherwise
+        let ip = settings
+            .ip
+            .filter(|ip| !ip.is_unspecified())
+            .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let port = settings.port.unwrap_or(self.port);
+
+        let connected = Arc::new(RconClient::connect((ip, port), &settings.password).await?);
+        *client = Some(connected.clone());
+
+        Ok(connected)
+    }
+
+    /// Runs an RCon command, a connection that stopped answering is dropped so the next command reconnects.
+    pub async fn command(&self, command: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let client = self.client().await?;
+
+        match client.command(command).await {
+            Ok(response) => Ok(response),
+            Err(e) => {
+                if matches!(e, RconError::Timeout | RconError::Disconnected) {
+                    self.client.lock().await.take();
+                }
+
+                Err(e.into())
+            }
+        }
+    }
+
+    pub async fn players(&self) -> Result<Vec<Player>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(rcon::parse_players(&self.command("players").await?))
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    repository::RestartScheduleRepository,
+    service::{ServerService, State, StatusService},
+};
+
+/// How often the schedules are checked, a restart happens at most that late.
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+const DAY: i64 = 24 * 60 * 60;
+
+/// Restarts the running servers at the times of day their instance is scheduled to.
+pub struct RestartService {
+    repository: RestartScheduleRepository,
+    servers: Arc<ServerService>,
+}
+
+impl RestartService {
+    pub fn new(repository: RestartScheduleRepository, servers: Arc<ServerService>) -> Arc<Self> {
+        Arc::new(Self { repository, servers })
+    }
+
+    /// A server that is stopped at a scheduled time stays stopped.
+    pub fn start(self: &Arc<Self>, status: Arc<StatusService>) {
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            let mut last_check = now();
+
+            loop {
+                tokio::time::sleep(CHECK_INTERVAL).await;
+
+                let schedules = match service.repository.get_all().await {
+                    Ok(schedules) => schedules,
+                    Err(e) => {
+                        tracing::error!("Failed to load the restart schedules: {}", e);
+                        continue;
+                    }
+                };
+
+                let check = now();
+
+                for (instance, minutes) in schedules {
+                    let due = next_restart(&minutes, last_check).is_some_and(|restart| restart <= check);
+
+                    if !due || status.arma(instance).await != State::Running {
+                        continue;
+                    }
+
+                    tracing::info!("Restarting instance {} as scheduled", instance);
+
+                    let servers = service.servers.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = servers.restart(instance).await {
+                            tracing::error!("Failed to restart instance {}: {}", instance, e);
+                        }
+                    });
+                }
+
+                last_check = check;
+            }
+        });
+    }
+}
+
+/// The first restart after the unix timestamp, `minutes` are the times of day in minutes after midnight UTC.
+pub fn next_restart(minutes: &[i64], after: i64) -> Option<i64> {
+    let today = after.div_euclid(DAY) * DAY;
+
+    [today, today + DAY]
+        .into_iter()
+        .flat_map(|day| minutes.iter().map(move |minute| day + minute * 60))
+        .filter(|restart| *restart > after)
+        .min()
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs() as i64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,291 @@
+use std::{sync::Arc, time::Duration};
+
+use api_schema::response::{PreflightReport, Preset, RotationMission};
+use process::ProcessControls;
+use tokio::sync::Mutex;
+
+use crate::{
+    repository::{KeyRepository, MissionRotationRepository, PresetRepository},
+    service::{BanService, InstanceService, ServerInstance, State, StatusService},
+};
+
+/// How long the server gets to start before the headless clients try to connect.
+const HEADLESS_CLIENT_DELAY: Duration = Duration::from_secs(10);
+
+/// How long a server gets to shut down before it is killed.
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Starts and stops the servers of the instances, along with their headless clients.
+pub struct ServerService {
+    status: Arc<StatusService>,
+    instances: Arc<InstanceService>,
+    preset_repository: PresetRepository,
+    mission_rotation_repository: MissionRotationRepository,
+    key_repository: KeyRepository,
+    bans: Arc<BanService>,
+}
+
+impl ServerService {
+    pub fn new(
+        status: Arc<StatusService>,
+        instances: Arc<InstanceService>,
+        preset_repository: PresetRepository,
+        mission_rotation_repository: MissionRotationRepository,
+        key_repository: KeyRepository,
+        bans: Arc<BanService>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            status,
+            instances,
+            preset_repository,
+            mission_rotation_repository,
+            key_repository,
+            bans,
+        })
+    }
+
+    /// Runs the preflight checks, installs the keys and launches the server.
+    pub async fn start(&self, instance: i64) -> Result<(), String> {
+        let server = self
+            .instances
+            .get(instance)
+            .await
+            .ok_or_else(|| format!("Instance {} does not exist", instance))?;
+
+        if self.status.arma(instance).await != State::Stopped {
+            return Err("Arma is already running".to_string());
+        }
+
+        self.status.set_arma(instance, State::Starting).await;
+
+        let result = self.launch(&server).await;
+
+        if result.is_err() {
+            self.status.set_arma(instance, State::Stopped).await;
+        }
+
+        result
+    }
+
+    async fn launch(&self, server: &ServerInstance) -> Result<(), String> {
+        let instance = server.id();
+
+        let Ok(Some(preset)) = self.preset_repository.get_instance_preset(&server.instance).await else {
+            return Err("No preset selected".to_string());
+        };
+
+        let Ok(missions) = self.mission_rotation_repository.get(instance).await else {
+            return Err("Failed to load the mission rotation".to_string());
+        };
+
+        let Ok(managed_keys) = self.key_repository.get_installed().await else {
+            return Err("Failed to load the installed keys".to_string());
+        };
+
+        let arma = prepare_launch(server, &preset, &missions, &managed_keys).await?;
+
+        // the keys folder is shared by all instances, the server only reads it on startup
+        self.install_keys(&preset).await?;
+
+        // the engine only reads its bans on startup, a server without its ban files shouldn't be kept from starting though
+        if let Err(e) = self.bans.sync(&self.instances, &self.status).await {
+            tracing::warn!("Failed to sync the bans: {}", e);
+        }
+
+        let headless_clients = arma.build_headless_clients();
+        let c = arma.run().map_err(|e| format!("{}", e))?;
+
+        let clients = start_headless_clients(self.status.clone(), instance, headless_clients);
+
+        let a_status = self.status.clone();
+        tokio::spawn(async move {
+            a_status.set_arma(instance, State::Running).await;
+
+            loop {
+                if a_status.arma(instance).await == State::Stopping {
+                    // the headless clients go first, they would only try to reconnect otherwise
+                    for client in clients.lock().await.iter() {
+                        client.kill();
+                    }
+
+                    c.kill();
+                }
+
+                match c.next().await {
+                    Ok(Some(_)) => {}
+                    Ok(None) => {
+                        break;
+                    }
+                    Err(_) => {}
+                }
+            }
+
+            for client in clients.lock().await.iter() {
+                client.kill();
+            }
+
+            a_status.set_arma(instance, State::Stopped).await;
+        });
+
+        Ok(())
+    }
+
+    async fn install_keys(&self, preset: &Preset) -> Result<(), String> {
+        let managed = self
+            .key_repository
+            .get_installed()
+            .await
+            .map_err(|e| format!("Database Error: {}", e))?;
+
+        let preset = preset.clone();
+
+        let installed = tokio::task::spawn_blocking(move || arma::install_keys(&preset, &managed))
+            .await
+            .map_err(|e| format!("Failed to install keys: {}", e))?
+            .map_err(|e| format!("Failed to install keys: {}", e))?;
+
+        self.key_repository
+            .set_installed(&installed)
+            .await
+            .map_err(|e| format!("Database Error: {}", e))
+    }
+
+    /// Asks the server to stop, it is killed if it doesn't within a few seconds.
+    pub async fn stop(&self, instance: i64) -> Result<(), String> {
+        let server = self
+            .instances
+            .get(instance)
+            .await
+            .ok_or_else(|| format!("Instance {} does not exist", instance))?;
+
+        if self.status.arma(instance).await == State::Stopped {
+            return Err("Arma is not running".to_string());
+        }
+
+        self.status.set_arma(instance, State::Stopping).await;
+
+        let status = self.status.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(STOP_TIMEOUT).await;
+
+            if status.arma(instance).await != State::Stopped {
+                for index in 1..=server.instance.headless_clients as usize {
+                    arma::kill(&arma::headless_client_profiles(&server.profiles_path(), index));
+                }
+
+                arma::kill(&server.profiles_path());
+                status.set_arma(instance, State::Stopped).await;
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Stops the server and starts it again once it is down, with the preset and rotation as they are now.
+    pub async fn restart(&self, instance: i64) -> Result<(), String> {
+        self.stop(instance).await?;
+
+        // the kill after the timeout marks it stopped at the latest
+        while self.status.arma(instance).await != State::Stopped {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+
+        self.start(instance).await
+    }
+}
+
+/// Starts the headless clients once the server had some time to come up,
+/// the returned list is filled as they are started so the server can stop them again.
+fn start_headless_clients(
+    status: Arc<StatusService>,
+    instance: i64,
+    headless_clients: Vec<arma::HeadlessClient>,
+) -> Arc<Mutex<Vec<ProcessControls>>> {
+    let clients = Arc::new(Mutex::new(Vec::new()));
+
+    if headless_clients.is_empty() {
+        return clients;
+    }
+
+    let started = clients.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(HEADLESS_CLIENT_DELAY).await;
+
+        for headless_client in headless_clients {
+            if status.arma(instance).await != State::Running {
+                return;
+            }
+
+            let index = headless_client.index();
+            let name = headless_client.name();
+
+            let c = match headless_client.run() {
+                Ok(c) => c,
+                Err(e) => {
+                    tracing::error!("Failed to start headless client {}: {}", name, e);
+                    continue;
+                }
+            };
+
+            started.lock().await.push(c.clone());
+
+            let h_status = status.clone();
+            tokio::spawn(async move {
+                h_status.set_headless_client(instance, index, State::Running).await;
+                c.wait().await;
+                h_status.set_headless_client(instance, index, State::Stopped).await;
+            });
+        }
+    });
+
+    clients
+}
+
+/// The checks walk the mods, scan their signatures and bind the ports, so they run off the async executor.
+pub(crate) async fn preflight(
+    server: &ServerInstance,
+    preset: &Preset,
+    missions: &[RotationMission],
+    managed_keys: &[String],
+) -> Result<PreflightReport, String> {
+    let arma = server.server().missions(missions.to_vec());
+    let preset = preset.clone();
+    let managed_keys = managed_keys.to_vec();
+
+    tokio::task::spawn_blocking(move || arma::preflight(&preset, &arma, &managed_keys))
+        .await
+        .map_err(|e| format!("Failed to run the preflight checks: {}", e))
+}
+
+/// Builds the server from the preset and mission rotation, shared between starting and previewing
+/// so the preview always reflects what would actually be executed.
+pub(crate) fn prepare_server(
+    server: &ServerInstance,
+    preset: &Preset,
+    missions: &[RotationMission],
+) -> Result<arma::Arma3, String> {
+    let mod_str = arma::get_mod_str(preset).map_err(|e| format!("{}", e))?;
+
+    Ok(server.server().missions(missions.to_vec()).mods(mod_str))
+}
+
+/// Runs the preflight checks and builds the server, nothing is written to disk when a check fails.
+async fn prepare_launch(
+    server: &ServerInstance,
+    preset: &Preset,
+    missions: &[RotationMission],
+    managed_keys: &[String],
+) -> Result<arma::Arma3, String> {
+    let report = preflight(server, preset, missions, managed_keys).await?;
+
+    if report.has_errors() {
+        let errors = report
+            .errors()
+            .map(|check| format!("{}: {}", check.name, check.message))
+            .collect::<Vec<_>>();
+
+        return Err(format!("Preflight failed, {}", errors.join("; ")));
+    }
+
+    prepare_server(server, preset, missions)
+}
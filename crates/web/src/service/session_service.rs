@@ -0,0 +1,131 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    repository::{NewSession, SessionRepository},
+    service::{InstanceService, ServerInstance, State, StatusService},
+};
+
+/// How often the players are checked, a session ends at most that much later than the player left.
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// The open session of a player.
+struct OpenSession {
+    id: i64,
+    mission: Option<String>,
+}
+
+/// Turns the player lists of the running servers into sessions.
+pub struct SessionService {
+    repository: SessionRepository,
+}
+
+impl SessionService {
+    pub fn new(repository: SessionRepository) -> Arc<Self> {
+        Arc::new(Self { repository })
+    }
+
+    /// Players are taken from A2S, RCon adds their GUIDs when it is reachable.
+    /// A session ends when the player leaves, the mission changes or the server stops.
+    pub fn start(self: &Arc<Self>, instances: Arc<InstanceService>, status: Arc<StatusService>) {
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            // open sessions by instance and player name
+            let mut open: HashMap<i64, HashMap<String, OpenSession>> = HashMap::new();
+
+            loop {
+                tokio::time::sleep(CHECK_INTERVAL).await;
+
+                for instance in instances.get_all().await {
+                    if status.arma(instance.id).await != State::Running {
+                        open.remove(&instance.id);
+                        continue;
+                    }
+
+                    let Some(server) = instances.get(instance.id).await else {
+                        continue;
+                    };
+
+                    let sessions = open.entry(instance.id).or_default();
+
+                    if let Err(e) = service.track(&server, sessions).await {
+                        tracing::error!("Failed to track the players of instance {}: {}", instance.id, e);
+                    }
+                }
+            }
+        });
+    }
+
+    async fn track(&self, server: &ServerInstance, sessions: &mut HashMap<String, OpenSession>) -> Result<(), String> {
+        // nothing is known about the players before the server answered once
+        let Some(info) = server.a2s.get_latest_info() else {
+            return Ok(());
+        };
+
+        let mission = Some(info.game).filter(|mission| !mission.is_empty());
+
+        let players = server
+            .a2s
+            .get_latest_players()
+            .into_iter()
+            .map(|player| player.name)
+            .filter(|name| !name.is_empty())
+            .collect::<HashSet<_>>();
+
+        let guids = match server.rcon.players().await {
+            Ok(players) => players
+                .into_iter()
+                .filter(|player| !player.guid.is_empty())
+                .map(|player| (player.name, player.guid))
+                .collect(),
+            Err(_) => HashMap::new(),
+        };
+
+        sessions.retain(|name, session| players.contains(name) && session.mission == mission);
+
+        let now = now();
+
+        for name in players {
+            let guid = guids.get(&name).map(String::as_str);
+
+            if let Some(session) = sessions.get(&name) {
+                self.repository
+                    .touch(session.id, guid, now)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                continue;
+            }
+
+            let session = NewSession {
+                instance_id: server.instance.id,
+                name: &name,
+                guid,
+                mission: mission.as_deref(),
+                joined_at: now,
+            };
+
+            let id = self.repository.open(session).await.map_err(|e| e.to_string())?;
+
+            sessions.insert(
+                name,
+                OpenSession {
+                    id,
+                    mission: mission.clone(),
+                },
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs() as i64)
+        .unwrap_or_default()
+}
@@ -3,6 +3,7 @@ use std::{convert::Infallible, sync::Arc};
 use axum::response::sse::Event;
 use tokio::sync::RwLock;
 
+use api_schema::response::Instance;
 pub use api_schema::response::State;
 pub use api_schema::response::Status;
 
@@ -13,19 +14,34 @@ pub struct StatusService {
 
 #[allow(unused)]
 impl StatusService {
-    pub fn new() -> Arc<Self> {
+    pub fn new(instances: &[Instance]) -> Arc<Self> {
         let tx = tokio::sync::watch::channel(Ok(Event::default())).0;
 
-        // use sysinfo to check if arma3server_x64.exe is running
-        // if it is, set arma to State::Running
+        // use sysinfo to check if arma3server_x64.exe is running for an instance
+        // if it is, set that instance to State::Running
 
         // use sysinfo to check if steamcmd.exe is running
         // if it is, set steam to State::Running
 
         let mut status = Status::default();
 
-        if arma::is_runnung() {
-            status.arma = State::Running;
+        for instance in instances {
+            let profiles = paths::get_instance_path(&instance.name);
+
+            if arma::is_running(&profiles) {
+                status.instances.insert(instance.id, State::Running);
+            }
+
+            let headless_clients = (1..=instance.headless_clients as usize)
+                .map(
+                    |index| match arma::is_running(&arma::headless_client_profiles(&profiles, index)) {
+                        true => State::Running,
+                        false => State::Stopped,
+                    },
+                )
+                .collect();
+
+            status.headless_clients.insert(instance.id, headless_clients);
         }
 
         if steam::is_runnung() {
@@ -48,9 +64,14 @@ impl StatusService {
         last_status.steamcmd.clone()
     }
 
-    pub async fn arma(&self) -> State {
+    pub async fn arma(&self, instance: i64) -> State {
+        let last_status = self.last_status.read().await;
+        last_status.arma(instance)
+    }
+
+    pub async fn headless_client(&self, instance: i64, index: usize) -> State {
         let last_status = self.last_status.read().await;
-        last_status.arma.clone()
+        last_status.headless_client(instance, index)
     }
 
     pub fn subscribe(&self) -> tokio::sync::watch::Receiver<Result<Event, Infallible>> {
@@ -65,9 +86,32 @@ impl StatusService {
         let _ = self.tx.send(event);
     }
 
-    pub async fn set_arma(&self, state: State) {
+    pub async fn set_arma(&self, instance: i64, state: State) {
+        let mut last_status = self.last_status.write().await;
+        last_status.instances.insert(instance, state);
+
+        let event: Result<Event, Infallible> = Ok(Event::default().json_data(&*last_status).unwrap());
+        let _ = self.tx.send(event);
+    }
+
+    pub async fn set_headless_client(&self, instance: i64, index: usize, state: State) {
+        let mut last_status = self.last_status.write().await;
+        let states = last_status.headless_clients.entry(instance).or_default();
+
+        if states.len() < index {
+            states.resize(index, State::Stopped);
+        }
+
+        states[index - 1] = state;
+
+        let event: Result<Event, Infallible> = Ok(Event::default().json_data(&*last_status).unwrap());
+        let _ = self.tx.send(event);
+    }
+
+    pub async fn remove_arma(&self, instance: i64) {
         let mut last_status = self.last_status.write().await;
-        last_status.arma = state;
+        last_status.instances.remove(&instance);
+        last_status.headless_clients.remove(&instance);
 
         let event: Result<Event, Infallible> = Ok(Event::default().json_data(&*last_status).unwrap());
         let _ = self.tx.send(event);
@@ -0,0 +1,156 @@
+use std::{collections::HashSet, sync::Arc};
+
+use api_schema::response::{MessageKind, ServerMessage, WhitelistKind};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    repository::{AuditRepository, NewAuditEntry, WhitelistRepository},
+    service::{InstanceService, MessageService},
+};
+
+/// Kicks players that aren't on the whitelist as soon as RCon reports their GUID.
+pub struct WhitelistService {
+    repository: WhitelistRepository,
+    audit_repository: AuditRepository,
+}
+
+impl WhitelistService {
+    pub fn new(repository: WhitelistRepository, audit_repository: AuditRepository) -> Arc<Self> {
+        Arc::new(Self {
+            repository,
+            audit_repository,
+        })
+    }
+
+    /// Follows the messages of all servers, the settings are looked up on every connect so changes apply right away.
+    pub fn start(self: &Arc<Self>, instances: Arc<InstanceService>, messages: &MessageService) {
+        let service = self.clone();
+        let mut messages = messages.subscribe();
+
+        tokio::spawn(async move {
+            // the slots already kicked, a player connecting is reported twice
+            let mut kicked = HashSet::new();
+
+            loop {
+                match messages.recv().await {
+                    Ok(message) => service.check(&instances, &mut kicked, message).await,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("The whitelist missed {} messages", skipped);
+                    }
+                    Err(RecvError::Closed) => return,
+                }
+            }
+        });
+    }
+
+    async fn check(&self, instances: &InstanceService, kicked: &mut HashSet<(i64, i64)>, message: ServerMessage) {
+        let Some(slot) = message.slot else {
+            return;
+        };
+
+        let key = (message.instance_id, slot);
+
+        match message.kind {
+            MessageKind::Disconnect => {
+                kicked.remove(&key);
+            }
+            MessageKind::Connect if !kicked.contains(&key) => {
+                let Some(guid) = message.guid.as_deref() else {
+                    return;
+                };
+
+                let kick_message = match self.enforce(message.instance_id, guid).await {
+                    Ok(Some(kick_message)) => kick_message,
+                    Ok(None) => return,
+                    Err(e) => {
+                        tracing::error!("Failed to check the whitelist: {}", e);
+                        return;
+                    }
+                };
+
+                let Some(server) = instances.get(message.instance_id).await else {
+                    return;
+                };
+
+                if let Err(e) = server.rcon.command(&format!("kick {} {}", slot, kick_message)).await {
+                    tracing::warn!("Failed to kick a player that isn't on the whitelist: {}", e);
+                    return;
+                }
+
+                kicked.insert(key);
+
+                let entry = NewAuditEntry {
+                    instance_id: message.instance_id,
+                    user: "Whitelist",
+                    action: "kick",
+                    player: message.player.as_deref(),
+                    guid: Some(guid),
+                    details: Some(kick_message),
+                };
+
+                if let Err(e) = self.audit_repository.add(entry).await.map_err(|e| e.to_string()) {
+                    tracing::error!("Failed to add a whitelist kick to the audit log: {}", e);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The kick message if the instance enforces the whitelist and the player isn't on it.
+    async fn enforce(&self, instance_id: i64, guid: &str) -> Result<Option<String>, String> {
+        let settings = self
+            .repository
+            .get_settings(instance_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let Some(settings) = settings.filter(|settings| settings.enabled || settings.preset_enabled) else {
+            return Ok(None);
+        };
+
+        let whitelisted = self.repository.contains(guid).await.map_err(|e| e.to_string())?;
+
+        Ok((!whitelisted).then_some(settings.message))
+    }
+}
+
+/// The entries of a CSV, each line needs a Steam UID or GUID and the next column that isn't one is the name.
+/// Returns the entries with their names and how many lines had neither, like a header.
+pub fn parse_whitelist(content: &str) -> (Vec<(WhitelistKind, String, String)>, usize) {
+    let mut entries = Vec::new();
+    let mut skipped = 0;
+
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let columns = line
+            .split([',', ';', '\t'])
+            .map(|column| column.trim().trim_matches('"').trim())
+            .collect::<Vec<_>>();
+
+        let id = columns.iter().find_map(|column| {
+            let value = column.to_lowercase();
+
+            if arma::is_uid(&value) {
+                Some((WhitelistKind::Uid, value))
+            } else if arma::is_guid(&value) {
+                Some((WhitelistKind::Guid, value))
+            } else {
+                None
+            }
+        });
+
+        let Some((kind, value)) = id else {
+            skipped += 1;
+            continue;
+        };
+
+        let name = columns
+            .iter()
+            .find(|column| !column.is_empty() && column.to_lowercase() != value)
+            .map(|column| column.to_string())
+            .unwrap_or_default();
+
+        entries.push((kind, value, name));
+    }
+
+    (entries, skipped)
+}
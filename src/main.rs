@@ -12,9 +12,6 @@ async fn main() -> anyhow::Result<()> {
         arma_manager_web::start().await;
     });
 
-    arma::prepare_config().unwrap();
-    arma::prepare_profile().unwrap();
-
     steam::Steam::install().await.unwrap();
 
     // wait for ctrl-c